@@ -117,6 +117,32 @@ function updateTannerGraph(targetSvgId, typeFilter) {
     const qubitXStep = nQubits > 1 ? (width - 2 * margin) / (nQubits - 1) : 0;
     const stabXStep = nStabs > 1 ? (width - 2 * margin) / (nStabs - 1) : 0;
 
+    // Positions from the code's layout when it has one; stabilizers
+    // without an ancilla (logical rows of the tableau) stay on the bottom row
+    const layout = JSON.parse(window.qecModule.getCodeLayout(window.currentCode));
+    const hasUnplaced = layout && relevantStabs.some(s => !layout.ancilla_qubits[s.originalIdx]);
+    const positions = layoutPositions(layout, width, height, margin, hasUnplaced ? 60 : 0);
+    const qubitPos = (i) => positions
+        ? positions.data[i]
+        : { x: margin + (nQubits > 1 ? i * qubitXStep : width / 2), y: qubitY };
+    const stabPos = (localIdx, originalIdx) => positions && positions.ancilla[originalIdx]
+        ? positions.ancilla[originalIdx]
+        : { x: margin + (nStabs > 1 ? localIdx * stabXStep : width / 2), y: stabY };
+
+    // Plaquette outlines behind the graph
+    if (positions) {
+        positions.plaquettes
+            .filter(p => p.pauli === typeFilter || p.pauli === 'Y' || p.pauli === 'mixed')
+            .forEach(p => {
+                const polygon = document.createElementNS("http://www.w3.org/2000/svg", "polygon");
+                polygon.setAttribute("points", p.vertices.map(v => `${v.x},${v.y}`).join(" "));
+                polygon.setAttribute("fill", typeFilter === 'X' ? "#ef5350" : "#42a5f5");
+                polygon.setAttribute("fill-opacity", triggered.includes(p.stabilizer) ? "0.35" : "0.12");
+                polygon.setAttribute("class", "tanner-plaquette");
+                svg.appendChild(polygon);
+            });
+    }
+
     // 1. Get applied errors
     const appliedErrors = JSON.parse(window.simulator.getAppliedErrors());
     const erroredQubitIndices = appliedErrors.map(e => e.qubit);
@@ -133,10 +159,8 @@ function updateTannerGraph(targetSvgId, typeFilter) {
                 (typeFilter === 'Z' && (pauli === 'Z' || pauli === 'Y'));
 
             if (pauli && pauli !== 'I' && isRelevantEdge) {
-                const x1 = margin + (nQubits > 1 ? qIdx * qubitXStep : width / 2);
-                const y1 = qubitY;
-                const x2 = margin + (nStabs > 1 ? sLocalIdx * stabXStep : width / 2);
-                const y2 = stabY;
+                const { x: x1, y: y1 } = qubitPos(qIdx);
+                const { x: x2, y: y2 } = stabPos(sLocalIdx, stabInfo.originalIdx);
 
                 const line = document.createElementNS("http://www.w3.org/2000/svg", "line");
                 line.setAttribute("x1", x1);
@@ -184,8 +208,7 @@ function updateTannerGraph(targetSvgId, typeFilter) {
 
     // 3. Draw Qubit Nodes
     for (let i = 0; i < nQubits; i++) {
-        const x = margin + (nQubits > 1 ? i * qubitXStep : width / 2);
-        const y = qubitY;
+        const { x, y } = qubitPos(i);
 
         const qubitError = appliedErrors.find(e => e.qubit === i);
         const errorType = qubitError ? qubitError.error_type : null;
@@ -223,8 +246,7 @@ function updateTannerGraph(targetSvgId, typeFilter) {
 
     // 4. Draw Stabilizer Nodes
     relevantStabs.forEach((stabInfo, i) => {
-        const x = margin + (nStabs > 1 ? i * stabXStep : width / 2);
-        const y = stabY;
+        const { x, y } = stabPos(i, stabInfo.originalIdx);
         const isTriggered = stabInfo.isTriggered;
 
         const g = document.createElementNS("http://www.w3.org/2000/svg", "g");
@@ -271,6 +293,33 @@ function updateTannerGraph(targetSvgId, typeFilter) {
     }
 }
 
+// Map layout coordinates from the Rust side into the SVG box, keeping the
+// aspect ratio and leaving `reserve` pixels free at the bottom
+function layoutPositions(layout, width, height, margin, reserve) {
+    if (!layout || layout.data_qubits.length === 0) return null;
+
+    const points = layout.data_qubits.concat(layout.ancilla_qubits);
+    const xs = points.map(p => p.x);
+    const ys = points.map(p => p.y);
+    const minX = Math.min(...xs), maxX = Math.max(...xs);
+    const minY = Math.min(...ys), maxY = Math.max(...ys);
+    const boxWidth = width - 2 * margin;
+    const boxHeight = height - 2 * margin - reserve;
+    const scale = Math.min(
+        boxWidth / Math.max(maxX - minX, 1e-9),
+        boxHeight / Math.max(maxY - minY, 1e-9)
+    );
+    const offsetX = margin + (boxWidth - scale * (maxX - minX)) / 2;
+    const offsetY = margin + (boxHeight - scale * (maxY - minY)) / 2;
+    const map = p => ({ x: offsetX + (p.x - minX) * scale, y: offsetY + (p.y - minY) * scale });
+
+    return {
+        data: layout.data_qubits.map(map),
+        ancilla: layout.ancilla_qubits.map(map),
+        plaquettes: layout.plaquettes.map(p => ({ ...p, vertices: p.vertices.map(map) }))
+    };
+}
+
 // Ensure resize handling
 window.addEventListener('resize', () => {
     if (window.simulator) {
@@ -527,17 +576,50 @@ function updateCodeInfo() {
     if (!infoJson) return;
 
     const info = JSON.parse(infoJson);
+    const distanceDetail = info.x_distance != null && info.z_distance != null
+        ? ` (X: ${info.x_distance}, Z: ${info.z_distance})`
+        : '';
 
     let html = `
         <div class="code-info-grid">
             <div class="info-item"><strong>Name:</strong> ${info.name}</div>
             <div class="info-item"><strong>Qubits:</strong> ${info.n_qubits}</div>
             <div class="info-item"><strong>Logical:</strong> ${info.n_logical}</div>
-            <div class="info-item"><strong>Distance:</strong> ${info.distance}</div>
+            <div class="info-item"><strong>Distance:</strong> ${info.distance}${distanceDetail}</div>
         </div>
         <div class="info-description">${info.description}</div>
     `;
 
+    const gatesJson = window.qecModule.getLogicalGates(window.currentCode);
+    const gates = gatesJson ? JSON.parse(gatesJson) : null;
+    if (gates && gates.length > 0) {
+        html += `<div class="info-item"><strong>Logical gates:</strong> ${gates.join(", ")}</div>`;
+    }
+
+    const logicalsJson = window.qecModule.getLogicalOperators(window.currentCode);
+    const logicals = logicalsJson ? JSON.parse(logicalsJson) : null;
+    if (logicals) {
+        html += '<div class="logical-operators">';
+        logicals.x.forEach((lx, i) => {
+            html += `<div class="info-item"><strong>X̄<sub>${i}</sub>:</strong> <code>${lx}</code>`;
+            html += ` <strong>Z̄<sub>${i}</sub>:</strong> <code>${logicals.z[i]}</code></div>`;
+        });
+        html += '</div>';
+    }
+
+    // Circuits taking |0...0⟩ to the encoded |0̄⟩ and |+̄⟩
+    const circuits = [["zero", "|0̄⟩"], ["plus", "|+̄⟩"]]
+        .map(([kind, label]) => [label, window.qecModule.getEncodingCircuit(window.currentCode, kind)])
+        .filter(([, text]) => text && text !== "null");
+    if (circuits.length > 0) {
+        html += '<div class="encoding-circuits">';
+        circuits.forEach(([label, text]) => {
+            const body = text.trim() || "(no gates: |0...0⟩ is already encoded)";
+            html += `<details><summary>Prepare ${label}</summary><pre>${body}</pre></details>`;
+        });
+        html += '</div>';
+    }
+
     container.innerHTML = html;
 }
 
@@ -0,0 +1,723 @@
+#![allow(dead_code)]
+// Code equivalence and automorphisms under qubit permutations and local
+// Cliffords
+//
+// A map sends qubit a to qubit `permutation[a]` and conjugates it by a
+// single-qubit Clifford. Signs are ignored throughout: two codes are
+// equivalent if one stabilizer group maps onto the other up to signs, and
+// logical actions are given up to Pauli corrections.
+//
+// The search assigns qubits one at a time. A qubit can only go to a qubit
+// whose Paulis appear with the same weight distributions in the stabilizer
+// group, and after each step the stabilizers supported on the assigned
+// qubits must already map into the other group.
+use crate::codes::StabilizerCode;
+use crate::pauli::{Pauli, PauliString};
+use crate::symplectic;
+use crate::validation::CodeError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Largest stabilizer group, as log2 of its size, that is enumerated
+const MAX_GROUP_LOG2: usize = 16;
+
+/// Search nodes visited before giving up
+const MAX_SEARCH_NODES: usize = 1_000_000;
+
+/// Automorphisms examined per implementation class by `logical_gates`
+const MAX_AUTOMORPHISMS: usize = 5_000;
+
+/// Single-qubit Clifford up to Paulis, given by the images of X and Z
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalClifford {
+    pub x: Pauli,
+    pub z: Pauli,
+}
+
+impl LocalClifford {
+    pub const IDENTITY: LocalClifford = LocalClifford {
+        x: Pauli::X,
+        z: Pauli::Z,
+    };
+
+    /// The six permutations of {X, Y, Z}
+    pub const ALL: [LocalClifford; 6] = [
+        LocalClifford::IDENTITY,
+        LocalClifford {
+            x: Pauli::Z,
+            z: Pauli::X,
+        },
+        LocalClifford {
+            x: Pauli::Y,
+            z: Pauli::Z,
+        },
+        LocalClifford {
+            x: Pauli::X,
+            z: Pauli::Y,
+        },
+        LocalClifford {
+            x: Pauli::Y,
+            z: Pauli::X,
+        },
+        LocalClifford {
+            x: Pauli::Z,
+            z: Pauli::Y,
+        },
+    ];
+
+    pub fn apply(self, p: Pauli) -> Pauli {
+        match p {
+            Pauli::I => Pauli::I,
+            Pauli::X => self.x,
+            Pauli::Z => self.z,
+            Pauli::Y => self.x.multiply(self.z).1,
+        }
+    }
+
+    /// Gate name: I, H, S, SQRT_X, C_XYZ or C_ZYX
+    pub fn name(self) -> &'static str {
+        clifford_name((self.x, self.z))
+    }
+}
+
+fn clifford_name(images: (Pauli, Pauli)) -> &'static str {
+    match images {
+        (Pauli::X, Pauli::Z) => "I",
+        (Pauli::Z, Pauli::X) => "H",
+        (Pauli::Y, Pauli::Z) => "S",
+        (Pauli::X, Pauli::Y) => "SQRT_X",
+        (Pauli::Y, Pauli::X) => "C_XYZ",
+        (Pauli::Z, Pauli::Y) => "C_ZYX",
+        _ => "?",
+    }
+}
+
+/// Qubit permutation followed by local Cliffords
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeMap {
+    /// Qubit a goes to `permutation[a]`
+    pub permutation: Vec<usize>,
+    /// Clifford applied to qubit a
+    pub local: Vec<LocalClifford>,
+}
+
+/// How an automorphism is carried out on hardware
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Implementation {
+    /// Single-qubit gates only
+    Transversal,
+    /// Single-qubit gates and one layer of SWAPs: the permutation is an
+    /// involution, a fold of the code onto itself
+    FoldTransversal,
+    /// Single-qubit gates and an arbitrary relabeling of qubits
+    PermutationTransversal,
+}
+
+impl fmt::Display for Implementation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Implementation::Transversal => write!(f, "transversal"),
+            Implementation::FoldTransversal => write!(f, "fold-transversal"),
+            Implementation::PermutationTransversal => write!(f, "permutation-transversal"),
+        }
+    }
+}
+
+impl CodeMap {
+    pub fn identity(n_qubits: usize) -> Self {
+        CodeMap {
+            permutation: (0..n_qubits).collect(),
+            local: vec![LocalClifford::IDENTITY; n_qubits],
+        }
+    }
+
+    pub fn apply(&self, op: &PauliString) -> PauliString {
+        let mut image = PauliString::identity(op.n_qubits());
+        for (a, &p) in op.paulis.iter().enumerate() {
+            image.paulis[self.permutation[a]] = self.local[a].apply(p);
+        }
+        image
+    }
+
+    pub fn implementation(&self) -> Implementation {
+        let p = &self.permutation;
+        if p.iter().enumerate().all(|(a, &b)| a == b) {
+            Implementation::Transversal
+        } else if p.iter().all(|&b| p[b] < p.len() && p[p[b]] == b) {
+            Implementation::FoldTransversal
+        } else {
+            Implementation::PermutationTransversal
+        }
+    }
+}
+
+/// Permutations a search may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermutationRule {
+    Identity,
+    Involution,
+    Any,
+}
+
+/// Stabilizer group with the data the search needs
+struct Group {
+    n: usize,
+    /// Independent generators, packed as (x, z) bit masks
+    generators: Vec<(u64, u64)>,
+    elements: HashSet<(u64, u64)>,
+    /// Number of elements of each weight with X, Y or Z on each qubit
+    histograms: Vec<[Vec<u32>; 3]>,
+}
+
+fn pack(op: &PauliString) -> (u64, u64) {
+    let (mut x, mut z) = (0u64, 0u64);
+    for (q, p) in op.paulis.iter().enumerate() {
+        if matches!(p, Pauli::X | Pauli::Y) {
+            x |= 1 << q;
+        }
+        if matches!(p, Pauli::Z | Pauli::Y) {
+            z |= 1 << q;
+        }
+    }
+    (x, z)
+}
+
+fn pauli_at((x, z): (u64, u64), q: usize) -> Pauli {
+    match (x >> q & 1 == 1, z >> q & 1 == 1) {
+        (false, false) => Pauli::I,
+        (true, false) => Pauli::X,
+        (true, true) => Pauli::Y,
+        (false, true) => Pauli::Z,
+    }
+}
+
+fn pauli_index(p: Pauli) -> usize {
+    match p {
+        Pauli::X => 0,
+        Pauli::Y => 1,
+        Pauli::Z => 2,
+        Pauli::I => unreachable!(),
+    }
+}
+
+impl Group {
+    fn new(code: &StabilizerCode) -> Result<Self, CodeError> {
+        let n = code.n_qubits;
+        let generators = symplectic::independent_subset(&code.generators);
+        if n > 64 || generators.len() > MAX_GROUP_LOG2 {
+            return Err(CodeError::invalid_parameter(
+                format!("[[{}, {}]]", n, code.n_logical()),
+                "generators",
+                format!(
+                    "automorphism search needs at most 64 qubits and 2^{} stabilizers",
+                    MAX_GROUP_LOG2
+                ),
+            ));
+        }
+        let packed: Vec<(u64, u64)> = generators.iter().map(pack).collect();
+        let mut elements = HashSet::with_capacity(1 << packed.len());
+        let mut histograms = vec![[vec![0u32; n + 1], vec![0u32; n + 1], vec![0u32; n + 1]]; n];
+        let mut current = (0u64, 0u64);
+        elements.insert(current);
+        // Gray code walk: each step multiplies in one generator
+        for step in 1u64..1 << packed.len() {
+            let (gx, gz) = packed[step.trailing_zeros() as usize];
+            current = (current.0 ^ gx, current.1 ^ gz);
+            elements.insert(current);
+            let support = current.0 | current.1;
+            let weight = support.count_ones() as usize;
+            for (q, histogram) in histograms.iter_mut().enumerate() {
+                if support >> q & 1 == 1 {
+                    histogram[pauli_index(pauli_at(current, q))][weight] += 1;
+                }
+            }
+        }
+        Ok(Group {
+            n,
+            generators: packed,
+            elements,
+            histograms,
+        })
+    }
+
+    /// Qubit order for the search: qubits of light stabilizers first, so
+    /// that whole stabilizers are assigned early and prune the search
+    fn search_order(&self) -> Vec<usize> {
+        let mut light: Vec<u64> = self
+            .elements
+            .iter()
+            .map(|&(x, z)| x | z)
+            .filter(|&s| s != 0)
+            .collect();
+        light.sort_by_key(|s| (s.count_ones(), *s));
+        light.truncate(8 * self.n);
+
+        let mut order = Vec::with_capacity(self.n);
+        let mut covered = 0u64;
+        while let Some(&next) = light
+            .iter()
+            .filter(|&&s| s & !covered != 0)
+            .min_by_key(|&&s| ((s & !covered).count_ones(), s.count_ones()))
+        {
+            order.extend((0..self.n).filter(|&q| (next & !covered) >> q & 1 == 1));
+            covered |= next;
+        }
+        order.extend((0..self.n).filter(|&q| covered >> q & 1 == 0));
+        order
+    }
+
+    /// For each prefix of `order`, a basis of the stabilizers supported on it
+    fn prefix_subgroups(generators: &[PauliString], order: &[usize]) -> Vec<Vec<(u64, u64)>> {
+        let n = order.len();
+        let rows: Vec<Vec<bool>> = generators.iter().map(symplectic::to_bits).collect();
+        (0..n)
+            .map(|i| {
+                // Combinations of generators vanishing outside order[..=i]
+                let outside: Vec<Vec<bool>> = order[i + 1..]
+                    .iter()
+                    .flat_map(|&q| [q, n + q])
+                    .map(|column| rows.iter().map(|row| row[column]).collect())
+                    .collect();
+                symplectic::null_space(&outside, rows.len())
+                    .into_iter()
+                    .map(|combination| {
+                        let mut element = (0u64, 0u64);
+                        for (g, _) in combination.iter().enumerate().filter(|(_, &c)| c) {
+                            let (x, z) = pack(&generators[g]);
+                            element = (element.0 ^ x, element.1 ^ z);
+                        }
+                        element
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Backtracking search for maps from one stabilizer group onto another
+struct Search<'a> {
+    source: &'a Group,
+    target: &'a Group,
+    order: Vec<usize>,
+    prefixes: Vec<Vec<(u64, u64)>>,
+    rule: PermutationRule,
+    limit: usize,
+    nodes: usize,
+    map: Vec<Option<(usize, LocalClifford)>>,
+    preimage: Vec<Option<usize>>,
+    found: Vec<CodeMap>,
+}
+
+impl Search<'_> {
+    /// Whether the search finished without hitting the node budget
+    fn run(&mut self) -> bool {
+        self.extend(0)
+    }
+
+    fn image(&self, (x, z): (u64, u64)) -> (u64, u64) {
+        let mut image = (0u64, 0u64);
+        for a in 0..self.source.n {
+            let (has_x, has_z) = (x >> a & 1 == 1, z >> a & 1 == 1);
+            if !has_x && !has_z {
+                continue;
+            }
+            let (b, clifford) = self.map[a].expect("element outside assigned qubits");
+            // Images of X and Z multiply, up to phase, into the image of Y
+            for (present, p) in [(has_x, clifford.x), (has_z, clifford.z)] {
+                if present {
+                    if matches!(p, Pauli::X | Pauli::Y) {
+                        image.0 ^= 1 << b;
+                    }
+                    if matches!(p, Pauli::Z | Pauli::Y) {
+                        image.1 ^= 1 << b;
+                    }
+                }
+            }
+        }
+        image
+    }
+
+    /// Whether the stabilizers restricted to the assigned qubits map into
+    /// the target stabilizers restricted to their images
+    fn projections_match(&self, depth: usize) -> bool {
+        let mask = |qubits: &mut dyn Iterator<Item = usize>| qubits.fold(0u64, |m, q| m | 1 << q);
+        let source_mask = mask(&mut self.order[..=depth].iter().copied());
+        let target_mask = mask(&mut self.order[..=depth].iter().map(|&a| self.map[a].unwrap().0));
+        let mut span = Vec::new();
+        for &(x, z) in &self.target.generators {
+            insert_reduced(&mut span, (x & target_mask, z & target_mask));
+        }
+        self.source.generators.iter().all(|&(x, z)| {
+            let image = self.image((x & source_mask, z & source_mask));
+            insert_reduced(&mut span.clone(), image).is_none()
+        })
+    }
+
+    fn extend(&mut self, depth: usize) -> bool {
+        if self.found.len() >= self.limit {
+            return true;
+        }
+        if depth == self.order.len() {
+            self.found.push(CodeMap {
+                permutation: self.map.iter().map(|m| m.unwrap().0).collect(),
+                local: self.map.iter().map(|m| m.unwrap().1).collect(),
+            });
+            return true;
+        }
+        self.nodes += 1;
+        if self.nodes > MAX_SEARCH_NODES {
+            return false;
+        }
+        let a = self.order[depth];
+        for b in 0..self.target.n {
+            if self.preimage[b].is_some() {
+                continue;
+            }
+            let allowed = match self.rule {
+                PermutationRule::Any => true,
+                PermutationRule::Identity => a == b,
+                PermutationRule::Involution => {
+                    self.map[b].is_none_or(|(image, _)| image == a)
+                        && self.preimage[a].is_none_or(|c| c == b)
+                }
+            };
+            if !allowed {
+                continue;
+            }
+            for clifford in LocalClifford::ALL {
+                let consistent = [Pauli::X, Pauli::Y, Pauli::Z].iter().all(|&p| {
+                    self.source.histograms[a][pauli_index(p)]
+                        == self.target.histograms[b][pauli_index(clifford.apply(p))]
+                });
+                if !consistent {
+                    continue;
+                }
+                self.map[a] = Some((b, clifford));
+                self.preimage[b] = Some(a);
+                let closed = self.prefixes[depth]
+                    .iter()
+                    .all(|&s| self.target.elements.contains(&self.image(s)))
+                    && self.projections_match(depth);
+                let complete = !closed || self.extend(depth + 1);
+                self.map[a] = None;
+                self.preimage[b] = None;
+                if !complete {
+                    return false;
+                }
+                if self.found.len() >= self.limit {
+                    return true;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Reduce `v` against an echelon basis and add it if independent, returning
+/// the new row; rows are kept sorted by decreasing leading bit
+fn insert_reduced(basis: &mut Vec<u128>, (x, z): (u64, u64)) -> Option<u128> {
+    let mut v = (x as u128) << 64 | z as u128;
+    for &row in basis.iter() {
+        v = v.min(v ^ row);
+    }
+    if v == 0 {
+        return None;
+    }
+    let at = basis.partition_point(|&row| row > v);
+    basis.insert(at, v);
+    Some(v)
+}
+
+/// Up to `limit` maps from `source` onto `target`
+fn search_maps(
+    source: &StabilizerCode,
+    target: &StabilizerCode,
+    rule: PermutationRule,
+    limit: usize,
+) -> Result<(Vec<CodeMap>, bool), CodeError> {
+    let source_group = Group::new(source)?;
+    let target_group = Group::new(target)?;
+    if source_group.n != target_group.n
+        || source_group.elements.len() != target_group.elements.len()
+    {
+        return Ok((Vec::new(), true));
+    }
+    let order = source_group.search_order();
+    let generators = symplectic::independent_subset(&source.generators);
+    let n = source_group.n;
+    let mut search = Search {
+        source: &source_group,
+        target: &target_group,
+        prefixes: Group::prefix_subgroups(&generators, &order),
+        order,
+        rule,
+        limit,
+        nodes: 0,
+        map: vec![None; n],
+        preimage: vec![None; n],
+        found: Vec::new(),
+    };
+    let complete = search.run();
+    Ok((search.found, complete))
+}
+
+/// A map taking the stabilizer group of `a` onto that of `b`, or None if
+/// the codes are not equivalent under qubit permutations and local Cliffords
+/// Fails if the groups are too large or the search exceeds its budget.
+pub fn find_equivalence(
+    a: &StabilizerCode,
+    b: &StabilizerCode,
+) -> Result<Option<CodeMap>, CodeError> {
+    let (mut found, complete) = search_maps(a, b, PermutationRule::Any, 1)?;
+    if found.is_empty() && !complete {
+        return Err(CodeError::invalid_parameter(
+            format!("[[{}, {}]]", a.n_qubits, a.n_logical()),
+            "equivalence",
+            "search budget exhausted",
+        ));
+    }
+    Ok(found.pop())
+}
+
+/// Automorphisms of a code's stabilizer group allowed by `rule`, at most
+/// `limit` of them, and whether the list is complete
+pub fn automorphisms(
+    code: &StabilizerCode,
+    rule: PermutationRule,
+    limit: usize,
+) -> Result<(Vec<CodeMap>, bool), CodeError> {
+    let (found, complete) = search_maps(code, code, rule, limit)?;
+    let complete = complete && found.len() < limit;
+    Ok((found, complete))
+}
+
+/// Action of an automorphism on the logical operators, up to Paulis
+/// Row i is the image of logical i in the basis X̄_0..X̄_{k-1}, Z̄_0..Z̄_{k-1}.
+pub fn logical_action(code: &StabilizerCode, map: &CodeMap) -> Vec<Vec<bool>> {
+    let k = code.logical_x.len();
+    code.logical_x
+        .iter()
+        .chain(&code.logical_z)
+        .map(|l| {
+            let image = map.apply(l);
+            let x = code.logical_z.iter().map(|z| !image.commutes_with(z));
+            let z = code.logical_x.iter().map(|x| !image.commutes_with(x));
+            let row: Vec<bool> = x.chain(z).collect();
+            debug_assert_eq!(row.len(), 2 * k);
+            row
+        })
+        .collect()
+}
+
+/// Name of a logical action: a Clifford name for one logical qubit, the
+/// images of the logical Paulis otherwise
+pub fn action_name(action: &[Vec<bool>]) -> String {
+    let k = action.len() / 2;
+    let pauli = |x: bool, z: bool| match (x, z) {
+        (true, false) => Pauli::X,
+        (true, true) => Pauli::Y,
+        (false, true) => Pauli::Z,
+        (false, false) => Pauli::I,
+    };
+    if k == 1 {
+        let images = (
+            pauli(action[0][0], action[0][1]),
+            pauli(action[1][0], action[1][1]),
+        );
+        return clifford_name(images).to_string();
+    }
+    let image = |row: &[bool]| -> String {
+        (0..k)
+            .filter_map(|j| {
+                let p = pauli(row[j], row[k + j]);
+                (p != Pauli::I).then(|| format!("{}{}", p.to_char(), j))
+            })
+            .collect::<Vec<_>>()
+            .join("*")
+    };
+    let images: Vec<String> = (0..k)
+        .flat_map(|i| {
+            [
+                format!("X{}->{}", i, image(&action[i])),
+                format!("Z{}->{}", i, image(&action[k + i])),
+            ]
+        })
+        .collect();
+    format!("[{}]", images.join(", "))
+}
+
+/// Logical gate implemented by an automorphism
+#[derive(Debug, Clone)]
+pub struct LogicalGate {
+    pub name: String,
+    pub implementation: Implementation,
+    pub map: CodeMap,
+}
+
+impl fmt::Display for LogicalGate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.implementation, self.name)
+    }
+}
+
+/// Distinct nontrivial logical actions of the code's automorphisms, each
+/// with its simplest implementation
+/// Transversal automorphisms are searched first, then folds, then general
+/// permutations; each class is cut off after `MAX_AUTOMORPHISMS` maps.
+pub fn logical_gates(code: &StabilizerCode) -> Result<Vec<LogicalGate>, CodeError> {
+    let k = code.logical_x.len();
+    let identity: Vec<Vec<bool>> = (0..2 * k)
+        .map(|i| (0..2 * k).map(|j| i == j).collect())
+        .collect();
+    let mut seen = vec![identity];
+    let mut gates = Vec::new();
+    for rule in [
+        PermutationRule::Identity,
+        PermutationRule::Involution,
+        PermutationRule::Any,
+    ] {
+        let (maps, _) = search_maps(code, code, rule, MAX_AUTOMORPHISMS)?;
+        for map in maps {
+            let action = logical_action(code, &map);
+            if seen.contains(&action) {
+                continue;
+            }
+            gates.push(LogicalGate {
+                name: action_name(&action),
+                implementation: map.implementation(),
+                map,
+            });
+            seen.push(action);
+        }
+    }
+    Ok(gates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{get_stabilizer_code, repetition_code};
+
+    fn gate_names(code: &str) -> Vec<String> {
+        let code = get_stabilizer_code(code).unwrap();
+        logical_gates(&code)
+            .unwrap()
+            .iter()
+            .map(|g| g.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_equivalence_under_permutation_and_local_cliffords() {
+        let code = get_stabilizer_code("five_qubit").unwrap();
+        let scramble = CodeMap {
+            permutation: vec![3, 0, 4, 1, 2],
+            local: vec![
+                LocalClifford::ALL[1],
+                LocalClifford::ALL[4],
+                LocalClifford::ALL[0],
+                LocalClifford::ALL[2],
+                LocalClifford::ALL[5],
+            ],
+        };
+        let generators: Vec<PauliString> =
+            code.generators.iter().map(|g| scramble.apply(g)).collect();
+        let scrambled = StabilizerCode::from_generators(5, generators).unwrap();
+        let map = find_equivalence(&code, &scrambled).unwrap().unwrap();
+        for g in &code.generators {
+            assert!(symplectic::is_in_span(&scrambled.generators, &map.apply(g)));
+        }
+
+        // Bit-flip and phase-flip repetition codes differ by transversal H
+        let bit_flip = repetition_code(3).unwrap();
+        let phase_flip = StabilizerCode::from_generators(
+            3,
+            bit_flip
+                .generators
+                .iter()
+                .map(|g| {
+                    CodeMap {
+                        permutation: vec![0, 1, 2],
+                        local: vec![LocalClifford::ALL[1]; 3],
+                    }
+                    .apply(g)
+                })
+                .collect(),
+        )
+        .unwrap();
+        let map = find_equivalence(&bit_flip, &phase_flip).unwrap().unwrap();
+        assert!(map.local.iter().all(|c| c.name() == "H"));
+
+        let steane = get_stabilizer_code("steane").unwrap();
+        let surface = get_stabilizer_code("surface_d3").unwrap();
+        assert!(find_equivalence(&steane, &surface).unwrap().is_none());
+        let five = repetition_code(5).unwrap();
+        assert!(find_equivalence(&code, &five).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_steane_automorphisms() {
+        let steane = get_stabilizer_code("steane").unwrap();
+        // The Hamming code's permutation group GL(3, 2) has order 168, and
+        // every permutation combines with each of the six global Cliffords
+        let (all, complete) = automorphisms(&steane, PermutationRule::Any, 10_000).unwrap();
+        assert!(complete);
+        assert_eq!(all.len(), 168 * 6);
+        let (transversal, _) = automorphisms(&steane, PermutationRule::Identity, 100).unwrap();
+        assert_eq!(transversal.len(), 6);
+
+        let gates = gate_names("steane");
+        for gate in ["H", "S", "SQRT_X", "C_XYZ", "C_ZYX"] {
+            assert!(
+                gates.contains(&format!("transversal {}", gate)),
+                "{:?}",
+                gates
+            );
+        }
+    }
+
+    #[test]
+    fn test_fold_and_permutation_transversal_gates() {
+        // [[4,2,2]] with X̄_1 = XXII, Z̄_1 = ZIZI, X̄_2 = XIXI, Z̄_2 = ZZII:
+        // swapping qubits 1 and 2 swaps the logical qubits
+        let op = |s: &str| PauliString::from_str(s).unwrap();
+        let code = StabilizerCode::new(
+            4,
+            vec![op("XXXX"), op("ZZZZ")],
+            vec![op("XXII"), op("XIXI")],
+            vec![op("ZIZI"), op("ZZII")],
+        )
+        .unwrap();
+        let gates = logical_gates(&code).unwrap();
+        let swap = gates
+            .iter()
+            .find(|g| g.name == "[X0->X1, Z0->Z1, X1->X0, Z1->Z0]")
+            .unwrap();
+        assert_eq!(swap.implementation, Implementation::FoldTransversal);
+        assert_eq!(swap.map.permutation, vec![0, 2, 1, 3]);
+
+        // The rotated surface code's boundaries only match after a quarter
+        // turn, so its H needs a 4-cycle of qubits rather than a fold
+        let surface = get_stabilizer_code("surface_d3").unwrap();
+        let gates = logical_gates(&surface).unwrap();
+        assert_eq!(gates.len(), 1);
+        assert_eq!(gates[0].to_string(), "permutation-transversal H");
+        let p = &gates[0].map.permutation;
+        assert!((0..9).all(|q| p[p[p[p[q]]]] == q && p[p[q]] != q || q == 4));
+    }
+
+    #[test]
+    fn test_logical_action_names() {
+        assert_eq!(action_name(&[vec![true, false], vec![false, true]]), "I");
+        assert_eq!(action_name(&[vec![false, true], vec![true, false]]), "H");
+        // Logical SWAP on two qubits
+        let swap = vec![
+            vec![false, true, false, false],
+            vec![true, false, false, false],
+            vec![false, false, false, true],
+            vec![false, false, true, false],
+        ];
+        assert_eq!(action_name(&swap), "[X0->X1, Z0->Z1, X1->X0, Z1->Z0]");
+    }
+}
@@ -0,0 +1,342 @@
+#![allow(dead_code)]
+// Noise models from device calibration data
+//
+// Each calibrated quantity is turned into a Pauli channel by twirling:
+// amplitude and phase damping over a duration t (twirl::thermal_relaxation)
+// become
+//   px = py = (1 - e^(-t/T1)) / 4,  pz = (1 - e^(-t/T2)) / 2 - (1 - e^(-t/T1)) / 4
+// and the randomized benchmarking error r of a gate, less the decoherence
+// already expected during the gate, becomes depolarizing noise with total
+// probability (d + 1) r / d for dimension d. Readout assignment errors
+// become a symmetric bit flip before measurement.
+use crate::error::{NoiseError, PauliChannel, TwoQubitChannel, TwoQubitNoise};
+use crate::noise::{NoiseModel, QubitNoise};
+use crate::twirl::thermal_relaxation;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Calibration of one qubit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QubitCalibration {
+    /// Energy relaxation time in microseconds
+    pub t1_us: f64,
+    /// Dephasing time (Ramsey or echo) in microseconds, at most 2 T1
+    pub t2_us: f64,
+    /// Average error per single-qubit gate from randomized benchmarking
+    pub single_qubit_gate_error: f64,
+    /// Readout assignment matrix: `readout[prepared][measured]`
+    pub readout: [[f64; 2]; 2],
+}
+
+/// Calibration of one coupler
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CouplerCalibration {
+    pub qubits: [usize; 2],
+    /// Average error per two-qubit gate from interleaved randomized benchmarking
+    pub gate_error: f64,
+}
+
+/// Operation durations in nanoseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateDurations {
+    pub single_qubit_ns: f64,
+    pub two_qubit_ns: f64,
+    pub measurement_ns: f64,
+}
+
+/// Calibration snapshot of a chip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceCalibration {
+    pub name: String,
+    pub qubits: Vec<QubitCalibration>,
+    #[serde(default)]
+    pub couplers: Vec<CouplerCalibration>,
+    pub durations: GateDurations,
+}
+
+/// Probability distribution over single-qubit Paulis indexed by (x, z) bits:
+/// I, X, Z, Y
+type Distribution1 = [f64; 4];
+
+/// Pauli-twirled amplitude and phase damping over `duration_ns`
+fn decoherence(qubit: &QubitCalibration, duration_ns: f64) -> Result<Distribution1, NoiseError> {
+    let p = thermal_relaxation(duration_ns, qubit.t1_us, qubit.t2_us)?.pauli_probabilities();
+    // The twirl orders Paulis I, X, Y, Z; here the index is the (x, z) bits
+    Ok([p[0], p[1], p[3], p[2]])
+}
+
+/// Depolarizing distribution over `n_paulis` Paulis with total error `p`
+fn depolarizing(p: f64, n_paulis: usize) -> Vec<f64> {
+    let mut dist = vec![p / (n_paulis - 1) as f64; n_paulis];
+    dist[0] = 1.0 - p;
+    dist
+}
+
+/// Distribution of the product of independent Paulis drawn from `a` and `b`
+/// Multiplying Paulis XORs their (x, z) bit indices.
+fn compose(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; a.len()];
+    for (i, pa) in a.iter().enumerate() {
+        for (j, pb) in b.iter().enumerate() {
+            out[i ^ j] += pa * pb;
+        }
+    }
+    out
+}
+
+/// Depolarizing strength left for a gate with RB error `error` in dimension
+/// `dim` once the decoherence `noise` during the gate is accounted for
+fn residual_depolarizing(error: f64, dim: f64, noise: &[f64]) -> f64 {
+    let total = (dim + 1.0) * error / dim;
+    (total - (1.0 - noise[0])).max(0.0)
+}
+
+fn pauli_channel(dist: &[f64]) -> PauliChannel {
+    PauliChannel::Pauli {
+        px: dist[1],
+        py: dist[3],
+        pz: dist[2],
+    }
+}
+
+const LABELS: [char; 4] = ['I', 'X', 'Z', 'Y'];
+
+impl DeviceCalibration {
+    pub fn from_json(json: &str) -> Result<Self, NoiseError> {
+        let calibration: DeviceCalibration =
+            serde_json::from_str(json).map_err(|e| NoiseError::InvalidFile {
+                format: "calibration".to_string(),
+                reason: e.to_string(),
+            })?;
+        calibration.validate()?;
+        Ok(calibration)
+    }
+
+    /// Check that times are positive and physical and errors are probabilities
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        let probability = |p: f64| (0.0..=1.0).contains(&p);
+        for (i, qubit) in self.qubits.iter().enumerate() {
+            if !(qubit.t1_us > 0.0 && qubit.t2_us > 0.0) {
+                return Err(NoiseError::invalid(
+                    &self.name,
+                    format!("qubits[{}].t1_us", i),
+                    "times must be positive",
+                ));
+            }
+            if qubit.t2_us > 2.0 * qubit.t1_us {
+                return Err(NoiseError::invalid(
+                    &self.name,
+                    format!("qubits[{}].t2_us", i),
+                    "T2 exceeds 2 T1",
+                ));
+            }
+            if !probability(qubit.single_qubit_gate_error) {
+                return Err(NoiseError::invalid(
+                    &self.name,
+                    format!("qubits[{}].single_qubit_gate_error", i),
+                    "must lie in [0, 1]",
+                ));
+            }
+            let rows_ok = qubit.readout.iter().all(|row| {
+                row.iter().all(|&p| probability(p)) && (row[0] + row[1] - 1.0).abs() < 1e-6
+            });
+            if !rows_ok {
+                return Err(NoiseError::invalid(
+                    &self.name,
+                    format!("qubits[{}].readout", i),
+                    "rows must be probability distributions",
+                ));
+            }
+        }
+        for (i, coupler) in self.couplers.iter().enumerate() {
+            let [a, b] = coupler.qubits;
+            if a == b || a >= self.qubits.len() || b >= self.qubits.len() {
+                return Err(NoiseError::invalid(
+                    &self.name,
+                    format!("couplers[{}].qubits", i),
+                    "not two distinct qubits",
+                ));
+            }
+            if !probability(coupler.gate_error) {
+                return Err(NoiseError::invalid(
+                    &self.name,
+                    format!("couplers[{}].gate_error", i),
+                    "must lie in [0, 1]",
+                ));
+            }
+        }
+        let d = &self.durations;
+        if [d.single_qubit_ns, d.two_qubit_ns, d.measurement_ns]
+            .iter()
+            .any(|&t| t < 0.0)
+        {
+            return Err(NoiseError::invalid(
+                &self.name,
+                "durations".to_string(),
+                "must be non-negative",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Pauli-twirled circuit noise model of the chip
+    /// Idle qubits decohere for the length of a two-qubit gate per time step
+    /// and for the measurement time while others are read out; resets are
+    /// ideal since the calibration does not characterize them, and gates on
+    /// uncalibrated couplers are noiseless.
+    pub fn noise_model(&self) -> Result<NoiseModel, NoiseError> {
+        self.validate()?;
+        let d = &self.durations;
+        let step_ns = d.single_qubit_ns.max(d.two_qubit_ns);
+
+        let qubits: BTreeMap<usize, QubitNoise> = self
+            .qubits
+            .iter()
+            .enumerate()
+            .map(|(i, qubit)| {
+                let during_gate = decoherence(qubit, d.single_qubit_ns)?;
+                let p = residual_depolarizing(qubit.single_qubit_gate_error, 2.0, &during_gate);
+                let gate = compose(&during_gate, &depolarizing(p, 4));
+                let flip = (qubit.readout[0][1] + qubit.readout[1][0]) / 2.0;
+                let noise = QubitNoise {
+                    single_qubit_gate: Some(pauli_channel(&gate)),
+                    measurement: Some(PauliChannel::BitFlip { p: flip }),
+                    reset: None,
+                    idle: Some(pauli_channel(&decoherence(qubit, step_ns)?)),
+                    measurement_idle: Some(pauli_channel(&decoherence(qubit, d.measurement_ns)?)),
+                };
+                Ok((i, noise))
+            })
+            .collect::<Result<_, NoiseError>>()?;
+
+        let couplers = self
+            .couplers
+            .iter()
+            .map(|coupler| {
+                let [a, b] = coupler.qubits;
+                let (da, db) = (
+                    decoherence(&self.qubits[a], d.two_qubit_ns)?,
+                    decoherence(&self.qubits[b], d.two_qubit_ns)?,
+                );
+                let during_gate: Vec<f64> = (0..16).map(|i| da[i >> 2] * db[i & 3]).collect();
+                let p = residual_depolarizing(coupler.gate_error, 4.0, &during_gate);
+                let dist = compose(&during_gate, &depolarizing(p, 16));
+                let probabilities = dist
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .filter(|(_, &p)| p > 0.0)
+                    .map(|(i, &p)| (format!("{}{}", LABELS[i >> 2], LABELS[i & 3]), p))
+                    .collect();
+                Ok(TwoQubitNoise {
+                    channel: TwoQubitChannel::Table { probabilities },
+                    pairs: vec![coupler.qubits],
+                })
+            })
+            .collect::<Result<_, NoiseError>>()?;
+
+        let model = NoiseModel {
+            name: self.name.clone(),
+            qubits,
+            couplers,
+            ..NoiseModel::noiseless()
+        };
+        model.validate()?;
+        Ok(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qubit(t1_us: f64, t2_us: f64, error: f64) -> QubitCalibration {
+        QubitCalibration {
+            t1_us,
+            t2_us,
+            single_qubit_gate_error: error,
+            readout: [[0.98, 0.02], [0.06, 0.94]],
+        }
+    }
+
+    fn device() -> DeviceCalibration {
+        DeviceCalibration {
+            name: "chip".to_string(),
+            qubits: vec![qubit(100.0, 80.0, 0.001), qubit(50.0, 30.0, 0.002)],
+            couplers: vec![CouplerCalibration {
+                qubits: [0, 1],
+                gate_error: 0.01,
+            }],
+            durations: GateDurations {
+                single_qubit_ns: 30.0,
+                two_qubit_ns: 300.0,
+                measurement_ns: 1000.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_decoherence_twirl() {
+        // T2 = 2 T1 is pure relaxation: X, Y and Z equally likely
+        let [_, px, pz, py] = decoherence(&qubit(10.0, 20.0, 0.0), 1000.0).unwrap();
+        let relax = 1.0 - (-0.1f64).exp();
+        assert!((px - relax / 4.0).abs() < 1e-12 && px == py);
+        assert!((pz - (1.0 - (-0.05f64).exp()) / 2.0 + relax / 4.0).abs() < 1e-12);
+
+        // Dephasing-limited qubits see mostly Z errors
+        let [_, px, pz, _] = decoherence(&qubit(1000.0, 10.0, 0.0), 1000.0).unwrap();
+        assert!(pz > 10.0 * px);
+    }
+
+    #[test]
+    fn test_gate_error_matches_benchmark() {
+        // With negligible decoherence the gate is depolarizing with 3r/2
+        let mut calibration = device();
+        calibration.qubits[0] = qubit(1e9, 1e9, 0.002);
+        let model = calibration.noise_model().unwrap();
+        let channel = model.qubits[&0].single_qubit_gate.unwrap();
+        let total: f64 = channel.probabilities().iter().sum();
+        assert!((total - 0.003).abs() < 1e-9);
+
+        // The two-qubit table carries 5r/4 in total, decoherence included
+        let Some(TwoQubitChannel::Table { probabilities }) = model.coupler_channel(1, 0) else {
+            panic!("coupler has no table");
+        };
+        let total: f64 = probabilities.values().sum();
+        assert!((total - 0.0125).abs() < 1e-3, "{}", total);
+        assert!(!probabilities.contains_key("II"));
+    }
+
+    #[test]
+    fn test_readout_and_round_trip() {
+        let calibration = device();
+        let json = serde_json::to_string(&calibration).unwrap();
+        assert_eq!(DeviceCalibration::from_json(&json).unwrap(), calibration);
+
+        let model = calibration.noise_model().unwrap();
+        let Some(PauliChannel::BitFlip { p }) = model.qubits[&1].measurement else {
+            panic!("readout is not a bit flip");
+        };
+        assert!((p - 0.04).abs() < 1e-12);
+        // JSON floats may lose the last bit, so compare the structure
+        let parsed = NoiseModel::from_json(&model.to_json()).unwrap();
+        assert_eq!(parsed.qubits.len(), 2);
+        assert_eq!(parsed.couplers[0].pairs, vec![[0, 1]]);
+        assert_eq!(parsed.qubits[&0].measurement, model.qubits[&0].measurement);
+    }
+
+    #[test]
+    fn test_invalid_calibration() {
+        let mut calibration = device();
+        calibration.qubits[1].t2_us = 150.0;
+        assert!(calibration.noise_model().is_err());
+
+        let mut calibration = device();
+        calibration.couplers[0].qubits = [0, 2];
+        assert!(calibration.validate().is_err());
+
+        let mut calibration = device();
+        calibration.qubits[0].readout = [[0.9, 0.2], [0.0, 1.0]];
+        assert!(calibration.validate().is_err());
+    }
+}
@@ -0,0 +1,694 @@
+#![allow(dead_code)]
+use crate::codes::StabilizerCode;
+use crate::pauli::{Pauli, PauliString};
+use crate::stabilizer::StabilizerState;
+use crate::symplectic;
+use crate::validation::{validate_code, validate_stabilizers, CodeError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Clifford gate on named qubits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Gate {
+    H(usize),
+    S(usize),
+    Sdg(usize),
+    X(usize),
+    Y(usize),
+    Z(usize),
+    Cnot(usize, usize),
+    Cz(usize, usize),
+    Swap(usize, usize),
+}
+
+impl Gate {
+    /// Inverse gate
+    pub fn inverse(self) -> Gate {
+        match self {
+            Gate::S(q) => Gate::Sdg(q),
+            Gate::Sdg(q) => Gate::S(q),
+            other => other,
+        }
+    }
+
+    /// Qubits the gate acts on
+    pub fn qubits(self) -> Vec<usize> {
+        match self {
+            Gate::H(q) | Gate::S(q) | Gate::Sdg(q) | Gate::X(q) | Gate::Y(q) | Gate::Z(q) => {
+                vec![q]
+            }
+            Gate::Cnot(a, b) | Gate::Cz(a, b) | Gate::Swap(a, b) => vec![a, b],
+        }
+    }
+
+    /// Apply the gate to a stabilizer state
+    pub fn apply(self, state: &mut StabilizerState) {
+        match self {
+            Gate::H(q) => state.apply_h(q),
+            Gate::S(q) => state.apply_s(q),
+            Gate::Sdg(q) => state.apply_sdg(q),
+            Gate::X(q) => state.apply_x(q),
+            Gate::Y(q) => state.apply_y(q),
+            Gate::Z(q) => state.apply_z(q),
+            Gate::Cnot(c, t) => state.apply_cnot(c, t),
+            Gate::Cz(a, b) => state.apply_cz(a, b),
+            Gate::Swap(a, b) => state.apply_swap(a, b),
+        }
+    }
+
+    /// Conjugate a Hermitian Pauli string: P -> G P G†
+    pub fn conjugate(self, p: &mut PauliString) {
+        let bits = |p: &PauliString, q: usize| match p.paulis[q] {
+            Pauli::I => (false, false),
+            Pauli::X => (true, false),
+            Pauli::Y => (true, true),
+            Pauli::Z => (false, true),
+        };
+        let set = |p: &mut PauliString, q: usize, (x, z): (bool, bool)| {
+            p.paulis[q] = match (x, z) {
+                (false, false) => Pauli::I,
+                (true, false) => Pauli::X,
+                (true, true) => Pauli::Y,
+                (false, true) => Pauli::Z,
+            };
+        };
+
+        let flip = match self {
+            Gate::H(q) => {
+                let (x, z) = bits(p, q);
+                set(p, q, (z, x));
+                x && z
+            }
+            Gate::S(q) => {
+                let (x, z) = bits(p, q);
+                set(p, q, (x, z ^ x));
+                x && z
+            }
+            Gate::Sdg(q) => {
+                let (x, z) = bits(p, q);
+                set(p, q, (x, z ^ x));
+                x && !z
+            }
+            Gate::X(q) => bits(p, q).1,
+            Gate::Y(q) => {
+                let (x, z) = bits(p, q);
+                x != z
+            }
+            Gate::Z(q) => bits(p, q).0,
+            Gate::Cnot(c, t) => {
+                let ((xc, zc), (xt, zt)) = (bits(p, c), bits(p, t));
+                set(p, c, (xc, zc ^ zt));
+                set(p, t, (xt ^ xc, zt));
+                xc && zt && (xt == zc)
+            }
+            Gate::Cz(a, b) => {
+                let ((xa, za), (xb, zb)) = (bits(p, a), bits(p, b));
+                set(p, a, (xa, za ^ xb));
+                set(p, b, (xb, zb ^ xa));
+                xa && xb && (za != zb)
+            }
+            Gate::Swap(a, b) => {
+                p.paulis.swap(a, b);
+                false
+            }
+        };
+        if flip {
+            p.phase = p.phase.negate();
+        }
+    }
+}
+
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gate::H(q) => write!(f, "H {}", q),
+            Gate::S(q) => write!(f, "S {}", q),
+            Gate::Sdg(q) => write!(f, "S_DAG {}", q),
+            Gate::X(q) => write!(f, "X {}", q),
+            Gate::Y(q) => write!(f, "Y {}", q),
+            Gate::Z(q) => write!(f, "Z {}", q),
+            Gate::Cnot(c, t) => write!(f, "CNOT {} {}", c, t),
+            Gate::Cz(a, b) => write!(f, "CZ {} {}", a, b),
+            Gate::Swap(a, b) => write!(f, "SWAP {} {}", a, b),
+        }
+    }
+}
+
+/// Sequence of Clifford gates, applied first to last
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Circuit {
+    pub n_qubits: usize,
+    pub gates: Vec<Gate>,
+}
+
+impl Circuit {
+    pub fn new(n_qubits: usize) -> Self {
+        Circuit {
+            n_qubits,
+            gates: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, gate: Gate) {
+        self.gates.push(gate);
+    }
+
+    /// Append all gates of `other`
+    pub fn extend(&mut self, other: &Circuit) {
+        self.gates.extend_from_slice(&other.gates);
+    }
+
+    pub fn len(&self) -> usize {
+        self.gates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gates.is_empty()
+    }
+
+    /// Number of two-qubit gates
+    pub fn two_qubit_count(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|g| matches!(g, Gate::Cnot(..) | Gate::Cz(..) | Gate::Swap(..)))
+            .count()
+    }
+
+    /// Gates grouped into time steps: each gate runs in the first step after
+    /// the last gate on any of its qubits
+    pub fn moments(&self) -> Vec<Vec<Gate>> {
+        let mut moments: Vec<Vec<Gate>> = Vec::new();
+        let mut ready = vec![0usize; self.n_qubits];
+        for &gate in &self.gates {
+            let qubits = gate.qubits();
+            let step = qubits.iter().map(|&q| ready[q]).max().unwrap_or(0);
+            if step == moments.len() {
+                moments.push(Vec::new());
+            }
+            moments[step].push(gate);
+            for q in qubits {
+                ready[q] = step + 1;
+            }
+        }
+        moments
+    }
+
+    /// Inverse circuit: gates reversed and inverted
+    pub fn inverse(&self) -> Circuit {
+        Circuit {
+            n_qubits: self.n_qubits,
+            gates: self.gates.iter().rev().map(|g| g.inverse()).collect(),
+        }
+    }
+
+    /// Apply every gate to a stabilizer state
+    pub fn apply(&self, state: &mut StabilizerState) {
+        for gate in &self.gates {
+            gate.apply(state);
+        }
+    }
+
+    /// Image U P U† of a Pauli string under the circuit unitary U
+    pub fn conjugate(&self, p: &PauliString) -> PauliString {
+        let mut image = p.clone();
+        for gate in &self.gates {
+            gate.conjugate(&mut image);
+        }
+        image
+    }
+
+    /// Run the circuit on |0...0⟩
+    pub fn prepare(&self) -> StabilizerState {
+        let mut state = StabilizerState::new(self.n_qubits);
+        self.apply(&mut state);
+        state
+    }
+
+    /// Synthesize a circuit for the Clifford U with U X_q U† = `x_images[q]`
+    /// and U Z_q U† = `z_images[q]`
+    /// The images must be Hermitian and form a symplectic basis, checked as
+    /// the logical operators of a code without stabilizers. Each qubit
+    /// in turn is swept clean: its X image is reduced to X_q with H/S and
+    /// CNOTs, then its Z image to Z_q with gates that fix X_q, and the signs
+    /// are fixed with Paulis. The gates found undo U, so U is their inverse.
+    pub fn from_tableau(
+        x_images: &[PauliString],
+        z_images: &[PauliString],
+    ) -> Result<Circuit, CodeError> {
+        let n = x_images.len();
+        validate_code(&StabilizerCode {
+            n_qubits: n,
+            generators: Vec::new(),
+            logical_x: x_images.to_vec(),
+            logical_z: z_images.to_vec(),
+            distance: 0,
+        })?;
+        let mut rows: Vec<PauliString> = x_images.iter().chain(z_images).cloned().collect();
+        let mut undo = Circuit::new(n);
+        let mut apply = |gate: Gate, rows: &mut Vec<PauliString>| {
+            for row in rows.iter_mut() {
+                gate.conjugate(row);
+            }
+            undo.push(gate);
+        };
+
+        for q in 0..n {
+            // X image -> X on qubits >= q only
+            for j in q..n {
+                match rows[q].paulis[j] {
+                    Pauli::Z => apply(Gate::H(j), &mut rows),
+                    Pauli::Y => apply(Gate::S(j), &mut rows),
+                    _ => {}
+                }
+            }
+            if rows[q].paulis[q] == Pauli::I {
+                // A symplectic basis keeps the reduced X image nontrivial
+                let j = (q + 1..n)
+                    .find(|&j| rows[q].paulis[j] == Pauli::X)
+                    .ok_or_else(|| CodeError::LogicalPairing {
+                        first: format!("X{}", q),
+                        second: format!("Z{}", q),
+                    })?;
+                apply(Gate::Cnot(j, q), &mut rows);
+            }
+            for j in q + 1..n {
+                if rows[q].paulis[j] == Pauli::X {
+                    apply(Gate::Cnot(q, j), &mut rows);
+                }
+            }
+
+            // Z image -> Z_q using gates that leave X_q alone
+            let z = n + q;
+            if rows[z].paulis[q] == Pauli::Y {
+                // H S H fixes X and maps Y to Z up to sign
+                apply(Gate::H(q), &mut rows);
+                apply(Gate::S(q), &mut rows);
+                apply(Gate::H(q), &mut rows);
+            }
+            for j in q + 1..n {
+                match rows[z].paulis[j] {
+                    Pauli::X => apply(Gate::H(j), &mut rows),
+                    Pauli::Y => {
+                        apply(Gate::S(j), &mut rows);
+                        apply(Gate::H(j), &mut rows);
+                    }
+                    _ => {}
+                }
+                if rows[z].paulis[j] == Pauli::Z {
+                    apply(Gate::Cnot(j, q), &mut rows);
+                }
+            }
+
+            if rows[q].phase != crate::pauli::Phase::Plus {
+                apply(Gate::Z(q), &mut rows);
+            }
+            if rows[z].phase != crate::pauli::Phase::Plus {
+                apply(Gate::X(q), &mut rows);
+            }
+        }
+        Ok(undo.inverse())
+    }
+
+    /// Circuit preparing the stabilizer state of `stabilizers` (independent
+    /// commuting Hermitian Paulis, signs respected) from |0...0⟩
+    /// The group is repeatedly row reduced and its lightest element is
+    /// collapsed onto a single Z with basis changes and CNOTs, so states that
+    /// are already close to |0...0⟩ need few gates. Signs are fixed at the end
+    /// with a Pauli that anticommutes with exactly the wrong stabilizers.
+    pub fn state_preparation(stabilizers: &[PauliString]) -> Result<Circuit, CodeError> {
+        let n = stabilizers.first().map_or(0, PauliString::n_qubits);
+        validate_stabilizers(n, stabilizers)?;
+        let mut undo = Circuit::new(n);
+        let mut pinned: Vec<Vec<bool>> = Vec::new();
+        let mut rows: Vec<Vec<bool>> = stabilizers.iter().map(symplectic::to_bits).collect();
+        while !rows.is_empty() {
+            // Rows reduced against the Z_q already produced avoid those qubits
+            let basis = symplectic::reduced_basis(&[pinned.clone(), rows].concat());
+            let mut rest: Vec<PauliString> = basis
+                .iter()
+                .filter(|row| !pinned.contains(row))
+                .map(|row| symplectic::from_bits(row))
+                .collect();
+            let Some(lightest) = (0..rest.len()).min_by_key(|&i| rest[i].weight()) else {
+                break;
+            };
+            let mut row = rest.swap_remove(lightest);
+            let mut apply = |gate: Gate, row: &mut PauliString, rest: &mut Vec<PauliString>| {
+                gate.conjugate(row);
+                for other in rest.iter_mut() {
+                    gate.conjugate(other);
+                }
+                undo.push(gate);
+            };
+
+            let support: Vec<usize> = (0..n).filter(|&q| row.paulis[q] != Pauli::I).collect();
+            for &q in &support {
+                match row.paulis[q] {
+                    Pauli::X => apply(Gate::H(q), &mut row, &mut rest),
+                    Pauli::Y => {
+                        apply(Gate::S(q), &mut row, &mut rest);
+                        apply(Gate::H(q), &mut row, &mut rest);
+                    }
+                    _ => {}
+                }
+            }
+            let target = support[0];
+            for &q in &support[1..] {
+                apply(Gate::Cnot(q, target), &mut row, &mut rest);
+            }
+            pinned.push(symplectic::to_bits(&PauliString::single(
+                n,
+                target,
+                Pauli::Z,
+            )));
+            rows = rest.iter().map(symplectic::to_bits).collect();
+        }
+
+        let mut circuit = undo.inverse();
+        let state = circuit.prepare();
+        let wrong: Vec<bool> = stabilizers
+            .iter()
+            .map(|s| state.peek_pauli(s) == Some(true))
+            .collect();
+        // P anticommutes with S exactly when (z_S | x_S) · (x_P | z_P) = 1
+        let swapped: Vec<Vec<bool>> = stabilizers
+            .iter()
+            .map(|s| {
+                let bits = symplectic::to_bits(s);
+                [&bits[n..], &bits[..n]].concat()
+            })
+            .collect();
+        // Independent stabilizers can take any signs
+        let correction = symplectic::solve(&swapped, &wrong)
+            .map(|bits| symplectic::from_bits(&bits))
+            .ok_or_else(|| CodeError::DependentGenerator {
+                index: stabilizers.len() - 1,
+            })?;
+        for (q, p) in correction.paulis.iter().enumerate() {
+            match p {
+                Pauli::I => {}
+                Pauli::X => circuit.push(Gate::X(q)),
+                Pauli::Y => circuit.push(Gate::Y(q)),
+                Pauli::Z => circuit.push(Gate::Z(q)),
+            }
+        }
+        Ok(circuit)
+    }
+}
+
+impl fmt::Display for Circuit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for gate in &self.gates {
+            writeln!(f, "{}", gate)?;
+        }
+        Ok(())
+    }
+}
+
+/// Unitary encoder of a code (not fault tolerant)
+/// Logical qubit j enters on physical qubit j, the other n - k qubits start
+/// in |0⟩: the circuit maps X_j, Z_j to X̄_j, Z̄_j for j < k and Z_{k+i} to
+/// stabilizer generator i.
+pub fn encoder(code: &StabilizerCode) -> Result<Circuit, CodeError> {
+    let logicals: Vec<PauliString> = code
+        .logical_x
+        .iter()
+        .chain(&code.logical_z)
+        .cloned()
+        .collect();
+    let destabilizers = symplectic::destabilizers(&code.generators, &logicals);
+    let x_images: Vec<PauliString> = code
+        .logical_x
+        .iter()
+        .chain(&destabilizers)
+        .cloned()
+        .collect();
+    let z_images: Vec<PauliString> = code
+        .logical_z
+        .iter()
+        .chain(&code.generators)
+        .cloned()
+        .collect();
+    Circuit::from_tableau(&x_images, &z_images)
+}
+
+/// Unencoding circuit: the inverse encoder, which moves logical qubit j back
+/// to physical qubit j and returns the others to |0⟩
+pub fn decoder(code: &StabilizerCode) -> Result<Circuit, CodeError> {
+    encoder(code).map(|circuit| circuit.inverse())
+}
+
+/// Prepare |0̄...0̄⟩ directly as the stabilizer state of the generators and
+/// logical Zs, which usually needs fewer gates than the full encoder
+pub fn prepare_logical_zero(code: &StabilizerCode) -> Result<Circuit, CodeError> {
+    let mut stabilizers = code.generators.clone();
+    stabilizers.extend(code.logical_z.iter().cloned());
+    Circuit::state_preparation(&stabilizers)
+}
+
+/// Prepare |+̄...+̄⟩ as the stabilizer state of the generators and logical Xs
+pub fn prepare_logical_plus(code: &StabilizerCode) -> Result<Circuit, CodeError> {
+    let mut stabilizers = code.generators.clone();
+    stabilizers.extend(code.logical_x.iter().cloned());
+    Circuit::state_preparation(&stabilizers)
+}
+
+/// Prepare the encoded version of the k-qubit stabilizer state with
+/// `logical_stabilizers`, e.g. ["XX", "ZZ"] for a logical Bell pair
+pub fn prepare_encoded_state(
+    code: &StabilizerCode,
+    logical_stabilizers: &[PauliString],
+) -> Result<Circuit, CodeError> {
+    let mut stabilizers = code.generators.clone();
+    stabilizers.extend(
+        logical_stabilizers
+            .iter()
+            .map(|l| logical_operator(code, l)),
+    );
+    Circuit::state_preparation(&stabilizers)
+}
+
+/// Logical operator for a k-qubit Pauli string: X_j -> X̄_j, Z_j -> Z̄_j
+pub fn logical_operator(code: &StabilizerCode, logical: &PauliString) -> PauliString {
+    let mut op = PauliString::identity(code.n_qubits);
+    op.phase = logical.phase;
+    for (j, p) in logical.paulis.iter().enumerate() {
+        let (x, z) = match p {
+            Pauli::I => (false, false),
+            Pauli::X => (true, false),
+            Pauli::Y => (true, true),
+            Pauli::Z => (false, true),
+        };
+        if x {
+            op = op.multiply(&code.logical_x[j]);
+        }
+        if z {
+            op = op.multiply(&code.logical_z[j]);
+        }
+        if x && z {
+            // X̄ Z̄ = -i Ȳ
+            op.phase = op.phase.multiply(crate::pauli::Phase::PlusI);
+        }
+    }
+    op
+}
+
+/// Check that `state` is in the code space, with every stabilizer generator
+/// at +1, and that each logical Pauli in `logical_stabilizers` has value +1
+pub fn verify_encoded_state(
+    code: &StabilizerCode,
+    state: &StabilizerState,
+    logical_stabilizers: &[PauliString],
+) -> bool {
+    code.generators
+        .iter()
+        .all(|g| state.peek_pauli(g) == Some(false))
+        && logical_stabilizers
+            .iter()
+            .all(|l| state.peek_pauli(&logical_operator(code, l)) == Some(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{get_stabilizer_code, repetition_code_3};
+
+    fn pauli(s: &str) -> PauliString {
+        PauliString::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_conjugation_matches_state_update() {
+        let gates = [
+            Gate::H(0),
+            Gate::S(1),
+            Gate::Cnot(0, 2),
+            Gate::Sdg(2),
+            Gate::Cz(1, 2),
+            Gate::Y(0),
+            Gate::Swap(0, 1),
+            Gate::Cnot(2, 1),
+        ];
+        let stabilizers = [pauli("YXI"), pauli("-ZZI"), pauli("IYY")];
+        let mut state = StabilizerState::from_stabilizers(3, &stabilizers);
+        let mut circuit = Circuit::new(3);
+        for gate in gates {
+            circuit.push(gate);
+        }
+        circuit.apply(&mut state);
+        for (i, s) in stabilizers.iter().enumerate() {
+            assert_eq!(state.get_stabilizer(i), circuit.conjugate(s));
+        }
+        // The inverse undoes the circuit
+        let roundtrip = circuit
+            .inverse()
+            .conjugate(&circuit.conjugate(&pauli("XYZ")));
+        assert_eq!(roundtrip, pauli("XYZ"));
+    }
+
+    #[test]
+    fn test_encoder_maps_tableau() {
+        for name in [
+            "repetition_3",
+            "five_qubit",
+            "steane",
+            "surface_d3",
+            "bb:l=3,m=3,a=1+y+y2,b=1+x+x2",
+        ] {
+            let code = get_stabilizer_code(name).unwrap();
+            let circuit = encoder(&code).unwrap();
+            let n = code.n_qubits;
+            let k = code.logical_x.len();
+            for j in 0..k {
+                let x = PauliString::single(n, j, Pauli::X);
+                let z = PauliString::single(n, j, Pauli::Z);
+                assert_eq!(circuit.conjugate(&x), code.logical_x[j], "{}", name);
+                assert_eq!(circuit.conjugate(&z), code.logical_z[j], "{}", name);
+            }
+            for (i, g) in code.generators.iter().enumerate() {
+                let z = PauliString::single(n, k + i, Pauli::Z);
+                assert_eq!(&circuit.conjugate(&z), g, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_logical_state_preparation() {
+        let code = repetition_code_3().unwrap();
+        let zero = prepare_logical_zero(&code).unwrap().prepare();
+        assert!(verify_encoded_state(&code, &zero, &[pauli("Z")]));
+        assert!(!verify_encoded_state(&code, &zero, &[pauli("X")]));
+        let plus_circuit = prepare_logical_plus(&code).unwrap();
+        let plus = plus_circuit.prepare();
+        assert!(verify_encoded_state(&code, &plus, &[pauli("X")]));
+        // |000⟩ is already |0̄⟩; |+̄⟩ needs one H and two CNOTs
+        assert!(prepare_logical_zero(&code).unwrap().is_empty());
+        assert_eq!(plus_circuit.two_qubit_count(), 2);
+
+        // Decoding returns the logical qubit to qubit 0 and ancillas to |0⟩
+        let mut state = plus.clone();
+        decoder(&code).unwrap().apply(&mut state);
+        assert_eq!(state.peek_pauli(&pauli("XII")), Some(false));
+        assert_eq!(state.peek_pauli(&pauli("IZI")), Some(false));
+        assert_eq!(state.peek_pauli(&pauli("IIZ")), Some(false));
+
+        for name in ["five_qubit", "steane", "surface_d3"] {
+            let code = get_stabilizer_code(name).unwrap();
+            let zero = prepare_logical_zero(&code).unwrap().prepare();
+            assert!(
+                verify_encoded_state(&code, &zero, &[pauli("Z")]),
+                "{}",
+                name
+            );
+            let plus = prepare_logical_plus(&code).unwrap().prepare();
+            assert!(
+                verify_encoded_state(&code, &plus, &[pauli("X")]),
+                "{}",
+                name
+            );
+            let minus_i = prepare_encoded_state(&code, &[pauli("-Y")])
+                .unwrap()
+                .prepare();
+            assert!(
+                verify_encoded_state(&code, &minus_i, &[pauli("-Y")]),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_synthesis_rejects_invalid_input() {
+        let ops = |strings: &[&str]| strings.iter().map(|s| pauli(s)).collect::<Vec<_>>();
+        assert!(Circuit::from_tableau(&ops(&["XI", "IX"]), &ops(&["ZI", "IZ"])).is_ok());
+        assert!(matches!(
+            Circuit::from_tableau(&ops(&["XI", "XI"]), &ops(&["ZI", "IZ"])),
+            Err(CodeError::LogicalPairing { .. })
+        ));
+        assert!(matches!(
+            Circuit::from_tableau(&ops(&["XI", "IX"]), &ops(&["ZI"])),
+            Err(CodeError::LogicalCountMismatch { .. })
+        ));
+
+        assert_eq!(
+            Circuit::state_preparation(&ops(&["XX", "ZI"])).unwrap_err(),
+            CodeError::NonCommutingStabilizers {
+                first: 0,
+                second: 1
+            }
+        );
+        assert_eq!(
+            Circuit::state_preparation(&ops(&["ZZ", "-ZZ"])).unwrap_err(),
+            CodeError::DependentGenerator { index: 1 }
+        );
+        assert!(matches!(
+            Circuit::state_preparation(&ops(&["ZZ", "Z"])),
+            Err(CodeError::QubitCountMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_moments() {
+        let mut circuit = Circuit::new(4);
+        circuit.push(Gate::H(0));
+        circuit.push(Gate::Cnot(0, 1));
+        circuit.push(Gate::Cnot(2, 3));
+        circuit.push(Gate::X(3));
+        let moments = circuit.moments();
+        assert_eq!(
+            moments,
+            vec![
+                vec![Gate::H(0), Gate::Cnot(2, 3)],
+                vec![Gate::Cnot(0, 1), Gate::X(3)]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encoded_bell_pair() {
+        let code = get_stabilizer_code("bb:l=3,m=3,a=1+y+y2,b=1+x+x2").unwrap();
+        let k = code.logical_x.len();
+        assert!(k >= 2);
+        let pair = |a: Pauli| {
+            let mut p = PauliString::identity(k);
+            p.paulis[0] = a;
+            p.paulis[1] = a;
+            p
+        };
+        let mut logical_stabilizers = vec![pair(Pauli::X), pair(Pauli::Z)];
+        for j in 2..k {
+            logical_stabilizers.push(PauliString::single(k, j, Pauli::Z));
+        }
+        let state = prepare_encoded_state(&code, &logical_stabilizers)
+            .unwrap()
+            .prepare();
+        assert!(verify_encoded_state(&code, &state, &logical_stabilizers));
+        assert_eq!(
+            state.peek_pauli(&logical_operator(
+                &code,
+                &PauliString::single(k, 0, Pauli::Z)
+            )),
+            None
+        );
+    }
+}
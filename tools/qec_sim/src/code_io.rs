@@ -0,0 +1,540 @@
+#![allow(dead_code)]
+use crate::codes::{css_operator, CodeInfo, StabilizerCode};
+use crate::distance::{self, DistanceCheck};
+use crate::layout::Layout;
+use crate::pauli::{Pauli, PauliString};
+use crate::registry::{CodeFamily, CodeRegistry, QuantumCode};
+use crate::symplectic;
+use crate::validation::{validate_info, CodeError};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Complete serializable description of a code
+/// The JSON form is `CodeInfo` with the operators as Pauli strings:
+/// {"name": ..., "n_qubits": 7, "n_logical": 1, "distance": 3,
+///  "generators": ["IIIXXXX", ...], "logical_x": [...], "logical_z": [...]}
+/// Logical operators may be omitted, in which case a basis is computed, and
+/// an optional "layout" gives drawing coordinates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeDescription {
+    #[serde(flatten)]
+    pub info: CodeInfo,
+    pub generators: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logical_x: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logical_z: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Layout>,
+}
+
+impl CodeDescription {
+    /// Describe a constructed code
+    pub fn new(info: CodeInfo, code: &StabilizerCode) -> Self {
+        let strings = |ops: &[PauliString]| ops.iter().map(|op| op.to_string()).collect();
+        CodeDescription {
+            info,
+            generators: strings(&code.generators),
+            logical_x: strings(&code.logical_x),
+            logical_z: strings(&code.logical_z),
+            layout: None,
+        }
+    }
+
+    /// Description of a registered code
+    pub fn from_registry(registry: &CodeRegistry, key: &str) -> Result<Self, CodeError> {
+        let entry = registry.get(key)?;
+        let code = entry.stabilizer_code()?;
+        Ok(CodeDescription {
+            layout: entry.layout(),
+            ..CodeDescription::new(entry.info(), &code)
+        })
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, CodeError> {
+        serde_json::from_str(json).map_err(|e| invalid_file("json", &e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// CSS code from classical parity-check matrices in alist format
+    /// Rows of `hx` become X checks and rows of `hz` Z checks; redundant rows
+    /// are dropped. k and the distance are computed.
+    pub fn from_css_alist(name: &str, hx: &str, hz: &str) -> Result<Self, CodeError> {
+        let (n_x, x_rows) = parse_alist(hx)?;
+        let (n_z, z_rows) = parse_alist(hz)?;
+        if n_x != n_z {
+            return Err(invalid_file(
+                "alist",
+                &format!("H_X has {} columns but H_Z has {}", n_x, n_z),
+            ));
+        }
+        let checks: Vec<PauliString> = x_rows
+            .iter()
+            .map(|row| css_operator(n_x, row, Pauli::X))
+            .chain(z_rows.iter().map(|row| css_operator(n_x, row, Pauli::Z)))
+            .collect();
+        let code = StabilizerCode::from_generators(n_x, symplectic::independent_subset(&checks))?;
+        let report = distance::compute_distance(&code);
+        let info = CodeInfo {
+            name: name.to_string(),
+            description: format!(
+                "CSS code from {}x{} and {}x{} parity checks",
+                x_rows.len(),
+                n_x,
+                z_rows.len(),
+                n_x
+            ),
+            n_qubits: n_x,
+            n_logical: code.n_logical(),
+            distance: report.distance,
+            x_distance: report.x_distance,
+            z_distance: report.z_distance,
+            distance_exact: report.exact,
+            logical_gates: None,
+        };
+        Ok(CodeDescription::new(info, &code))
+    }
+
+    /// H_X and H_Z of a CSS code in alist format; None for non-CSS codes
+    pub fn to_css_alist(&self) -> Result<Option<(String, String)>, CodeError> {
+        let generators = parse_operators(&self.generators, self.info.n_qubits)?;
+        if !symplectic::is_css(&generators) {
+            return Ok(None);
+        }
+        let rows = |pauli: Pauli| -> Vec<Vec<usize>> {
+            generators
+                .iter()
+                .filter(|g| g.paulis.contains(&pauli))
+                .map(|g| {
+                    (0..g.n_qubits())
+                        .filter(|&q| g.paulis[q] == pauli)
+                        .collect()
+                })
+                .collect()
+        };
+        let n = self.info.n_qubits;
+        Ok(Some((
+            write_alist(&rows(Pauli::X), n),
+            write_alist(&rows(Pauli::Z), n),
+        )))
+    }
+}
+
+/// Code loaded from a file, served by the registry like a built-in code
+#[derive(Debug, Clone)]
+pub struct LoadedCode {
+    pub key: String,
+    pub description: CodeDescription,
+    /// The code built and checked once when it was loaded
+    code: StabilizerCode,
+}
+
+impl LoadedCode {
+    /// Build the code and check the claims of its description
+    /// Files often describe codes too large for an exhaustive distance
+    /// search. The claimed distance is searched only within the budget;
+    /// beyond it the claim is kept as an upper bound, which the logical
+    /// representatives already guarantee, and `distance_exact` is cleared.
+    pub fn new(key: &str, mut description: CodeDescription) -> Result<Self, CodeError> {
+        let info = &description.info;
+        let generators = parse_operators(&description.generators, info.n_qubits)?;
+        let (logical_x, logical_z) =
+            if description.logical_x.is_empty() && description.logical_z.is_empty() {
+                symplectic::logical_basis(&generators, info.n_qubits)
+            } else {
+                (
+                    parse_operators(&description.logical_x, info.n_qubits)?,
+                    parse_operators(&description.logical_z, info.n_qubits)?,
+                )
+            };
+        let code = StabilizerCode::with_distance(
+            info.n_qubits,
+            generators,
+            logical_x,
+            logical_z,
+            info.distance,
+        )?;
+        validate_info(&code, info)?;
+        let searched = match distance::check_distance(&code, info.distance) {
+            DistanceCheck::Confirmed => true,
+            DistanceCheck::Lighter(computed) => {
+                return Err(CodeError::InfoMismatch {
+                    field: "distance".to_string(),
+                    claimed: info.distance,
+                    computed,
+                })
+            }
+            DistanceCheck::Skipped => false,
+        };
+        if let Some(layout) = &description.layout {
+            if layout.n_qubits() != info.n_qubits {
+                return Err(CodeError::QubitCountMismatch {
+                    operator: "layout".to_string(),
+                    expected: info.n_qubits,
+                    found: layout.n_qubits(),
+                });
+            }
+        }
+        description.info.distance_exact &= searched;
+        // Gate lists in files are not trusted; `get_logical_gates` searches
+        description.info.logical_gates = None;
+        Ok(LoadedCode {
+            key: key.to_string(),
+            description,
+            code,
+        })
+    }
+}
+
+impl QuantumCode for LoadedCode {
+    fn key(&self) -> String {
+        self.key.clone()
+    }
+
+    fn info(&self) -> CodeInfo {
+        self.description.info.clone()
+    }
+
+    fn generators(&self) -> Result<Vec<PauliString>, CodeError> {
+        Ok(self.code.generators.clone())
+    }
+
+    fn logicals(&self) -> Result<(Vec<PauliString>, Vec<PauliString>), CodeError> {
+        Ok((self.code.logical_x.clone(), self.code.logical_z.clone()))
+    }
+
+    fn layout(&self) -> Option<Layout> {
+        self.description.layout.clone()
+    }
+
+    fn stabilizer_code(&self) -> Result<StabilizerCode, CodeError> {
+        Ok(self.code.clone())
+    }
+}
+
+impl CodeRegistry {
+    /// Register a code description under `key`, checking it first
+    /// The key must be new and a plain name: registry keys use ':', '=' and
+    /// ',' for family parameters.
+    pub fn register_description(
+        &mut self,
+        key: &str,
+        description: CodeDescription,
+    ) -> Result<(), CodeError> {
+        let reason = if key.trim().is_empty() {
+            Some("must not be empty")
+        } else if key.contains([':', '=', ',']) || key.trim() != key {
+            Some("must not contain ':', '=', ',' or surrounding spaces")
+        } else if self.is_taken(key) {
+            Some("already names a registered code")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(CodeError::invalid_parameter(key, "key", reason));
+        }
+        let loaded = LoadedCode::new(key, description)?;
+        self.register(CodeFamily {
+            name: key.to_string(),
+            parameters: Vec::new(),
+            examples: vec![key.to_string()],
+            build: Box::new(move |_| Ok(Box::new(loaded.clone()))),
+        });
+        Ok(())
+    }
+}
+
+/// Read a JSON code description from disk
+pub fn read_code_json(path: &Path) -> Result<CodeDescription, CodeError> {
+    CodeDescription::from_json(&read_file(path)?)
+}
+
+/// Write a JSON code description to disk
+pub fn write_code_json(path: &Path, description: &CodeDescription) -> Result<(), CodeError> {
+    std::fs::write(path, description.to_json()).map_err(|e| invalid_file("json", &e.to_string()))
+}
+
+/// Read a CSS code from a pair of alist files
+pub fn read_css_alist(name: &str, hx: &Path, hz: &Path) -> Result<CodeDescription, CodeError> {
+    CodeDescription::from_css_alist(name, &read_file(hx)?, &read_file(hz)?)
+}
+
+fn read_file(path: &Path) -> Result<String, CodeError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| invalid_file(&path.display().to_string(), &e.to_string()))
+}
+
+fn invalid_file(format: &str, reason: &str) -> CodeError {
+    CodeError::InvalidFile {
+        format: format.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn parse_operators(strings: &[String], n_qubits: usize) -> Result<Vec<PauliString>, CodeError> {
+    strings
+        .iter()
+        .map(|s| {
+            let op =
+                PauliString::from_str(s).ok_or_else(|| CodeError::InvalidPauliString(s.clone()))?;
+            if op.n_qubits() != n_qubits {
+                return Err(CodeError::QubitCountMismatch {
+                    operator: s.clone(),
+                    expected: n_qubits,
+                    found: op.n_qubits(),
+                });
+            }
+            Ok(op)
+        })
+        .collect()
+}
+
+/// Parse a binary matrix in MacKay's alist format
+/// Returns the number of columns and, for each row, its nonzero columns.
+/// The column lists must agree with the row lists; zero padding is allowed.
+pub fn parse_alist(text: &str) -> Result<(usize, Vec<Vec<usize>>), CodeError> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    let mut numbers = |what: &str| -> Result<Vec<usize>, CodeError> {
+        let line = lines
+            .next()
+            .ok_or_else(|| invalid_file("alist", &format!("missing {}", what)))?;
+        line.split_whitespace()
+            .map(|t| {
+                t.parse()
+                    .map_err(|_| invalid_file("alist", &format!("bad number {:?} in {}", t, what)))
+            })
+            .collect()
+    };
+
+    let header = numbers("header")?;
+    let [n_cols, n_rows] = header[..] else {
+        return Err(invalid_file("alist", "header must be \"n_cols n_rows\""));
+    };
+    numbers("maximum weights")?;
+    numbers("column weights")?;
+    numbers("row weights")?;
+
+    let mut from_columns = vec![Vec::new(); n_rows];
+    for c in 0..n_cols {
+        for r in numbers("column list")?.into_iter().filter(|&r| r != 0) {
+            if r > n_rows {
+                return Err(invalid_file("alist", &format!("row {} out of range", r)));
+            }
+            from_columns[r - 1].push(c);
+        }
+    }
+    let mut rows = Vec::with_capacity(n_rows);
+    for _ in 0..n_rows {
+        let mut row: Vec<usize> = numbers("row list")?
+            .into_iter()
+            .filter(|&c| c != 0)
+            .map(|c| c - 1)
+            .collect();
+        if row.iter().any(|&c| c >= n_cols) {
+            return Err(invalid_file("alist", "column out of range"));
+        }
+        row.sort_unstable();
+        rows.push(row);
+    }
+    if rows != from_columns {
+        return Err(invalid_file("alist", "row and column lists disagree"));
+    }
+    Ok((n_cols, rows))
+}
+
+/// Write a binary matrix, given as nonzero columns per row, in alist format
+pub fn write_alist(rows: &[Vec<usize>], n_cols: usize) -> String {
+    let mut columns = vec![Vec::new(); n_cols];
+    for (r, row) in rows.iter().enumerate() {
+        for &c in row {
+            columns[c].push(r);
+        }
+    }
+    let max_col = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let max_row = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+    let join = |values: &mut dyn Iterator<Item = usize>| {
+        values.map(|v| v.to_string()).collect::<Vec<_>>().join(" ")
+    };
+    let padded = |entries: &[usize], width: usize| {
+        join(&mut (0..width).map(|i| entries.get(i).map_or(0, |e| e + 1)))
+    };
+
+    let mut lines = vec![
+        format!("{} {}", n_cols, rows.len()),
+        format!("{} {}", max_col, max_row),
+        join(&mut columns.iter().map(Vec::len)),
+        join(&mut rows.iter().map(Vec::len)),
+    ];
+    lines.extend(columns.iter().map(|c| padded(c, max_col)));
+    lines.extend(rows.iter().map(|r| padded(r, max_row)));
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAMMING_ALIST: &str = "7 3
+3 4
+1 1 2 1 2 2 3
+4 4 4
+1 0 0
+2 0 0
+1 2 0
+3 0 0
+1 3 0
+2 3 0
+1 2 3
+1 3 5 7
+2 3 6 7
+4 5 6 7
+";
+
+    #[test]
+    fn test_json_roundtrip() {
+        let registry = CodeRegistry::builtin();
+        for key in registry.available() {
+            let description = CodeDescription::from_registry(&registry, &key).unwrap();
+            let parsed = CodeDescription::from_json(&description.to_json()).unwrap();
+            // Coordinates survive the text form only up to rounding
+            let (layout, parsed_layout) = (description.layout.clone(), parsed.layout.clone());
+            assert_eq!(
+                CodeDescription {
+                    layout: None,
+                    ..parsed.clone()
+                },
+                CodeDescription {
+                    layout: None,
+                    ..description
+                }
+            );
+            let coordinates = |layout: Option<Layout>| -> Vec<f64> {
+                layout
+                    .map(|l| l.data_qubits.iter().flat_map(|p| [p.x, p.y]).collect())
+                    .unwrap_or_default()
+            };
+            let (a, b) = (coordinates(layout), coordinates(parsed_layout));
+            assert_eq!(a.len(), b.len());
+            assert!(a.iter().zip(&b).all(|(x, y)| (x - y).abs() < 1e-12));
+
+            let mut target = CodeRegistry::new();
+            target.register_description("loaded", parsed).unwrap();
+            let original = registry.get(&key).unwrap().stabilizer_code().unwrap();
+            let loaded = target.get("loaded").unwrap().stabilizer_code().unwrap();
+            assert_eq!(loaded.generators, original.generators);
+            assert_eq!(loaded.distance, original.distance);
+            // Too large to search on load, so the claim stays a bound
+            let exact = target.get("loaded").unwrap().info().distance_exact;
+            assert_eq!(exact, original.n_qubits <= 25, "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_json_without_logicals() {
+        let json = r#"{
+            "name": "Steane from file",
+            "n_qubits": 7,
+            "n_logical": 1,
+            "distance": 3,
+            "generators": ["IIIXXXX", "IXXIIXX", "XIXIXIX", "IIIZZZZ", "IZZIIZZ", "ZIZIZIZ"]
+        }"#;
+        let description = CodeDescription::from_json(json).unwrap();
+        assert_eq!(description.info.description, "");
+        let mut registry = CodeRegistry::builtin();
+        registry
+            .register_description("my_steane", description.clone())
+            .unwrap();
+        let code = registry
+            .get("my_steane")
+            .unwrap()
+            .stabilizer_code()
+            .unwrap();
+        assert_eq!(code.logical_x.len(), 1);
+
+        // Claims are checked against the code on registration
+        let mut wrong = description;
+        wrong.info.distance = 4;
+        assert!(matches!(
+            registry.register_description("bad", wrong),
+            Err(CodeError::InfoMismatch { .. })
+        ));
+        assert!(matches!(
+            CodeDescription::from_json("{\"name\": 1}"),
+            Err(CodeError::InvalidFile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_register_rejects_taken_keys() {
+        let mut registry = CodeRegistry::builtin();
+        let description = CodeDescription::from_registry(&registry, "steane").unwrap();
+        for key in [
+            "steane",
+            "surface",
+            "surface_d3",
+            "surface:d=3",
+            "a=b",
+            "a,b",
+            " spaced",
+            "",
+        ] {
+            assert!(
+                matches!(
+                    registry.register_description(key, description.clone()),
+                    Err(CodeError::InvalidParameter { .. })
+                ),
+                "{:?}",
+                key
+            );
+        }
+        // The built-in families are untouched
+        assert_eq!(registry.get("surface_d3").unwrap().key(), "surface:d=3");
+
+        registry
+            .register_description("my_steane", description.clone())
+            .unwrap();
+        assert!(registry
+            .register_description("my_steane", description)
+            .is_err());
+    }
+
+    #[test]
+    fn test_alist_roundtrip() {
+        let (n, rows) = parse_alist(HAMMING_ALIST).unwrap();
+        assert_eq!(n, 7);
+        assert_eq!(rows[0], vec![0, 2, 4, 6]);
+        assert_eq!(write_alist(&rows, n), HAMMING_ALIST);
+
+        // Hamming(7,4) for both H_X and H_Z gives the Steane code
+        let steane =
+            CodeDescription::from_css_alist("steane", HAMMING_ALIST, HAMMING_ALIST).unwrap();
+        assert_eq!(
+            (
+                steane.info.n_qubits,
+                steane.info.n_logical,
+                steane.info.distance
+            ),
+            (7, 1, 3)
+        );
+        let (hx, hz) = steane.to_css_alist().unwrap().unwrap();
+        assert_eq!(parse_alist(&hx).unwrap().1, rows);
+        assert_eq!(parse_alist(&hz).unwrap().1, rows);
+
+        let five = CodeDescription::from_registry(&CodeRegistry::builtin(), "five_qubit").unwrap();
+        assert_eq!(five.to_css_alist().unwrap(), None);
+    }
+
+    #[test]
+    fn test_alist_errors() {
+        assert!(parse_alist("").is_err());
+        let mismatched = HAMMING_ALIST.replace("1 3 5 7", "1 3 5 6");
+        assert!(matches!(
+            parse_alist(&mismatched),
+            Err(CodeError::InvalidFile { .. })
+        ));
+    }
+}
@@ -1,69 +1,204 @@
-use crate::pauli::PauliString;
+use crate::automorphism::logical_gates;
+use crate::distance::{self, DistanceReport};
+use crate::pauli::{Pauli, PauliString};
+use crate::registry::with_registry;
 use crate::stabilizer::StabilizerState;
+use crate::symplectic;
+use crate::validation::{validate_code, CodeError};
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
 
-/// Predefined quantum error correction codes
+// Predefined quantum error correction codes
+
+/// Stabilizer code described by its generators and logical operators
+#[derive(Debug, Clone)]
+pub struct StabilizerCode {
+    /// Number of physical qubits
+    pub n_qubits: usize,
+    /// Stabilizer generators (n - k of them)
+    pub generators: Vec<PauliString>,
+    /// Logical X operators, one per logical qubit
+    pub logical_x: Vec<PauliString>,
+    /// Logical Z operators, paired with `logical_x`
+    pub logical_z: Vec<PauliString>,
+    /// Code distance, computed by `distance::compute_distance`
+    pub distance: usize,
+}
+
+impl StabilizerCode {
+    /// Create a code, validate it and compute its distance
+    pub fn new(
+        n_qubits: usize,
+        generators: Vec<PauliString>,
+        logical_x: Vec<PauliString>,
+        logical_z: Vec<PauliString>,
+    ) -> Result<Self, CodeError> {
+        let mut code = StabilizerCode {
+            n_qubits,
+            generators,
+            logical_x,
+            logical_z,
+            distance: 0,
+        };
+        validate_code(&code)?;
+        code.distance = code.distance_report().distance;
+        Ok(code)
+    }
+
+    /// Create and validate a code whose distance is known analytically,
+    /// skipping the distance search
+    /// The distance may not exceed the weight of any logical representative.
+    pub fn with_distance(
+        n_qubits: usize,
+        generators: Vec<PauliString>,
+        logical_x: Vec<PauliString>,
+        logical_z: Vec<PauliString>,
+        distance: usize,
+    ) -> Result<Self, CodeError> {
+        let code = StabilizerCode {
+            n_qubits,
+            generators,
+            logical_x,
+            logical_z,
+            distance,
+        };
+        validate_code(&code)?;
+        let lightest = code
+            .logical_x
+            .iter()
+            .chain(&code.logical_z)
+            .map(PauliString::weight)
+            .min();
+        if let Some(weight) = lightest.filter(|&w| w < distance) {
+            return Err(CodeError::InfoMismatch {
+                field: "distance".to_string(),
+                claimed: distance,
+                computed: weight,
+            });
+        }
+        Ok(code)
+    }
+
+    /// Create a code from its generators alone, computing a symplectic basis
+    /// of logical operators
+    pub fn from_generators(
+        n_qubits: usize,
+        generators: Vec<PauliString>,
+    ) -> Result<Self, CodeError> {
+        let (logical_x, logical_z) = symplectic::logical_basis(&generators, n_qubits);
+        StabilizerCode::new(n_qubits, generators, logical_x, logical_z)
+    }
+
+    /// Distance of the code, with X and Z distances for CSS codes
+    pub fn distance_report(&self) -> DistanceReport {
+        distance::compute_distance(self)
+    }
+
+    /// Number of encoded logical qubits, k = n - rank(S)
+    pub fn n_logical(&self) -> usize {
+        self.n_qubits - symplectic::rank(&self.generators)
+    }
+
+    /// Compute a symplectic basis of logical operators (X̄_i, Z̄_i) from the
+    /// generators, independent of the operators stored in the code
+    pub fn compute_logical_operators(&self) -> (Vec<PauliString>, Vec<PauliString>) {
+        symplectic::logical_basis(&self.generators, self.n_qubits)
+    }
+
+    /// Syndrome of a Pauli error: `true` for each generator it anticommutes with
+    pub fn syndrome_of(&self, error: &PauliString) -> Vec<bool> {
+        self.generators
+            .iter()
+            .map(|g| !g.commutes_with(error))
+            .collect()
+    }
+
+    /// Stabilizer state of the logical |0...0⟩ codeword
+    /// The tableau holds the generators followed by the logical Z operators
+    pub fn to_state(&self) -> StabilizerState {
+        let mut state = StabilizerState::new(self.n_qubits);
+        for (i, stab) in self.generators.iter().chain(&self.logical_z).enumerate() {
+            state.set_stabilizer(i, stab);
+        }
+        state
+    }
+}
+
+fn paulis(strings: &[&str]) -> Result<Vec<PauliString>, CodeError> {
+    strings
+        .iter()
+        .map(|s| {
+            PauliString::from_str(s).ok_or_else(|| CodeError::InvalidPauliString(s.to_string()))
+        })
+        .collect()
+}
 
 /// Create a 3-qubit bit-flip repetition code
 /// Encodes 1 logical qubit into 3 physical qubits
 /// Stabilizers: Z0Z1, Z1Z2
 /// Logical operators: X0X1X2, Z0Z1Z2
-pub fn repetition_code_3() -> StabilizerState {
-    let stabilizers = vec![
-        PauliString::from_str("ZZI").unwrap(),
-        PauliString::from_str("IZZ").unwrap(),
-    ];
-
-    // Note: This is a distance-3 code, but only detects 1 bit flip
-    // We initialize with 2 stabilizers for 3 qubits (encodes 1 logical qubit)
-    let mut state = StabilizerState::new(3);
-    state.set_stabilizer(0, &stabilizers[0]);
-    state.set_stabilizer(1, &stabilizers[1]);
+/// Shorthand for `repetition_code(3)`, which the registry also builds as
+/// "repetition_3", used throughout the tests
+#[cfg(test)]
+pub fn repetition_code_3() -> Result<StabilizerCode, CodeError> {
+    repetition_code(3)
+}
 
-    state
+/// Create an n-qubit bit-flip repetition code
+/// Stabilizers: Z_i Z_{i+1}
+/// Logical operators: X on every qubit, Z on every qubit
+/// Corrects (n - 1) / 2 bit flips but no phase flip, so the distance is 1
+pub fn repetition_code(n: usize) -> Result<StabilizerCode, CodeError> {
+    if n < 2 {
+        return Err(CodeError::invalid_parameter(
+            "repetition",
+            "n",
+            "must be at least 2",
+        ));
+    }
+    let generators = (0..n - 1)
+        .map(|i| css_operator(n, &[i, i + 1], Pauli::Z))
+        .collect();
+    let all: Vec<usize> = (0..n).collect();
+    StabilizerCode::with_distance(
+        n,
+        generators,
+        vec![css_operator(n, &all, Pauli::X)],
+        vec![css_operator(n, &all, Pauli::Z)],
+        1,
+    )
 }
 
 /// Create a 5-qubit perfect code
 /// Smallest code that can correct any single-qubit error
 /// Stabilizers: XZZXI, IXZZX, XIXZZ, ZXIXZ
-pub fn five_qubit_code() -> StabilizerState {
-    let stabilizers = vec![
-        PauliString::from_str("XZZXI").unwrap(),
-        PauliString::from_str("IXZZX").unwrap(),
-        PauliString::from_str("XIXZZ").unwrap(),
-        PauliString::from_str("ZXIXZ").unwrap(),
-    ];
-
-    let mut state = StabilizerState::new(5);
-    for (i, stab) in stabilizers.iter().enumerate() {
-        state.set_stabilizer(i, stab);
-    }
-
-    state
+/// Logical operators: XXXXX, ZZZZZ
+pub fn five_qubit_code() -> Result<StabilizerCode, CodeError> {
+    StabilizerCode::new(
+        5,
+        paulis(&["XZZXI", "IXZZX", "XIXZZ", "ZXIXZ"])?,
+        paulis(&["XXXXX"])?,
+        paulis(&["ZZZZZ"])?,
+    )
 }
 
 /// Create a Steane code (7-qubit code)
 /// Can correct any single-qubit error
 /// Stabilizers are generators of the code space
-pub fn steane_code() -> StabilizerState {
-    let stabilizers = vec![
-        PauliString::from_str("IIIXXXX").unwrap(),
-        PauliString::from_str("IXXIIXX").unwrap(),
-        PauliString::from_str("XIXIXIX").unwrap(),
-        PauliString::from_str("IIIZZZZ").unwrap(),
-        PauliString::from_str("IZZIIZZ").unwrap(),
-        PauliString::from_str("ZIZIZIZ").unwrap(),
-    ];
-
-    let mut state = StabilizerState::new(7);
-    for (i, stab) in stabilizers.iter().enumerate() {
-        state.set_stabilizer(i, stab);
-    }
-
-    state
+/// Logical operators: transversal X and Z
+pub fn steane_code() -> Result<StabilizerCode, CodeError> {
+    StabilizerCode::new(
+        7,
+        paulis(&[
+            "IIIXXXX", "IXXIIXX", "XIXIXIX", "IIIZZZZ", "IZZIIZZ", "ZIZIZIZ",
+        ])?,
+        paulis(&["XXXXXXX"])?,
+        paulis(&["ZZZZZZZ"])?,
+    )
 }
 
-/// Create a distance-3 surface code (9 qubits)
+/// Create a distance-3 rotated surface code (9 qubits)
 /// Arranged in a 3x3 grid:
 ///  0 - 1 - 2
 ///  |   |   |
@@ -71,86 +206,266 @@ pub fn steane_code() -> StabilizerState {
 ///  |   |   |
 ///  6 - 7 - 8
 ///
-/// 4 X-type stabilizers (on faces) and 4 Z-type stabilizers (on vertices)
-pub fn surface_code_d3() -> StabilizerState {
-    // X stabilizers (plaquettes)
-    let stabilizers = vec![
-        PauliString::from_str("XXIIIIIII").unwrap(), // 0-1
-        PauliString::from_str("IXIIXIIII").unwrap(), // 1-2-4
-        PauliString::from_str("IIIXXXXII").unwrap(), // 3-4-6-7
-        PauliString::from_str("IIIIIIXIX").unwrap(), // 5-7-8
-        // Z stabilizers (vertices)
-        PauliString::from_str("ZIIZIIIII").unwrap(), // 0-3
-        PauliString::from_str("IZIZIZIII").unwrap(), // 1-3-4-6
-        PauliString::from_str("IIZIIIZIZ").unwrap(), // 2-4-5-7
-        PauliString::from_str("IIIIIIZZI").unwrap(), // 5-8
-    ];
+/// Weight-4 plaquettes alternate between X and Z type, weight-2 X checks
+/// sit on the top and bottom boundaries and weight-2 Z checks on the left
+/// and right boundaries
+/// Logical operators: X on the left column, Z on the top row
+/// Shorthand for `rotated_surface_code(3)`, which the registry also builds
+/// as "surface_d3", used throughout the tests
+#[cfg(test)]
+pub fn surface_code_d3() -> Result<StabilizerCode, CodeError> {
+    rotated_surface_code(3)
+}
+
+/// Stabilizer plaquette of the rotated surface code
+/// Face (i, j) has data qubits (i, j), (i, j+1), (i+1, j) and (i+1, j+1) as
+/// corners, where rows and columns -1 and d-1 are half outside the grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SurfacePlaquette {
+    pub pauli: Pauli,
+    pub face: (isize, isize),
+    pub support: Vec<usize>,
+}
+
+/// Plaquettes of the distance-d rotated surface code, X type first, each
+/// type in row-major face order
+pub fn surface_plaquettes(d: usize) -> Vec<SurfacePlaquette> {
+    rectangular_surface_plaquettes(d, d)
+}
+
+/// Plaquettes of a rotated surface code patch on a `rows` x `cols` grid,
+/// qubit index `row * cols + col`
+/// Face (i, j) is X type when i + j is odd; weight-2 faces are kept only if
+/// they are X type on the top/bottom boundary or Z type on the left/right.
+pub fn rectangular_surface_plaquettes(rows: usize, cols: usize) -> Vec<SurfacePlaquette> {
+    let (height, width) = (rows as isize, cols as isize);
+    let mut x_faces = Vec::new();
+    let mut z_faces = Vec::new();
+    for i in -1..height {
+        for j in -1..width {
+            let support: Vec<usize> = [(i, j), (i, j + 1), (i + 1, j), (i + 1, j + 1)]
+                .iter()
+                .filter(|(r, c)| (0..height).contains(r) && (0..width).contains(c))
+                .map(|&(r, c)| (r * width + c) as usize)
+                .collect();
+            let pauli = if (i + j).rem_euclid(2) == 1 {
+                Pauli::X
+            } else {
+                Pauli::Z
+            };
+            let keep = match support.len() {
+                4 => true,
+                2 if i == -1 || i == height - 1 => pauli == Pauli::X,
+                2 => pauli == Pauli::Z,
+                _ => false,
+            };
+            if keep {
+                let plaquette = SurfacePlaquette {
+                    pauli,
+                    face: (i, j),
+                    support,
+                };
+                match pauli {
+                    Pauli::X => x_faces.push(plaquette),
+                    _ => z_faces.push(plaquette),
+                }
+            }
+        }
+    }
+    x_faces.extend(z_faces);
+    x_faces
+}
+
+/// Create a distance-d rotated surface code on a d x d grid of data qubits,
+/// qubit index `row * d + col`
+/// Logical operators: X on the left column, Z on the top row
+pub fn rotated_surface_code(d: usize) -> Result<StabilizerCode, CodeError> {
+    if d < 2 {
+        return Err(CodeError::invalid_parameter(
+            "surface",
+            "d",
+            "must be at least 2",
+        ));
+    }
+    let n = d * d;
+    let generators = surface_plaquettes(d)
+        .iter()
+        .map(|p| css_operator(n, &p.support, p.pauli))
+        .collect();
+    let left: Vec<usize> = (0..d).map(|r| r * d).collect();
+    let top: Vec<usize> = (0..d).collect();
+    StabilizerCode::with_distance(
+        n,
+        generators,
+        vec![css_operator(n, &left, Pauli::X)],
+        vec![css_operator(n, &top, Pauli::Z)],
+        d,
+    )
+}
+
+/// Monomial x^a y^b of a bivariate bicycle polynomial, stored as (a, b)
+pub type Monomial = (usize, usize);
+
+/// All 2lm checks of the bivariate bicycle code with polynomials A and B
+/// in x = S_l ⊗ I_m, y = I_l ⊗ S_m (Bravyi et al. 2024)
+/// H_X = [A | B] and H_Z = [B^T | A^T] on n = 2lm qubits: left qubits
+/// `i * m + j` and right qubits `l * m + i * m + j` for the monomial x^i y^j.
+/// Check `i * m + j` is row x^i y^j of H_X, check `l * m + i * m + j` the
+/// same row of H_Z. The checks are not independent.
+pub fn bivariate_bicycle_checks(
+    l: usize,
+    m: usize,
+    a: &[Monomial],
+    b: &[Monomial],
+) -> Vec<PauliString> {
+    let block = l * m;
+    let n = 2 * block;
+    let shift = |index: usize, (da, db): Monomial, sign: bool| {
+        let (i, j) = (index / m, index % m);
+        let (i, j) = if sign {
+            ((i + da) % l, (j + db) % m)
+        } else {
+            ((i + l - da % l) % l, (j + m - db % m) % m)
+        };
+        i * m + j
+    };
+    // Row r of a polynomial matrix P has ones at r shifted by each monomial;
+    // row r of P^T has ones at r shifted back
+    let row = |r: usize, poly: &[Monomial], transpose: bool, offset: usize, bits: &mut [bool]| {
+        for &mono in poly {
+            bits[offset + shift(r, mono, !transpose)] ^= true;
+        }
+    };
 
-    let mut state = StabilizerState::new(9);
-    for (i, stab) in stabilizers.iter().enumerate() {
-        state.set_stabilizer(i, stab);
+    let mut checks = Vec::with_capacity(n);
+    for (pauli, left, right, transpose) in [(Pauli::X, a, b, false), (Pauli::Z, b, a, true)] {
+        for r in 0..block {
+            let mut support = vec![false; n];
+            row(r, left, transpose, 0, &mut support);
+            row(r, right, transpose, block, &mut support);
+            let qubits: Vec<usize> = (0..n).filter(|&q| support[q]).collect();
+            checks.push(css_operator(n, &qubits, pauli));
+        }
     }
+    checks
+}
 
-    state
+/// Independent stabilizer generators of the bivariate bicycle code: the
+/// checks with redundant ones dropped
+pub fn bivariate_bicycle_generators(
+    l: usize,
+    m: usize,
+    a: &[Monomial],
+    b: &[Monomial],
+) -> Vec<PauliString> {
+    symplectic::independent_subset(&bivariate_bicycle_checks(l, m, a, b))
 }
 
-/// Get a code by name
-pub fn get_code_by_name(name: &str) -> Option<StabilizerState> {
-    match name {
-        "repetition_3" => Some(repetition_code_3()),
-        "five_qubit" => Some(five_qubit_code()),
-        "steane" => Some(steane_code()),
-        "surface_d3" => Some(surface_code_d3()),
-        _ => None,
+/// Pauli string with `pauli` on every qubit in `support`
+pub fn css_operator(n_qubits: usize, support: &[usize], pauli: Pauli) -> PauliString {
+    let mut op = PauliString::identity(n_qubits);
+    for &q in support {
+        op.paulis[q] = pauli;
     }
+    op
+}
+
+/// Get a code by registry key, e.g. "steane" or "surface:d=5"
+pub fn get_stabilizer_code(name: &str) -> Result<StabilizerCode, CodeError> {
+    with_registry(|registry| registry.get(name))?.stabilizer_code()
 }
 
 /// Get metadata about a code
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CodeInfo {
     pub name: String,
+    #[serde(default)]
     pub description: String,
     pub n_qubits: usize,
     pub n_logical: usize,
     pub distance: usize,
+    /// X and Z distances, reported for CSS codes
+    pub x_distance: Option<usize>,
+    pub z_distance: Option<usize>,
+    /// False when the distances are upper bounds from a randomized search
+    #[serde(default = "exact_by_default")]
+    pub distance_exact: bool,
+    /// Logical gates implemented by automorphisms, e.g. "transversal H";
+    /// None until `get_logical_gates` has searched the code, and for codes
+    /// too large to search
+    #[serde(default)]
+    pub logical_gates: Option<Vec<String>>,
+}
+
+fn exact_by_default() -> bool {
+    true
+}
+
+/// Metadata of a registered code, validated against the constructed code
+/// `logical_gates` is filled in only once `get_logical_gates` has searched
+/// the code, since the search is too slow to run on every lookup.
+pub fn get_code_info(name: &str) -> Result<CodeInfo, CodeError> {
+    let entry = with_registry(|registry| registry.get(name))?;
+    let code = entry.stabilizer_code()?;
+    let mut info = entry.info();
+    info.logical_gates = LOGICAL_GATES
+        .with(|cache| cache.borrow().get(&gate_cache_key(&code)).cloned())
+        .flatten();
+    Ok(info)
+}
+
+thread_local! {
+    /// Logical gates of the codes searched so far, keyed by their generators,
+    /// with None for codes too large to search
+    /// The automorphism search takes up to a second in debug builds.
+    static LOGICAL_GATES: RefCell<HashMap<Vec<String>, Option<Vec<String>>>> =
+        RefCell::new(HashMap::new());
+}
+
+fn gate_cache_key(code: &StabilizerCode) -> Vec<String> {
+    code.generators.iter().map(|g| g.to_string()).collect()
+}
+
+/// Logical gates implemented by automorphisms of a registered code, e.g.
+/// "transversal H" or "permutation-transversal H", or None if the code is
+/// too large for the automorphism search
+pub fn get_logical_gates(name: &str) -> Result<Option<Vec<String>>, CodeError> {
+    let code = get_stabilizer_code(name)?;
+    let key = gate_cache_key(&code);
+    if let Some(gates) = LOGICAL_GATES.with(|cache| cache.borrow().get(&key).cloned()) {
+        return Ok(gates);
+    }
+    // The search only fails on codes beyond its qubit and group size limits
+    let gates = logical_gates(&code)
+        .ok()
+        .map(|gates| gates.iter().map(|g| g.to_string()).collect());
+    LOGICAL_GATES.with(|cache| cache.borrow_mut().insert(key, gates.clone()));
+    Ok(gates)
+}
+
+/// Logical operators of a code, serialized for the demo page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogicalOperators {
+    pub x: Vec<String>,
+    pub z: Vec<String>,
+}
+
+impl LogicalOperators {
+    pub fn of(code: &StabilizerCode) -> Self {
+        LogicalOperators {
+            x: code.logical_x.iter().map(|l| l.to_string()).collect(),
+            z: code.logical_z.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+}
+
+pub fn get_logical_operators(name: &str) -> Result<LogicalOperators, CodeError> {
+    get_stabilizer_code(name).map(|code| LogicalOperators::of(&code))
 }
 
-pub fn get_code_info(name: &str) -> Option<CodeInfo> {
-    match name {
-        "repetition_3" => Some(CodeInfo {
-            name: "3-qubit Repetition Code".to_string(),
-            description: "Bit-flip code, detects 1 X error".to_string(),
-            n_qubits: 3,
-            n_logical: 1,
-            distance: 3,
-        }),
-        "five_qubit" => Some(CodeInfo {
-            name: "5-qubit Perfect Code".to_string(),
-            description: "Smallest code correcting any single-qubit error".to_string(),
-            n_qubits: 5,
-            n_logical: 1,
-            distance: 3,
-        }),
-        "steane" => Some(CodeInfo {
-            name: "Steane Code".to_string(),
-            description: "7-qubit CSS code, corrects any single error".to_string(),
-            n_qubits: 7,
-            n_logical: 1,
-            distance: 3,
-        }),
-        "surface_d3" => Some(CodeInfo {
-            name: "Surface Code (d=3)".to_string(),
-            description: "9-qubit surface code on 3x3 grid".to_string(),
-            n_qubits: 9,
-            n_logical: 1,
-            distance: 3,
-        }),
-        _ => None,
-    }
-}
-
-pub fn available_codes() -> Vec<&'static str> {
-    vec!["repetition_3", "five_qubit", "steane", "surface_d3"]
+/// Example keys of every registered code family, including loaded codes
+pub fn available_codes() -> Vec<String> {
+    with_registry(|registry| registry.available())
 }
 
 #[cfg(test)]
@@ -159,7 +474,7 @@ mod tests {
 
     #[test]
     fn test_repetition_code() {
-        let state = repetition_code_3();
+        let state = repetition_code_3().unwrap().to_state();
         let stabs = state.get_all_stabilizers();
 
         assert_eq!(stabs.len(), 3);
@@ -169,7 +484,7 @@ mod tests {
 
     #[test]
     fn test_five_qubit_code() {
-        let state = five_qubit_code();
+        let state = five_qubit_code().unwrap().to_state();
         let stabs = state.get_all_stabilizers();
 
         assert_eq!(stabs.len(), 5);
@@ -181,11 +496,101 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_surface_code_d3_logicals() {
+        let code = surface_code_d3().unwrap();
+        for stab in &code.generators {
+            assert!(stab.commutes_with(&code.logical_x[0]));
+            assert!(stab.commutes_with(&code.logical_z[0]));
+        }
+        assert!(!code.logical_x[0].commutes_with(&code.logical_z[0]));
+    }
+
+    #[test]
+    fn test_computed_logicals_match_code() {
+        for code_name in available_codes() {
+            let code = get_stabilizer_code(&code_name).unwrap();
+            let (lx, lz) = code.compute_logical_operators();
+            assert_eq!(lx.len(), code.n_logical());
+            assert_eq!(lz.len(), code.n_logical());
+            assert_eq!(code.n_logical(), code.logical_x.len());
+
+            // Computed and stored logicals agree up to stabilizers
+            let mut with_z = code.generators.clone();
+            with_z.extend(lz.iter().cloned());
+            assert!(symplectic::is_in_span(&with_z, &code.logical_z[0]));
+            let mut with_x = code.generators.clone();
+            with_x.extend(lx.iter().cloned());
+            assert!(symplectic::is_in_span(&with_x, &code.logical_x[0]));
+        }
+    }
+
+    #[test]
+    fn test_code_info_distances() {
+        let info = get_code_info("repetition_3").unwrap();
+        assert_eq!(info.distance, 1);
+        assert_eq!(info.x_distance, Some(3));
+        assert_eq!(info.z_distance, Some(1));
+
+        for code_name in ["five_qubit", "steane", "surface_d3"] {
+            assert_eq!(get_code_info(code_name).unwrap().distance, 3);
+        }
+    }
+
+    #[test]
+    fn test_claimed_distance_bounded_by_logicals() {
+        let steane = steane_code().unwrap();
+        let build = |distance| {
+            StabilizerCode::with_distance(
+                7,
+                steane.generators.clone(),
+                steane.logical_x.clone(),
+                steane.logical_z.clone(),
+                distance,
+            )
+        };
+        assert!(build(3).is_ok());
+        // X̄ = XXXXXXX and Z̄ = ZZZZZZZ cap the claim at 7
+        assert!(build(7).is_ok());
+        assert_eq!(
+            build(8).unwrap_err(),
+            CodeError::InfoMismatch {
+                field: "distance".to_string(),
+                claimed: 8,
+                computed: 7
+            }
+        );
+    }
+
+    #[test]
+    fn test_logical_gates() {
+        assert_eq!(get_code_info("steane").unwrap().logical_gates, None);
+        let steane = get_logical_gates("steane").unwrap().unwrap();
+        assert!(steane.contains(&"transversal H".to_string()));
+        // Served from the cache the second time, and from then on in the info
+        assert_eq!(get_logical_gates("steane").unwrap(), Some(steane.clone()));
+        assert_eq!(get_code_info("steane").unwrap().logical_gates, Some(steane));
+        let five = get_logical_gates("five_qubit").unwrap().unwrap();
+        assert!(five.contains(&"transversal C_XYZ".to_string()));
+        // Too large to search, which is not the same as having no gates
+        assert_eq!(get_logical_gates("surface:d=5").unwrap(), None);
+        assert_eq!(get_code_info("surface:d=5").unwrap().logical_gates, None);
+        assert!(get_logical_gates("toric").is_err());
+    }
+
+    #[test]
+    fn test_unknown_code() {
+        assert_eq!(
+            get_stabilizer_code("toric").unwrap_err(),
+            CodeError::UnknownCode("toric".to_string())
+        );
+    }
+
     #[test]
     fn test_all_codes_available() {
         for code_name in available_codes() {
-            assert!(get_code_by_name(code_name).is_some());
-            assert!(get_code_info(code_name).is_some());
+            assert!(get_stabilizer_code(&code_name).is_ok());
+            assert!(get_code_info(&code_name).is_ok());
         }
     }
 }
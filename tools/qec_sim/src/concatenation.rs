@@ -0,0 +1,279 @@
+#![allow(dead_code)]
+use crate::codes::StabilizerCode;
+use crate::decoder::Decoder;
+use crate::pauli::{Pauli, PauliString, Phase};
+use crate::validation::{validate_code, CodeError};
+
+/// Code obtained by encoding every physical qubit of an outer code with an
+/// inner code
+///
+/// Physical qubits are grouped in blocks of `inner.n_qubits`; block `b`
+/// carries outer qubits `b * k_in .. (b + 1) * k_in`.
+/// The generators of `code` are the inner generators of block 0, block 1, ...
+/// followed by the outer generators lifted through the inner logical operators.
+#[derive(Debug, Clone)]
+pub struct ConcatenatedCode {
+    pub outer: StabilizerCode,
+    pub inner: StabilizerCode,
+    pub code: StabilizerCode,
+}
+
+/// Concatenate `outer` with `inner`
+/// Fails if the inner code encodes no qubit or if the outer qubits cannot be
+/// split evenly into inner blocks
+pub fn concatenate(
+    outer: &StabilizerCode,
+    inner: &StabilizerCode,
+) -> Result<ConcatenatedCode, CodeError> {
+    let k_in = inner.n_logical();
+    if k_in == 0 || !outer.n_qubits.is_multiple_of(k_in) {
+        return Err(CodeError::IncompatibleConcatenation {
+            outer_qubits: outer.n_qubits,
+            inner_logical: k_in,
+        });
+    }
+
+    let n_blocks = outer.n_qubits / k_in;
+    let n = n_blocks * inner.n_qubits;
+
+    let mut concatenated = ConcatenatedCode {
+        outer: outer.clone(),
+        inner: inner.clone(),
+        code: StabilizerCode {
+            n_qubits: n,
+            generators: Vec::new(),
+            logical_x: Vec::new(),
+            logical_z: Vec::new(),
+            distance: 0,
+        },
+    };
+
+    let mut generators = Vec::new();
+    for block in 0..n_blocks {
+        for g in &inner.generators {
+            generators.push(g.embed(n, block * inner.n_qubits));
+        }
+    }
+    generators.extend(outer.generators.iter().map(|g| concatenated.lift(g)));
+
+    // Computing the distance of a large concatenated code is expensive, so
+    // the code carries the product lower bound instead
+    concatenated.code = StabilizerCode {
+        n_qubits: n,
+        generators,
+        logical_x: outer
+            .logical_x
+            .iter()
+            .map(|l| concatenated.lift(l))
+            .collect(),
+        logical_z: outer
+            .logical_z
+            .iter()
+            .map(|l| concatenated.lift(l))
+            .collect(),
+        distance: distance_lower_bound(outer.distance, inner.distance, k_in),
+    };
+
+    validate_code(&concatenated.code)?;
+    Ok(concatenated)
+}
+
+/// A logical error of the concatenated code must hit at least d_out outer
+/// qubits, i.e. ceil(d_out / k_in) inner blocks, each with weight >= d_in
+fn distance_lower_bound(d_out: usize, d_in: usize, k_in: usize) -> usize {
+    d_out.div_ceil(k_in) * d_in
+}
+
+impl ConcatenatedCode {
+    /// Number of inner code blocks
+    pub fn n_blocks(&self) -> usize {
+        self.outer.n_qubits / self.inner.n_logical()
+    }
+
+    /// Number of physical qubits (n)
+    pub fn n_qubits(&self) -> usize {
+        self.code.n_qubits
+    }
+
+    /// Number of logical qubits (k)
+    pub fn n_logical(&self) -> usize {
+        self.code.n_logical()
+    }
+
+    /// Lower bound on the code distance (d)
+    pub fn distance(&self) -> usize {
+        self.code.distance
+    }
+
+    /// Map an operator on the outer code's qubits to physical qubits by
+    /// replacing each outer X/Y/Z with the corresponding inner logical operator
+    pub fn lift(&self, outer_op: &PauliString) -> PauliString {
+        let k_in = self.inner.n_logical();
+        let n_in = self.inner.n_qubits;
+        let n = self.n_blocks() * n_in;
+
+        let mut result = PauliString::identity(n);
+        result.phase = outer_op.phase;
+
+        for (q, pauli) in outer_op.paulis.iter().enumerate() {
+            let (block, j) = (q / k_in, q % k_in);
+            let logical = match pauli {
+                Pauli::I => continue,
+                Pauli::X => self.inner.logical_x[j].clone(),
+                Pauli::Z => self.inner.logical_z[j].clone(),
+                // Y = iXZ
+                Pauli::Y => {
+                    let mut y = self.inner.logical_x[j].multiply(&self.inner.logical_z[j]);
+                    y.phase = y.phase.multiply(Phase::PlusI);
+                    y
+                }
+            };
+            result = result.multiply(&logical.embed(n, block * n_in));
+        }
+
+        result
+    }
+
+    /// Hierarchical decoding
+    /// Each block is first decoded by `inner` using its own part of the
+    /// syndrome. The outer syndrome is then corrected for the inner
+    /// corrections and decoded by `outer` (a decoder for the outer code),
+    /// whose correction is lifted back to physical qubits.
+    /// Fails if the syndrome has the wrong length or a decoder returns a
+    /// correction on the wrong number of qubits.
+    pub fn decode_hierarchical(
+        &self,
+        syndrome: &[bool],
+        inner: &dyn Decoder,
+        outer: &dyn Decoder,
+    ) -> Result<PauliString, CodeError> {
+        let n = self.n_qubits();
+        let m_in = self.inner.generators.len();
+        let n_inner_checks = self.n_blocks() * m_in;
+        if syndrome.len() != self.code.generators.len() {
+            return Err(CodeError::invalid_parameter(
+                "concatenated",
+                "syndrome",
+                format!(
+                    "expected {} bits, found {}",
+                    self.code.generators.len(),
+                    syndrome.len()
+                ),
+            ));
+        }
+
+        let mut correction = PauliString::identity(n);
+        for block in 0..self.n_blocks() {
+            let block_syndrome = &syndrome[block * m_in..(block + 1) * m_in];
+            let block_correction = inner.decode(block_syndrome);
+            check_qubit_count(&block_correction, self.inner.n_qubits)?;
+            correction =
+                correction.multiply(&block_correction.embed(n, block * self.inner.n_qubits));
+        }
+
+        let outer_syndrome: Vec<bool> = self.code.generators[n_inner_checks..]
+            .iter()
+            .zip(&syndrome[n_inner_checks..])
+            .map(|(g, &s)| s ^ !g.commutes_with(&correction))
+            .collect();
+        let outer_correction = outer.decode(&outer_syndrome);
+        check_qubit_count(&outer_correction, self.outer.n_qubits)?;
+
+        correction = correction.multiply(&self.lift(&outer_correction));
+        correction.phase = Phase::Plus;
+        Ok(correction)
+    }
+}
+
+/// Check that a decoder's correction acts on `expected` qubits
+fn check_qubit_count(correction: &PauliString, expected: usize) -> Result<(), CodeError> {
+    if correction.n_qubits() == expected {
+        Ok(())
+    } else {
+        Err(CodeError::QubitCountMismatch {
+            operator: correction.to_string(),
+            expected,
+            found: correction.n_qubits(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{five_qubit_code, repetition_code_3, steane_code};
+    use crate::decoder::LookupDecoder;
+
+    fn is_logical_error(code: &StabilizerCode, residual: &PauliString) -> bool {
+        code.logical_x
+            .iter()
+            .chain(&code.logical_z)
+            .any(|l| !l.commutes_with(residual))
+    }
+
+    #[test]
+    fn test_steane_steane_parameters() {
+        let c = concatenate(&steane_code().unwrap(), &steane_code().unwrap()).unwrap();
+        assert_eq!(c.n_qubits(), 49);
+        assert_eq!(c.n_logical(), 1);
+        assert_eq!(c.distance(), 9);
+        assert_eq!(c.code.generators.len(), 48);
+
+        let stabs = &c.code.generators;
+        for i in 0..stabs.len() {
+            for j in 0..stabs.len() {
+                assert!(stabs[i].commutes_with(&stabs[j]));
+            }
+            assert!(stabs[i].commutes_with(&c.code.logical_x[0]));
+            assert!(stabs[i].commutes_with(&c.code.logical_z[0]));
+        }
+        assert!(!c.code.logical_x[0].commutes_with(&c.code.logical_z[0]));
+    }
+
+    #[test]
+    fn test_repetition_five_qubit() {
+        let c = concatenate(&repetition_code_3().unwrap(), &five_qubit_code().unwrap()).unwrap();
+        assert_eq!(c.n_qubits(), 15);
+        assert_eq!(c.n_logical(), 1);
+        assert_eq!(c.code.generators.len(), 14);
+        for g in &c.code.generators {
+            assert_eq!(g.phase, Phase::Plus);
+            for h in &c.code.generators {
+                assert!(g.commutes_with(h));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hierarchical_decoding() {
+        let steane = steane_code().unwrap();
+        let c = concatenate(&steane, &steane).unwrap();
+        let inner = LookupDecoder::new(&steane, 1);
+        let outer = LookupDecoder::new(&steane, 1);
+
+        // Two X errors in block 0 defeat the inner decoder, the outer level
+        // repairs the resulting logical error; block 3 is fixed locally
+        let mut error = PauliString::identity(49);
+        error.paulis[0] = Pauli::X;
+        error.paulis[1] = Pauli::X;
+        error.paulis[3 * 7 + 4] = Pauli::Z;
+
+        let correction = c
+            .decode_hierarchical(&c.code.syndrome_of(&error), &inner, &outer)
+            .unwrap();
+        let residual = error.multiply(&correction);
+        assert!(c.code.syndrome_of(&residual).iter().all(|&s| !s));
+        assert!(!is_logical_error(&c.code, &residual));
+
+        // Malformed syndromes and decoders for the wrong code are rejected
+        assert!(matches!(
+            c.decode_hierarchical(&[false; 3], &inner, &outer),
+            Err(CodeError::InvalidParameter { .. })
+        ));
+        let wrong = LookupDecoder::new(&five_qubit_code().unwrap(), 1);
+        assert!(matches!(
+            c.decode_hierarchical(&c.code.syndrome_of(&error), &wrong, &outer),
+            Err(CodeError::QubitCountMismatch { .. })
+        ));
+    }
+}
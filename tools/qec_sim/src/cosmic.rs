@@ -0,0 +1,288 @@
+#![allow(dead_code)]
+// Radiation-induced burst errors
+//
+// A cosmic ray or gamma impact briefly raises the error rate of every qubit
+// in a disk around the impact point; the excess decays exponentially over
+// many rounds as the quasiparticles recombine. Impacts arrive at random
+// rounds and land uniformly on the bounding box of the code layout.
+use crate::codes::StabilizerCode;
+use crate::decoder::Decoder;
+use crate::error::{Error, NoiseError, PauliChannel};
+use crate::layout::{Layout, Point};
+use crate::memory::{run_shot, RoundNoise};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Depolarizing strength of a maximally mixed qubit
+const MAX_DEPOLARIZING: f64 = 0.75;
+
+/// Burst error parameters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CosmicRayModel {
+    /// Depolarizing probability per qubit and round without impacts
+    pub background: f64,
+    /// Probability of an impact in each round
+    pub impact_rate: f64,
+    /// Radius of the affected disk in lattice spacings
+    pub radius: f64,
+    /// Extra depolarizing probability in the disk in the round of the impact
+    pub peak: f64,
+    /// Rounds for the extra error probability to decay by a factor e
+    pub decay_rounds: f64,
+}
+
+/// An impact at `center` in round `round`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Impact {
+    pub center: Point,
+    pub round: usize,
+}
+
+impl CosmicRayModel {
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        for (parameter, value) in [
+            ("background", self.background),
+            ("impact_rate", self.impact_rate),
+            ("peak", self.peak),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(NoiseError::invalid(
+                    "cosmic_rays",
+                    parameter,
+                    "must lie in [0, 1]",
+                ));
+            }
+        }
+        if !(self.radius >= 0.0 && self.decay_rounds > 0.0) {
+            return Err(NoiseError::invalid(
+                "cosmic_rays",
+                "radius",
+                "radius must be non-negative and decay_rounds positive",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Burst noise on the data qubits of a layout
+pub struct CosmicRays {
+    pub model: CosmicRayModel,
+    pub positions: Vec<Point>,
+    /// Impacts of the current shot
+    pub impacts: Vec<Impact>,
+}
+
+impl CosmicRays {
+    pub fn new(model: CosmicRayModel, layout: &Layout) -> Self {
+        CosmicRays {
+            model,
+            positions: layout.data_qubits.clone(),
+            impacts: Vec::new(),
+        }
+    }
+
+    /// Depolarizing probability of `qubit` in `round` given the impacts so far
+    pub fn error_probability(&self, qubit: usize, round: usize) -> f64 {
+        let position = &self.positions[qubit];
+        let excess: f64 = self
+            .impacts
+            .iter()
+            .filter(|impact| {
+                impact.round <= round && impact.center.distance(position) <= self.model.radius
+            })
+            .map(|impact| {
+                let age = (round - impact.round) as f64;
+                self.model.peak * (-age / self.model.decay_rounds).exp()
+            })
+            .sum();
+        (self.model.background + excess).min(MAX_DEPOLARIZING)
+    }
+
+    /// Uniformly random point in the bounding box of the qubits
+    fn random_center(&self, rng: &mut SmallRng) -> Point {
+        let (mut min, mut max) = (
+            Point::new(f64::INFINITY, f64::INFINITY),
+            Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        );
+        for p in &self.positions {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        let coordinate = |lo: f64, hi: f64, rng: &mut SmallRng| {
+            if hi > lo {
+                rng.gen_range(lo..=hi)
+            } else {
+                lo
+            }
+        };
+        let x = coordinate(min.x, max.x, rng);
+        Point::new(x, coordinate(min.y, max.y, rng))
+    }
+}
+
+impl RoundNoise for CosmicRays {
+    fn reset(&mut self) {
+        self.impacts.clear();
+    }
+
+    fn sample_round(&mut self, round: usize, rng: &mut SmallRng) -> Result<Vec<Error>, NoiseError> {
+        if rng.gen::<f64>() < self.model.impact_rate {
+            let center = self.random_center(rng);
+            self.impacts.push(Impact { center, round });
+        }
+        let mut errors = Vec::new();
+        for qubit in 0..self.positions.len() {
+            let p = self.error_probability(qubit, round);
+            let error = PauliChannel::Depolarizing { p }.sample_qubit(rng)?;
+            errors.extend(error.map(|t| Error::new(qubit, t)));
+        }
+        Ok(errors)
+    }
+}
+
+/// How often shots with and without impacts fail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CosmicRayStudy {
+    pub shots: usize,
+    pub rounds: usize,
+    pub failures: usize,
+    /// Shots with at least one impact
+    pub shots_with_impact: usize,
+    /// Failed shots with at least one impact
+    pub failures_with_impact: usize,
+}
+
+impl CosmicRayStudy {
+    /// Probability that a shot with an impact ends in a logical error
+    pub fn failure_rate_given_impact(&self) -> f64 {
+        self.failures_with_impact as f64 / self.shots_with_impact.max(1) as f64
+    }
+}
+
+/// Memory experiment on `code` laid out as `layout` under burst noise
+/// Fails if the model is invalid or the layout does not place every qubit
+/// of the code.
+pub fn cosmic_ray_study<D: Decoder + ?Sized>(
+    code: &StabilizerCode,
+    layout: &Layout,
+    model: CosmicRayModel,
+    decoder: &D,
+    rounds: usize,
+    shots: usize,
+    seed: u64,
+) -> Result<CosmicRayStudy, NoiseError> {
+    model.validate()?;
+    if layout.data_qubits.len() != code.n_qubits {
+        return Err(NoiseError::QubitCountMismatch {
+            expected: code.n_qubits,
+            found: layout.data_qubits.len(),
+        });
+    }
+    let mut noise = CosmicRays::new(model, layout);
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut study = CosmicRayStudy {
+        shots,
+        rounds,
+        failures: 0,
+        shots_with_impact: 0,
+        failures_with_impact: 0,
+    };
+    for _ in 0..shots {
+        let failed = run_shot(code, &mut noise, decoder, rounds, &mut rng)?;
+        let hit = !noise.impacts.is_empty();
+        study.failures += failed as usize;
+        study.shots_with_impact += hit as usize;
+        study.failures_with_impact += (failed && hit) as usize;
+    }
+    Ok(study)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::get_stabilizer_code;
+    use crate::decoder::LookupDecoder;
+    use crate::registry::with_registry;
+
+    const QUIET: CosmicRayModel = CosmicRayModel {
+        background: 0.0,
+        impact_rate: 0.0,
+        radius: 1.5,
+        peak: 0.5,
+        decay_rounds: 2.0,
+    };
+
+    fn surface_d3() -> (StabilizerCode, Layout) {
+        let code = get_stabilizer_code("surface_d3").unwrap();
+        let layout = with_registry(|r| r.get("surface_d3"))
+            .unwrap()
+            .layout()
+            .unwrap();
+        (code, layout)
+    }
+
+    #[test]
+    fn test_burst_profile() {
+        let (_, layout) = surface_d3();
+        let mut rays = CosmicRays::new(QUIET, &layout);
+        rays.impacts.push(Impact {
+            center: layout.data_qubits[0],
+            round: 3,
+        });
+        // Nothing before the impact, the peak in its round, then decay
+        assert_eq!(rays.error_probability(0, 2), 0.0);
+        assert!((rays.error_probability(0, 3) - 0.5).abs() < 1e-12);
+        let later = rays.error_probability(0, 5);
+        assert!((later - 0.5 * (-1.0f64).exp()).abs() < 1e-12);
+        // Qubits outside the disk are untouched
+        let far = (0..9)
+            .find(|&q| layout.data_qubits[q].distance(&layout.data_qubits[0]) > 1.5)
+            .unwrap();
+        assert_eq!(rays.error_probability(far, 3), 0.0);
+    }
+
+    #[test]
+    fn test_study_without_impacts() {
+        let (code, layout) = surface_d3();
+        let decoder = LookupDecoder::new(&code, 1);
+        let study = cosmic_ray_study(&code, &layout, QUIET, &decoder, 5, 50, 1).unwrap();
+        assert_eq!(study.shots_with_impact, 0);
+        assert_eq!(study.failures, 0);
+
+        // The layout must place every qubit of the code
+        let steane = get_stabilizer_code("steane").unwrap();
+        assert_eq!(
+            cosmic_ray_study(&steane, &layout, QUIET, &decoder, 5, 50, 1),
+            Err(NoiseError::QubitCountMismatch {
+                expected: 7,
+                found: 9
+            })
+        );
+    }
+
+    #[test]
+    fn test_impacts_cause_failures() {
+        let (code, layout) = surface_d3();
+        let decoder = LookupDecoder::new(&code, 1);
+        let model = CosmicRayModel {
+            impact_rate: 0.2,
+            radius: 2.0,
+            peak: 0.75,
+            decay_rounds: 5.0,
+            ..QUIET
+        };
+        let study = cosmic_ray_study(&code, &layout, model, &decoder, 10, 200, 2).unwrap();
+        assert!(study.shots_with_impact > 100);
+        // Only impacts cause failures, and they often do
+        assert_eq!(study.failures, study.failures_with_impact);
+        assert!(study.failure_rate_given_impact() > 0.3);
+
+        assert!(CosmicRayModel {
+            decay_rounds: 0.0,
+            ..QUIET
+        }
+        .validate()
+        .is_err());
+    }
+}
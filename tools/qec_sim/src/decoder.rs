@@ -0,0 +1,179 @@
+#![allow(dead_code)]
+use crate::codes::StabilizerCode;
+use crate::pauli::{Pauli, PauliString};
+use std::collections::HashMap;
+
+/// Decoder interface: maps a syndrome (one bit per stabilizer generator,
+/// `true` = -1 outcome) to a Pauli correction
+pub trait Decoder {
+    fn decode(&self, syndrome: &[bool]) -> PauliString;
+}
+
+/// Any closure taking a syndrome can be used as a decoder
+impl<F> Decoder for F
+where
+    F: Fn(&[bool]) -> PauliString,
+{
+    fn decode(&self, syndrome: &[bool]) -> PauliString {
+        self(syndrome)
+    }
+}
+
+/// Errors a lookup table may enumerate while it is still quick to build
+pub const MAX_LOOKUP_ERRORS: usize = 1_000_000;
+
+/// Lookup-table decoder
+/// Enumerates all errors up to `max_weight` in order of increasing weight and
+/// keeps the first (minimum-weight) error for each syndrome
+pub struct LookupDecoder {
+    n_qubits: usize,
+    table: HashMap<Vec<bool>, PauliString>,
+}
+
+impl LookupDecoder {
+    pub fn new(code: &StabilizerCode, max_weight: usize) -> Self {
+        let n = code.n_qubits;
+        let mut table = HashMap::new();
+        table.insert(vec![false; code.generators.len()], PauliString::identity(n));
+
+        for weight in 1..=max_weight.min(n) {
+            for support in combinations(n, weight) {
+                for_each_pauli_assignment(&support, n, |error| {
+                    table
+                        .entry(code.syndrome_of(error))
+                        .or_insert_with(|| error.clone());
+                });
+            }
+        }
+
+        LookupDecoder { n_qubits: n, table }
+    }
+
+    /// Largest weight up to `max_weight` whose table on `n_qubits` qubits
+    /// enumerates at most `MAX_LOOKUP_ERRORS` errors
+    /// There are C(n, w) 3^w errors of weight w, so the table grows
+    /// combinatorially with the weight.
+    pub fn affordable_weight(n_qubits: usize, max_weight: usize) -> usize {
+        let (mut count, mut total) = (1usize, 0usize);
+        for weight in 1..=max_weight.min(n_qubits) {
+            count = count.saturating_mul(3 * (n_qubits - weight + 1)) / weight;
+            total = total.saturating_add(count);
+            if total > MAX_LOOKUP_ERRORS {
+                return weight - 1;
+            }
+        }
+        max_weight
+    }
+
+    /// Number of distinct syndromes in the table
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+}
+
+impl Decoder for LookupDecoder {
+    /// Unknown syndromes are left uncorrected
+    fn decode(&self, syndrome: &[bool]) -> PauliString {
+        self.table
+            .get(syndrome)
+            .cloned()
+            .unwrap_or_else(|| PauliString::identity(self.n_qubits))
+    }
+}
+
+/// All `k`-element subsets of `0..n` in lexicographic order
+pub(crate) fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut result = Vec::new();
+    let mut current = Vec::with_capacity(k);
+    fn recurse(
+        start: usize,
+        n: usize,
+        k: usize,
+        current: &mut Vec<usize>,
+        out: &mut Vec<Vec<usize>>,
+    ) {
+        if current.len() == k {
+            out.push(current.clone());
+            return;
+        }
+        for i in start..n {
+            if n - i < k - current.len() {
+                break;
+            }
+            current.push(i);
+            recurse(i + 1, n, k, current, out);
+            current.pop();
+        }
+    }
+    recurse(0, n, k, &mut current, &mut result);
+    result
+}
+
+/// Call `f` with every Pauli string that is X, Y or Z on each qubit of `support`
+pub(crate) fn for_each_pauli_assignment<F: FnMut(&PauliString)>(
+    support: &[usize],
+    n: usize,
+    mut f: F,
+) {
+    const NON_IDENTITY: [Pauli; 3] = [Pauli::X, Pauli::Y, Pauli::Z];
+    let mut error = PauliString::identity(n);
+    let total = 3usize.pow(support.len() as u32);
+    for mut index in 0..total {
+        for &q in support {
+            error.paulis[q] = NON_IDENTITY[index % 3];
+            index /= 3;
+        }
+        f(&error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{repetition_code_3, steane_code};
+
+    #[test]
+    fn test_lookup_corrects_single_errors() {
+        let code = steane_code().unwrap();
+        let decoder = LookupDecoder::new(&code, 1);
+
+        for q in 0..7 {
+            for pauli in [Pauli::X, Pauli::Y, Pauli::Z] {
+                let error = PauliString::single(7, q, pauli);
+                let correction = decoder.decode(&code.syndrome_of(&error));
+                let residual = error.multiply(&correction);
+                assert!(code.syndrome_of(&residual).iter().all(|&s| !s));
+                assert!(residual.commutes_with(&code.logical_z[0]));
+                assert!(residual.commutes_with(&code.logical_x[0]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_prefers_minimum_weight() {
+        let code = repetition_code_3().unwrap();
+        let decoder = LookupDecoder::new(&code, 2);
+        let correction = decoder.decode(&[true, false]);
+        assert_eq!(correction.weight(), 1);
+    }
+
+    #[test]
+    fn test_affordable_weight() {
+        // 3 * 49 + 9 * C(49, 2) + 27 * C(49, 3) errors fit, weight 4 does not
+        assert_eq!(LookupDecoder::affordable_weight(49, 3), 3);
+        assert_eq!(LookupDecoder::affordable_weight(49, 10), 3);
+        assert_eq!(LookupDecoder::affordable_weight(7, 1), 1);
+        assert_eq!(LookupDecoder::affordable_weight(10_000, 5), 1);
+        assert_eq!(LookupDecoder::affordable_weight(1_000_000, 5), 0);
+    }
+
+    #[test]
+    fn test_closure_decoder() {
+        let decoder = |_: &[bool]| PauliString::from_str("XII").unwrap();
+        assert_eq!(decoder.decode(&[true, false]).to_string(), "XII");
+    }
+}
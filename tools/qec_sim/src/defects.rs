@@ -0,0 +1,404 @@
+// Rotated surface codes on chips with fabrication defects
+//
+// Broken hardware is reduced to a set of disabled data qubits: a dead data
+// qubit disables itself, a dead ancilla disables every data qubit of its
+// plaquette and a dead coupler disables the data qubit it connects to. The
+// plaquettes minus the disabled qubits are the gauge operators that are
+// still measured. Gauges around a defect anticommute with each other, so the
+// stabilizers are the products of them that commute with every gauge: the
+// super-stabilizers.
+use crate::codes::{css_operator, surface_plaquettes, StabilizerCode};
+use crate::decoder::Decoder;
+use crate::distance::{subsystem_distance, DistanceReport};
+use crate::error::NoiseError;
+use crate::layout::{Layout, Point};
+use crate::memory::{run_memory_with, MemoryResult, RoundNoise};
+use crate::pauli::{Pauli, PauliString};
+use crate::symplectic;
+use crate::validation::CodeError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Broken component of a distance-d rotated surface code chip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Defect {
+    /// Data qubit `row * d + col`
+    DataQubit(usize),
+    /// Ancilla measuring stabilizer generator `index` of `rotated_surface_code(d)`
+    Ancilla(usize),
+    /// Coupler between an ancilla and one data qubit of its plaquette
+    Coupler { ancilla: usize, data: usize },
+}
+
+/// Surface code adapted around defects
+#[derive(Debug, Clone)]
+pub struct DefectiveSurfaceCode {
+    pub d: usize,
+    /// Original index of each working data qubit, in code qubit order
+    pub data_qubits: Vec<usize>,
+    /// Original indices of the data qubits taken out of the code
+    pub disabled_qubits: Vec<usize>,
+    /// Measured plaquettes with the disabled qubits removed
+    pub gauge_operators: Vec<PauliString>,
+    /// Gauge operators multiplied into each stabilizer generator; plaquettes
+    /// away from defects are their own single gauge
+    pub super_stabilizers: Vec<Vec<usize>>,
+    /// Stabilizer code on the working qubits
+    /// Logical qubit 0 carries the information; the others are gauge qubits,
+    /// so only errors acting on qubit 0 (see `is_logical_failure`) count.
+    /// Its `distance` counts gauge operators as logical, unlike `distance`.
+    pub code: StabilizerCode,
+    /// Dressed distance: gauge operators are not logical errors
+    pub distance: DistanceReport,
+}
+
+impl DefectiveSurfaceCode {
+    /// Whether a residual error (error times correction) flips the encoded
+    /// qubit; residuals acting only on gauge qubits are harmless
+    pub fn is_logical_failure(&self, residual: &PauliString) -> bool {
+        !residual.commutes_with(&self.code.logical_x[0])
+            || !residual.commutes_with(&self.code.logical_z[0])
+    }
+
+    /// Memory experiment on the adapted code that only counts flips of the
+    /// encoded qubit as failures
+    pub fn run_memory<N: RoundNoise + ?Sized, D: Decoder + ?Sized>(
+        &self,
+        noise: &mut N,
+        decoder: &D,
+        rounds: usize,
+        shots: usize,
+        seed: u64,
+    ) -> Result<MemoryResult, NoiseError> {
+        run_memory_with(
+            &self.code,
+            noise,
+            decoder,
+            rounds,
+            shots,
+            seed,
+            |residual| self.is_logical_failure(residual),
+        )
+    }
+
+    /// Number of gauge qubits introduced by the defects
+    pub fn n_gauge_qubits(&self) -> usize {
+        self.code.logical_x.len() - 1
+    }
+
+    /// Grid layout of the working qubits with the super-stabilizers as
+    /// plaquettes
+    pub fn layout(&self) -> Layout {
+        let points: Vec<Point> = self
+            .data_qubits
+            .iter()
+            .map(|&q| Point::new((q % self.d) as f64, (q / self.d) as f64))
+            .collect();
+        Layout::from_data_qubits(points, &self.code.generators)
+    }
+}
+
+/// Adapt the distance-d rotated surface code to a list of defects
+/// Fails if a defect does not exist on the chip or if the defects leave no
+/// encoded qubit.
+pub fn defective_surface_code(
+    d: usize,
+    defects: &[Defect],
+) -> Result<DefectiveSurfaceCode, CodeError> {
+    let plaquettes = surface_plaquettes(d);
+    let n = d * d;
+    let code_name = format!("surface:d={}", d);
+    let check_ancilla = |a: usize| {
+        if a < plaquettes.len() {
+            Ok(())
+        } else {
+            Err(CodeError::invalid_parameter(
+                &code_name,
+                "defects",
+                format!("no ancilla {}", a),
+            ))
+        }
+    };
+
+    let mut disabled = BTreeSet::new();
+    for &defect in defects {
+        match defect {
+            Defect::DataQubit(q) if q < n => {
+                disabled.insert(q);
+            }
+            Defect::DataQubit(q) => {
+                return Err(CodeError::invalid_parameter(
+                    &code_name,
+                    "defects",
+                    format!("no data qubit {}", q),
+                ))
+            }
+            Defect::Ancilla(a) => {
+                check_ancilla(a)?;
+                disabled.extend(plaquettes[a].support.iter().copied());
+            }
+            Defect::Coupler { ancilla, data } => {
+                check_ancilla(ancilla)?;
+                if !plaquettes[ancilla].support.contains(&data) {
+                    return Err(CodeError::invalid_parameter(
+                        &code_name,
+                        "defects",
+                        format!("ancilla {} has no coupler to data qubit {}", ancilla, data),
+                    ));
+                }
+                disabled.insert(data);
+            }
+        }
+    }
+
+    let data_qubits: Vec<usize> = (0..n).filter(|q| !disabled.contains(q)).collect();
+    let mut index = vec![None; n];
+    for (i, &q) in data_qubits.iter().enumerate() {
+        index[q] = Some(i);
+    }
+    let n_live = data_qubits.len();
+    let gauge_operators: Vec<PauliString> = plaquettes
+        .iter()
+        .filter_map(|p| {
+            let support: Vec<usize> = p.support.iter().filter_map(|&q| index[q]).collect();
+            (!support.is_empty()).then(|| css_operator(n_live, &support, p.pauli))
+        })
+        .collect();
+
+    let (generators, super_stabilizers) = super_stabilizers(&gauge_operators);
+    let (bare_x, bare_z) = symplectic::symplectic_basis_modulo(
+        &symplectic::centralizer(&gauge_operators, n_live),
+        &generators,
+    );
+    if bare_x.len() != 1 {
+        return Err(CodeError::invalid_parameter(
+            &code_name,
+            "defects",
+            format!(
+                "the defects leave {} logical qubits instead of 1",
+                bare_x.len()
+            ),
+        ));
+    }
+    let bare_x = symplectic::reduce_weight(bare_x[0].clone(), &generators);
+    let bare_z = symplectic::reduce_weight(bare_z[0].clone(), &generators);
+    let (gauge_x, gauge_z) = symplectic::symplectic_basis_modulo(&gauge_operators, &generators);
+
+    let distance = subsystem_distance(n_live, &generators, &[bare_x.clone(), bare_z.clone()]);
+    let logical_x: Vec<PauliString> = std::iter::once(bare_x).chain(gauge_x).collect();
+    let logical_z: Vec<PauliString> = std::iter::once(bare_z).chain(gauge_z).collect();
+    // Without gauge qubits the dressed distance is the code distance
+    let code = if logical_x.len() == 1 {
+        StabilizerCode::with_distance(n_live, generators, logical_x, logical_z, distance.distance)?
+    } else {
+        StabilizerCode::new(n_live, generators, logical_x, logical_z)?
+    };
+    Ok(DefectiveSurfaceCode {
+        d,
+        data_qubits,
+        disabled_qubits: disabled.into_iter().collect(),
+        gauge_operators,
+        super_stabilizers,
+        code,
+        distance,
+    })
+}
+
+/// Independent generators of the center of the gauge group, each with the
+/// gauges it is the product of, X type first
+/// Gauges only interact within connected components of their anticommutation
+/// graph, so each component is solved on its own and the products stay local.
+fn super_stabilizers(gauges: &[PauliString]) -> (Vec<PauliString>, Vec<Vec<usize>>) {
+    let m = gauges.len();
+    let mut component: Vec<usize> = (0..m).collect();
+    fn root(component: &mut [usize], mut i: usize) -> usize {
+        while component[i] != i {
+            component[i] = component[component[i]];
+            i = component[i];
+        }
+        i
+    }
+    for i in 0..m {
+        for j in i + 1..m {
+            if !gauges[i].commutes_with(&gauges[j]) {
+                let (a, b) = (root(&mut component, i), root(&mut component, j));
+                component[a] = b;
+            }
+        }
+    }
+
+    let mut products: Vec<(PauliString, Vec<usize>)> = Vec::new();
+    let mut seen = BTreeSet::new();
+    for i in 0..m {
+        let r = root(&mut component, i);
+        if !seen.insert(r) {
+            continue;
+        }
+        let members: Vec<usize> = (0..m).filter(|&j| root(&mut component, j) == r).collect();
+        // Products commuting with every gauge of the component
+        let rows: Vec<Vec<bool>> = members
+            .iter()
+            .map(|&a| {
+                members
+                    .iter()
+                    .map(|&b| !gauges[a].commutes_with(&gauges[b]))
+                    .collect()
+            })
+            .collect();
+        for v in symplectic::null_space(&rows, members.len()) {
+            let used: Vec<usize> = members
+                .iter()
+                .zip(&v)
+                .filter(|(_, &bit)| bit)
+                .map(|(&g, _)| g)
+                .collect();
+            let product = used
+                .iter()
+                .fold(PauliString::identity(gauges[0].n_qubits()), |acc, &g| {
+                    acc.multiply(&gauges[g])
+                });
+            products.push((product, used));
+        }
+    }
+    products.sort_by_key(|(op, _)| !op.paulis.contains(&Pauli::X));
+
+    let ops: Vec<PauliString> = products.iter().map(|(op, _)| op.clone()).collect();
+    symplectic::independent_indices(&ops)
+        .into_iter()
+        .map(|i| products[i].clone())
+        .unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::rotated_surface_code;
+    use crate::decoder::LookupDecoder;
+    use crate::error::{Error, PauliFrame};
+    use crate::memory::run_memory;
+    use rand::rngs::SmallRng;
+
+    #[test]
+    fn test_no_defects_is_the_surface_code() {
+        let adapted = defective_surface_code(3, &[]).unwrap();
+        let surface = rotated_surface_code(3).unwrap();
+        assert_eq!(adapted.code.generators, surface.generators);
+        assert_eq!(adapted.n_gauge_qubits(), 0);
+        assert_eq!(adapted.distance.distance, 3);
+        assert!(adapted.super_stabilizers.iter().all(|g| g.len() == 1));
+    }
+
+    #[test]
+    fn test_dead_interior_qubit() {
+        let adapted = defective_surface_code(5, &[Defect::DataQubit(12)]).unwrap();
+        assert_eq!(adapted.code.n_qubits, 24);
+        assert_eq!(adapted.disabled_qubits, vec![12]);
+        // The four plaquettes around the hole become two weight-6 super-stabilizers
+        let supers: Vec<&PauliString> = adapted
+            .super_stabilizers
+            .iter()
+            .zip(&adapted.code.generators)
+            .filter(|(members, _)| members.len() > 1)
+            .map(|(_, g)| g)
+            .collect();
+        assert_eq!(supers.len(), 2);
+        assert!(supers.iter().all(|g| g.weight() == 6));
+        assert_eq!(adapted.code.generators.len(), 22);
+        assert_eq!(adapted.n_gauge_qubits(), 1);
+        let report = adapted.distance;
+        assert!(report.exact);
+        assert_eq!((report.x_distance, report.z_distance), (Some(4), Some(4)));
+        assert_eq!(adapted.layout().n_qubits(), 24);
+    }
+
+    #[test]
+    fn test_ancillas_and_couplers() {
+        let surface = rotated_surface_code(5).unwrap();
+        let a = 3;
+        let data = surface.generators[a]
+            .paulis
+            .iter()
+            .position(|&p| p != Pauli::I)
+            .unwrap();
+        let coupler = defective_surface_code(5, &[Defect::Coupler { ancilla: a, data }]).unwrap();
+        let qubit = defective_surface_code(5, &[Defect::DataQubit(data)]).unwrap();
+        assert_eq!(coupler.code.generators, qubit.code.generators);
+
+        let ancilla = defective_surface_code(5, &[Defect::Ancilla(a)]).unwrap();
+        assert_eq!(
+            ancilla.disabled_qubits.len(),
+            surface.generators[a].weight()
+        );
+        assert!(ancilla.distance.distance < 5);
+
+        assert!(defective_surface_code(5, &[Defect::DataQubit(25)]).is_err());
+        assert!(defective_surface_code(
+            5,
+            &[Defect::Coupler {
+                ancilla: a,
+                data: 24
+            }]
+        )
+        .is_err());
+        // A dead column cuts the logical X string
+        let column: Vec<Defect> = (0..5).map(|r| Defect::DataQubit(r * 5 + 2)).collect();
+        assert!(defective_surface_code(5, &column).is_err());
+    }
+
+    #[test]
+    fn test_lookup_decoder_on_adapted_code() {
+        let adapted =
+            defective_surface_code(5, &[Defect::DataQubit(7), Defect::Ancilla(20)]).unwrap();
+        assert!(adapted.distance.distance >= 3);
+        let n = adapted.code.n_qubits;
+        let decoder = LookupDecoder::new(&adapted.code, 1);
+        for q in 0..n {
+            for pauli in [Pauli::X, Pauli::Y, Pauli::Z] {
+                let error = PauliString::single(n, q, pauli);
+                let residual = error.multiply(&decoder.decode(&adapted.code.syndrome_of(&error)));
+                assert!(adapted.code.syndrome_of(&residual).iter().all(|&s| !s));
+                assert!(!adapted.is_logical_failure(&residual));
+            }
+        }
+    }
+
+    #[test]
+    fn test_memory_ignores_gauge_flips() {
+        // Applies a fixed Pauli string in the first round
+        struct Once(Vec<Error>);
+        impl RoundNoise for Once {
+            fn sample_round(
+                &mut self,
+                round: usize,
+                _rng: &mut SmallRng,
+            ) -> Result<Vec<Error>, NoiseError> {
+                Ok(if round == 0 { self.0.clone() } else { vec![] })
+            }
+        }
+
+        let adapted = defective_surface_code(5, &[Defect::DataQubit(12)]).unwrap();
+        assert!(adapted.code.distance < adapted.distance.distance);
+        let decoder = LookupDecoder::new(&adapted.code, 1);
+        let errors_of = |op: &PauliString| {
+            let mut frame = PauliFrame::new(op.n_qubits());
+            frame.apply(op);
+            frame.errors()
+        };
+        // A gauge X̄ has no syndrome, so it survives as the residual
+        let errors = errors_of(&adapted.code.logical_x[1]);
+        let result = adapted
+            .run_memory(&mut Once(errors.clone()), &decoder, 3, 2, 0)
+            .unwrap();
+        assert_eq!(result.failures, 0);
+        let counted = run_memory(&adapted.code, &mut Once(errors), &decoder, 3, 2, 0).unwrap();
+        assert_eq!(counted.failures, 2);
+
+        // The bare X̄ is a failure either way
+        let bare = errors_of(&adapted.code.logical_x[0]);
+        let result = adapted
+            .run_memory(&mut Once(bare), &decoder, 3, 2, 0)
+            .unwrap();
+        assert_eq!(result.failures, 2);
+    }
+}
@@ -0,0 +1,351 @@
+#![allow(dead_code)]
+use crate::codes::StabilizerCode;
+use crate::decoder::combinations;
+use crate::pauli::{Pauli, PauliString};
+use crate::symplectic;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of Pauli strings the exhaustive search may enumerate
+/// before `compute_distance` falls back to the randomized search
+const EXHAUSTIVE_BUDGET: usize = 2_000_000;
+
+/// Number of random information sets tried by `compute_distance`
+const RANDOM_TRIALS: usize = 200;
+
+/// Result of a distance computation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistanceReport {
+    /// Minimum weight of a nontrivial logical operator (an upper bound if not exact)
+    pub distance: usize,
+    /// Minimum weight of an X-type logical operator (CSS codes only)
+    pub x_distance: Option<usize>,
+    /// Minimum weight of a Z-type logical operator (CSS codes only)
+    pub z_distance: Option<usize>,
+    /// Whether the values were found by exhaustive search
+    pub exact: bool,
+}
+
+/// Nontrivial logical test: commutes with the stabilizers and anticommutes
+/// with at least one operator of a complete logical basis
+struct LogicalTest {
+    generators: Vec<PauliString>,
+    logicals: Vec<PauliString>,
+}
+
+impl LogicalTest {
+    fn new(code: &StabilizerCode) -> Self {
+        let (lx, lz) = code.compute_logical_operators();
+        LogicalTest {
+            generators: code.generators.clone(),
+            logicals: lx.into_iter().chain(lz).collect(),
+        }
+    }
+
+    fn is_logical(&self, op: &PauliString) -> bool {
+        self.generators.iter().all(|g| g.commutes_with(op))
+            && self.logicals.iter().any(|l| !l.commutes_with(op))
+    }
+}
+
+/// Smallest weight of a nontrivial logical operator using only the Paulis
+/// in `alphabet`, searching weights up to `max_weight`
+fn exhaustive_min_weight(
+    n: usize,
+    test: &LogicalTest,
+    alphabet: &[Pauli],
+    max_weight: usize,
+) -> Option<usize> {
+    let base = alphabet.len();
+    for weight in 1..=max_weight.min(n) {
+        for support in combinations(n, weight) {
+            let mut op = PauliString::identity(n);
+            for mut index in 0..base.pow(weight as u32) {
+                for &q in &support {
+                    op.paulis[q] = alphabet[index % base];
+                    index /= base;
+                }
+                if test.is_logical(&op) {
+                    return Some(weight);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Exact distance by exhaustive search over all Pauli strings of weight at
+/// most `max_weight`; None if no logical operator is that light
+pub fn exact_distance(code: &StabilizerCode, max_weight: usize) -> Option<usize> {
+    let test = LogicalTest::new(code);
+    exhaustive_min_weight(
+        code.n_qubits,
+        &test,
+        &[Pauli::X, Pauli::Y, Pauli::Z],
+        max_weight,
+    )
+}
+
+/// Exact X and Z distances of a CSS code by exhaustive search over X-type
+/// and Z-type strings of weight at most `max_weight`
+pub fn exact_css_distances(
+    code: &StabilizerCode,
+    max_weight: usize,
+) -> (Option<usize>, Option<usize>) {
+    let test = LogicalTest::new(code);
+    (
+        exhaustive_min_weight(code.n_qubits, &test, &[Pauli::X], max_weight),
+        exhaustive_min_weight(code.n_qubits, &test, &[Pauli::Z], max_weight),
+    )
+}
+
+/// Outcome of checking a claimed distance within the exhaustive budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceCheck {
+    /// No lighter logical operator exists
+    Confirmed,
+    /// A logical operator of this weight, below the claim
+    Lighter(usize),
+    /// The search would exceed the budget; the claim is unverified
+    Skipped,
+}
+
+/// Look for a logical operator over `alphabet` lighter than `claimed`
+/// Searches only when every weight below `claimed` fits in the budget.
+pub fn lighter_logical(code: &StabilizerCode, alphabet: &[Pauli], claimed: usize) -> DistanceCheck {
+    let max_weight = claimed.saturating_sub(1);
+    if max_weight > affordable_weight(code.n_qubits, alphabet.len()) {
+        return DistanceCheck::Skipped;
+    }
+    match exhaustive_min_weight(code.n_qubits, &LogicalTest::new(code), alphabet, max_weight) {
+        Some(weight) => DistanceCheck::Lighter(weight),
+        None => DistanceCheck::Confirmed,
+    }
+}
+
+/// Check a claimed distance without the randomized fallback of
+/// `compute_distance`
+/// The lightest logical operator of a CSS code is X or Z type, so those codes
+/// search the two types separately.
+pub fn check_distance(code: &StabilizerCode, claimed: usize) -> DistanceCheck {
+    if !symplectic::is_css(&code.generators) {
+        return lighter_logical(code, &[Pauli::X, Pauli::Y, Pauli::Z], claimed);
+    }
+    match (
+        lighter_logical(code, &[Pauli::X], claimed),
+        lighter_logical(code, &[Pauli::Z], claimed),
+    ) {
+        (DistanceCheck::Lighter(x), DistanceCheck::Lighter(z)) => DistanceCheck::Lighter(x.min(z)),
+        (DistanceCheck::Lighter(w), _) | (_, DistanceCheck::Lighter(w)) => {
+            DistanceCheck::Lighter(w)
+        }
+        (DistanceCheck::Confirmed, DistanceCheck::Confirmed) => DistanceCheck::Confirmed,
+        _ => DistanceCheck::Skipped,
+    }
+}
+
+/// Randomized information-set search
+/// Each trial row-reduces a basis of candidate operators with the qubits in a
+/// random order; the reduced rows are sparse and every one that is a logical
+/// operator gives an upper bound on the distance.
+fn information_set_bound<R: Rng>(
+    basis: &[PauliString],
+    test: &LogicalTest,
+    n_qubits: usize,
+    trials: usize,
+    rng: &mut R,
+) -> Option<usize> {
+    let n = n_qubits;
+    let vectors: Vec<Vec<bool>> = basis.iter().map(symplectic::to_bits).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    let mut best: Option<usize> = None;
+
+    for _ in 0..trials {
+        order.shuffle(rng);
+        // Column layout: x and z bits of order[0], then of order[1], ...
+        let permuted: Vec<Vec<bool>> = vectors
+            .iter()
+            .map(|v| order.iter().flat_map(|&q| [v[q], v[n + q]]).collect())
+            .collect();
+
+        for row in symplectic::reduced_basis(&permuted) {
+            let mut bits = vec![false; 2 * n];
+            for (i, &q) in order.iter().enumerate() {
+                bits[q] = row[2 * i];
+                bits[n + q] = row[2 * i + 1];
+            }
+            let op = symplectic::from_bits(&bits);
+            if best.is_none_or(|b| op.weight() < b) && test.is_logical(&op) {
+                best = Some(op.weight());
+            }
+        }
+    }
+    best
+}
+
+/// Upper bounds on the distance (and on the X/Z distances of CSS codes) from
+/// `trials` random information sets
+pub fn random_distance_upper_bound<R: Rng>(
+    code: &StabilizerCode,
+    trials: usize,
+    rng: &mut R,
+) -> DistanceReport {
+    let test = LogicalTest::new(code);
+    random_bound(code.n_qubits, &test, trials, rng)
+}
+
+fn random_bound<R: Rng>(
+    n: usize,
+    test: &LogicalTest,
+    trials: usize,
+    rng: &mut R,
+) -> DistanceReport {
+    let centralizer = symplectic::centralizer(&test.generators, n);
+
+    if symplectic::is_css(&test.generators) {
+        let is_type =
+            |op: &PauliString, pauli: Pauli| op.paulis.iter().all(|&p| p == Pauli::I || p == pauli);
+        let x_basis: Vec<PauliString> = centralizer
+            .iter()
+            .filter(|op| is_type(op, Pauli::X))
+            .cloned()
+            .collect();
+        let z_basis: Vec<PauliString> = centralizer
+            .iter()
+            .filter(|op| is_type(op, Pauli::Z))
+            .cloned()
+            .collect();
+        let dx = information_set_bound(&x_basis, test, n, trials, rng);
+        let dz = information_set_bound(&z_basis, test, n, trials, rng);
+        return DistanceReport {
+            distance: dx.unwrap_or(n).min(dz.unwrap_or(n)),
+            x_distance: dx,
+            z_distance: dz,
+            exact: false,
+        };
+    }
+
+    DistanceReport {
+        distance: information_set_bound(&centralizer, test, n, trials, rng).unwrap_or(n),
+        x_distance: None,
+        z_distance: None,
+        exact: false,
+    }
+}
+
+/// Largest weight whose exhaustive search fits in the budget
+fn affordable_weight(n: usize, alphabet_size: usize) -> usize {
+    let mut total = 0usize;
+    let mut binomial = 1usize;
+    for w in 1..=n {
+        binomial = binomial * (n - w + 1) / w;
+        let count = binomial.saturating_mul(alphabet_size.saturating_pow(w as u32));
+        total = total.saturating_add(count);
+        if total > EXHAUSTIVE_BUDGET {
+            return w - 1;
+        }
+    }
+    n
+}
+
+/// Compute the code distance
+/// Small codes are searched exhaustively; if no logical operator is found
+/// within the search budget, a randomized search gives an upper bound and
+/// `exact` is false. CSS codes also report their X and Z distances.
+pub fn compute_distance(code: &StabilizerCode) -> DistanceReport {
+    if code.n_logical() == 0 {
+        return DistanceReport {
+            distance: code.n_qubits,
+            x_distance: None,
+            z_distance: None,
+            exact: true,
+        };
+    }
+    search_distance(code.n_qubits, &LogicalTest::new(code))
+}
+
+/// Dressed distance of a subsystem code: minimum weight of an operator that
+/// commutes with the `stabilizers` and anticommutes with one of the
+/// `bare_logicals`; gauge operators do not count as logical errors
+pub fn subsystem_distance(
+    n_qubits: usize,
+    stabilizers: &[PauliString],
+    bare_logicals: &[PauliString],
+) -> DistanceReport {
+    let test = LogicalTest {
+        generators: stabilizers.to_vec(),
+        logicals: bare_logicals.to_vec(),
+    };
+    search_distance(n_qubits, &test)
+}
+
+fn search_distance(n: usize, test: &LogicalTest) -> DistanceReport {
+    if symplectic::is_css(&test.generators) {
+        let max_weight = affordable_weight(n, 1);
+        let dx = exhaustive_min_weight(n, test, &[Pauli::X], max_weight);
+        let dz = exhaustive_min_weight(n, test, &[Pauli::Z], max_weight);
+        if let (Some(dx), Some(dz)) = (dx, dz) {
+            return DistanceReport {
+                distance: dx.min(dz),
+                x_distance: Some(dx),
+                z_distance: Some(dz),
+                exact: true,
+            };
+        }
+    } else if let Some(d) = exhaustive_min_weight(
+        n,
+        test,
+        &[Pauli::X, Pauli::Y, Pauli::Z],
+        affordable_weight(n, 3),
+    ) {
+        return DistanceReport {
+            distance: d,
+            x_distance: None,
+            z_distance: None,
+            exact: true,
+        };
+    }
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    random_bound(n, test, RANDOM_TRIALS, &mut rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{five_qubit_code, repetition_code_3, steane_code, surface_code_d3};
+    use crate::concatenation::concatenate;
+
+    #[test]
+    fn test_exact_distances() {
+        assert_eq!(exact_distance(&five_qubit_code().unwrap(), 5), Some(3));
+        assert_eq!(exact_distance(&steane_code().unwrap(), 7), Some(3));
+        assert_eq!(exact_distance(&surface_code_d3().unwrap(), 9), Some(3));
+        assert_eq!(exact_distance(&five_qubit_code().unwrap(), 2), None);
+    }
+
+    #[test]
+    fn test_repetition_x_and_z_distance() {
+        let report = compute_distance(&repetition_code_3().unwrap());
+        assert!(report.exact);
+        assert_eq!(report.x_distance, Some(3));
+        assert_eq!(report.z_distance, Some(1));
+        assert_eq!(report.distance, 1);
+    }
+
+    #[test]
+    fn test_random_upper_bound() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let report = random_distance_upper_bound(&steane_code().unwrap(), 50, &mut rng);
+        assert_eq!(report.x_distance, Some(3));
+        assert_eq!(report.z_distance, Some(3));
+
+        // Steane inside Steane has distance 9; the bound can never go below it
+        let c = concatenate(&steane_code().unwrap(), &steane_code().unwrap()).unwrap();
+        let report = random_distance_upper_bound(&c.code, 50, &mut rng);
+        assert!(report.distance >= 9);
+        assert!(!report.exact);
+    }
+}
@@ -0,0 +1,582 @@
+// Calibration parameters that change from round to round
+//
+// A `Schedule` gives a parameter as a function of the round index. Stochastic
+// schedules such as random telegraph noise are Markov, so each value only
+// depends on the round and the state left by the previous round;
+// recalibration restarts the inner schedule every period as if from round 0.
+//
+// A `DriftModel` attaches schedules to fields of a `DeviceCalibration` (T1,
+// T2, gate and readout errors). Each round the drifted calibration is turned
+// into a `NoiseModel` exactly like a static snapshot, so the trajectory of
+// the parameters can be recorded next to the errors it caused.
+use crate::calibration::DeviceCalibration;
+use crate::error::{Error, NoiseError};
+use crate::memory::RoundNoise;
+use crate::noise::NoiseModel;
+use rand::rngs::SmallRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Parameter value per round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Schedule {
+    Constant {
+        value: f64,
+    },
+    /// start + slope * round
+    Linear {
+        start: f64,
+        slope: f64,
+    },
+    /// Random telegraph noise from a two-level defect: starts at `low` and
+    /// switches between `low` and `high` with probability `switch_rate` per round
+    Telegraph {
+        low: f64,
+        high: f64,
+        switch_rate: f64,
+    },
+    /// `schedule` restarted every `period` rounds by recalibration
+    Recalibrated {
+        schedule: Box<Schedule>,
+        period: usize,
+    },
+}
+
+impl Schedule {
+    /// Value in `round`
+    /// `level` is the telegraph state carried from round to round: None at
+    /// the start of a shot, then whether the defect is in its high state.
+    /// The schedule must be valid.
+    fn next<R: Rng>(&self, round: usize, level: &mut Option<bool>, rng: &mut R) -> f64 {
+        match self {
+            Schedule::Constant { value } => *value,
+            Schedule::Linear { start, slope } => start + slope * round as f64,
+            Schedule::Telegraph {
+                low,
+                high,
+                switch_rate,
+            } => {
+                let is_high = match *level {
+                    None => false,
+                    Some(is_high) => is_high ^ (rng.gen::<f64>() < *switch_rate),
+                };
+                *level = Some(is_high);
+                if is_high {
+                    *high
+                } else {
+                    *low
+                }
+            }
+            Schedule::Recalibrated { schedule, period } => {
+                let local = round % period;
+                if local == 0 {
+                    *level = None;
+                }
+                schedule.next(local, level, rng)
+            }
+        }
+    }
+
+    /// Values for rounds 0..rounds
+    pub fn realize<R: Rng>(&self, rounds: usize, rng: &mut R) -> Result<Vec<f64>, NoiseError> {
+        self.validate()?;
+        let mut level = None;
+        Ok((0..rounds)
+            .map(|round| self.next(round, &mut level, rng))
+            .collect())
+    }
+
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        match self {
+            Schedule::Telegraph { switch_rate, .. } if !(0.0..=1.0).contains(switch_rate) => Err(
+                NoiseError::invalid("schedule", "switch_rate", "must lie in [0, 1]"),
+            ),
+            Schedule::Recalibrated { period: 0, .. } => Err(NoiseError::invalid(
+                "schedule",
+                "period",
+                "must be positive",
+            )),
+            Schedule::Recalibrated { schedule, .. } => schedule.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Calibrated quantity driven by a schedule
+/// A missing qubit or coupler index drives that quantity on all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DriftParameter {
+    /// T1 in microseconds
+    T1 {
+        #[serde(default)]
+        qubit: Option<usize>,
+    },
+    /// T2 in microseconds
+    T2 {
+        #[serde(default)]
+        qubit: Option<usize>,
+    },
+    /// Randomized benchmarking error of single-qubit gates
+    SingleQubitGateError {
+        #[serde(default)]
+        qubit: Option<usize>,
+    },
+    /// Symmetric readout assignment error
+    ReadoutError {
+        #[serde(default)]
+        qubit: Option<usize>,
+    },
+    /// Randomized benchmarking error of two-qubit gates
+    GateError {
+        #[serde(default)]
+        coupler: Option<usize>,
+    },
+}
+
+/// Indices `index` selects out of `len`
+fn targets(index: Option<usize>, len: usize) -> Range<usize> {
+    match index {
+        Some(i) => i..i + 1,
+        None => 0..len,
+    }
+}
+
+impl DriftParameter {
+    /// Name used in error messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            DriftParameter::T1 { .. } => "t1",
+            DriftParameter::T2 { .. } => "t2",
+            DriftParameter::SingleQubitGateError { .. } => "single_qubit_gate_error",
+            DriftParameter::ReadoutError { .. } => "readout_error",
+            DriftParameter::GateError { .. } => "gate_error",
+        }
+    }
+
+    /// Whether the quantity acts on idle qubits, as opposed to gates and
+    /// readout
+    fn acts_on_idle(&self) -> bool {
+        matches!(self, DriftParameter::T1 { .. } | DriftParameter::T2 { .. })
+    }
+
+    /// Whether the qubit or coupler exists in `calibration`
+    fn exists_in(&self, calibration: &DeviceCalibration) -> bool {
+        match *self {
+            DriftParameter::T1 { qubit }
+            | DriftParameter::T2 { qubit }
+            | DriftParameter::SingleQubitGateError { qubit }
+            | DriftParameter::ReadoutError { qubit } => {
+                qubit.is_none_or(|q| q < calibration.qubits.len())
+            }
+            DriftParameter::GateError { coupler } => {
+                coupler.is_none_or(|c| c < calibration.couplers.len())
+            }
+        }
+    }
+
+    /// Set the quantity in `calibration` to `value`, clamping times to be
+    /// positive and errors to [0, 1]
+    fn set(&self, calibration: &mut DeviceCalibration, value: f64) {
+        let time = value.max(f64::MIN_POSITIVE);
+        let error = value.clamp(0.0, 1.0);
+        let n_qubits = calibration.qubits.len();
+        match *self {
+            DriftParameter::T1 { qubit } => {
+                for q in targets(qubit, n_qubits) {
+                    calibration.qubits[q].t1_us = time;
+                }
+            }
+            DriftParameter::T2 { qubit } => {
+                for q in targets(qubit, n_qubits) {
+                    calibration.qubits[q].t2_us = time;
+                }
+            }
+            DriftParameter::SingleQubitGateError { qubit } => {
+                for q in targets(qubit, n_qubits) {
+                    calibration.qubits[q].single_qubit_gate_error = error;
+                }
+            }
+            DriftParameter::ReadoutError { qubit } => {
+                for q in targets(qubit, n_qubits) {
+                    calibration.qubits[q].readout = [[1.0 - error, error], [error, 1.0 - error]];
+                }
+            }
+            DriftParameter::GateError { coupler } => {
+                for c in targets(coupler, calibration.couplers.len()) {
+                    calibration.couplers[c].gate_error = error;
+                }
+            }
+        }
+    }
+}
+
+/// A calibrated quantity with its schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterDrift {
+    pub parameter: DriftParameter,
+    pub schedule: Schedule,
+}
+
+/// Device calibration whose parameters drift
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftModel {
+    /// Calibration of the quantities that do not drift
+    pub calibration: DeviceCalibration,
+    /// Later drifts override earlier ones on the same quantity
+    pub drifts: Vec<ParameterDrift>,
+}
+
+impl DriftModel {
+    pub fn new(
+        calibration: DeviceCalibration,
+        drifts: Vec<ParameterDrift>,
+    ) -> Result<Self, NoiseError> {
+        let model = DriftModel {
+            calibration,
+            drifts,
+        };
+        model.validate()?;
+        Ok(model)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, NoiseError> {
+        let model: DriftModel =
+            serde_json::from_str(json).map_err(|e| NoiseError::InvalidFile {
+                format: "drift model".to_string(),
+                reason: e.to_string(),
+            })?;
+        DriftModel::new(model.calibration, model.drifts)
+    }
+
+    /// Check the calibration, every schedule and that every drifting
+    /// quantity exists on the device
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        self.calibration.validate()?;
+        for drift in &self.drifts {
+            drift.schedule.validate()?;
+            if !drift.parameter.exists_in(&self.calibration) {
+                return Err(NoiseError::invalid(
+                    &self.calibration.name,
+                    drift.parameter.name(),
+                    "no such qubit or coupler",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Calibration with drift `i` at `values[i]`
+    /// T2 is capped at 2 T1 when T1 drifts below half of it.
+    pub fn calibration_at(&self, values: &[f64]) -> DeviceCalibration {
+        let mut calibration = self.calibration.clone();
+        for (drift, &value) in self.drifts.iter().zip(values) {
+            drift.parameter.set(&mut calibration, value);
+        }
+        for qubit in &mut calibration.qubits {
+            qubit.t2_us = qubit.t2_us.min(2.0 * qubit.t1_us);
+        }
+        calibration
+    }
+}
+
+/// Drifting noise on the qubits of a device, recording the realized
+/// parameters
+/// In a memory experiment each data qubit decoheres for one time step of
+/// gates and one ancilla measurement per round while the syndrome is read
+/// perfectly, so only T1 and T2 may drift; `noise_model` still carries the
+/// static gate and readout errors for circuit-level simulations.
+pub struct DriftingNoise {
+    model: DriftModel,
+    /// Telegraph state of each drift in the current shot
+    levels: Vec<Option<bool>>,
+    /// Value of each drift in each round of the current shot
+    pub trajectory: Vec<Vec<f64>>,
+}
+
+/// Errors of one shot with the parameters they were drawn at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftSample {
+    pub trajectory: Vec<Vec<f64>>,
+    pub errors: Vec<Vec<Error>>,
+}
+
+impl DriftingNoise {
+    /// Noise of `model` on a code with `n_qubits` qubits, one per
+    /// calibrated qubit
+    /// Drifting gate or readout errors are rejected, since no round samples
+    /// them.
+    pub fn new(model: DriftModel, n_qubits: usize) -> Result<Self, NoiseError> {
+        model.validate()?;
+        if let Some(drift) = model.drifts.iter().find(|d| !d.parameter.acts_on_idle()) {
+            return Err(NoiseError::invalid(
+                &model.calibration.name,
+                drift.parameter.name(),
+                "only T1 and T2 can drift in a memory experiment",
+            ));
+        }
+        if model.calibration.qubits.len() != n_qubits {
+            return Err(NoiseError::QubitCountMismatch {
+                expected: n_qubits,
+                found: model.calibration.qubits.len(),
+            });
+        }
+        Ok(DriftingNoise {
+            levels: vec![None; model.drifts.len()],
+            model,
+            trajectory: Vec::new(),
+        })
+    }
+
+    pub fn model(&self) -> &DriftModel {
+        &self.model
+    }
+
+    /// Noise model of the last sampled round, or None before the first
+    pub fn noise_model(&self) -> Option<Result<NoiseModel, NoiseError>> {
+        let values = self.trajectory.last()?;
+        Some(self.model.calibration_at(values).noise_model())
+    }
+
+    /// Draw a full shot of `rounds` rounds
+    pub fn sample_shot(
+        &mut self,
+        rounds: usize,
+        rng: &mut SmallRng,
+    ) -> Result<DriftSample, NoiseError> {
+        self.reset();
+        let errors = (0..rounds)
+            .map(|round| self.sample_round(round, rng))
+            .collect::<Result<_, _>>()?;
+        Ok(DriftSample {
+            trajectory: self.trajectory.clone(),
+            errors,
+        })
+    }
+}
+
+impl RoundNoise for DriftingNoise {
+    fn reset(&mut self) {
+        self.trajectory.clear();
+        self.levels.fill(None);
+    }
+
+    fn sample_round(&mut self, round: usize, rng: &mut SmallRng) -> Result<Vec<Error>, NoiseError> {
+        let values: Vec<f64> = self
+            .model
+            .drifts
+            .iter()
+            .zip(&mut self.levels)
+            .map(|(drift, level)| drift.schedule.next(round, level, rng))
+            .collect();
+        let model = self.model.calibration_at(&values).noise_model()?;
+        self.trajectory.push(values);
+        let qubits: Vec<usize> = (0..self.model.calibration.qubits.len()).collect();
+        let mut errors = model.idle(&qubits, rng)?;
+        errors.extend(model.measurement_idle(&qubits, rng)?);
+        Ok(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibration::{GateDurations, QubitCalibration};
+    use crate::codes::get_stabilizer_code;
+    use crate::decoder::LookupDecoder;
+    use crate::memory::run_shot;
+    use rand::SeedableRng;
+
+    fn device(n_qubits: usize) -> DeviceCalibration {
+        let qubit = QubitCalibration {
+            t1_us: 1e9,
+            t2_us: 1e9,
+            single_qubit_gate_error: 0.0,
+            readout: [[1.0, 0.0], [0.0, 1.0]],
+        };
+        DeviceCalibration {
+            name: "chip".to_string(),
+            qubits: vec![qubit; n_qubits],
+            couplers: Vec::new(),
+            durations: GateDurations {
+                single_qubit_ns: 30.0,
+                two_qubit_ns: 300.0,
+                measurement_ns: 1000.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_linear_and_recalibrated() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let linear = Schedule::Linear {
+            start: 0.001,
+            slope: 0.001,
+        };
+        let values = linear.realize(3, &mut rng).unwrap();
+        assert!((values[2] - 0.003).abs() < 1e-12);
+
+        // Recalibration every 2 rounds turns the drift into a sawtooth
+        let sawtooth = Schedule::Recalibrated {
+            schedule: Box::new(linear),
+            period: 2,
+        };
+        assert_eq!(
+            sawtooth.realize(4, &mut rng).unwrap(),
+            vec![0.001, 0.002, 0.001, 0.002]
+        );
+        let never = Schedule::Recalibrated {
+            schedule: Box::new(Schedule::Constant { value: 0.0 }),
+            period: 0,
+        };
+        assert!(never.validate().is_err());
+        assert!(never.realize(4, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_telegraph() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        let always = Schedule::Telegraph {
+            low: 0.001,
+            high: 0.01,
+            switch_rate: 1.0,
+        };
+        assert_eq!(
+            always.realize(4, &mut rng).unwrap(),
+            vec![0.001, 0.01, 0.001, 0.01]
+        );
+
+        let rare = Schedule::Telegraph {
+            low: 0.001,
+            high: 0.01,
+            switch_rate: 0.05,
+        };
+        let values = rare.realize(2000, &mut rng).unwrap();
+        let switches = values.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!((50..150).contains(&switches), "{}", switches);
+        assert!(values.iter().all(|&v| v == 0.001 || v == 0.01));
+
+        // The level is tracked, not read back from the value, so it still
+        // switches when both levels are equal
+        let flat = Schedule::Recalibrated {
+            schedule: Box::new(Schedule::Telegraph {
+                low: 0.001,
+                high: 0.001,
+                switch_rate: 1.0,
+            }),
+            period: 3,
+        };
+        assert_eq!(flat.realize(4, &mut rng).unwrap(), vec![0.001; 4]);
+    }
+
+    #[test]
+    fn test_calibration_drift() {
+        let mut calibration = device(2);
+        calibration.qubits[1].t2_us = 50.0;
+        let model = DriftModel::new(
+            calibration,
+            vec![
+                ParameterDrift {
+                    parameter: DriftParameter::T1 { qubit: Some(1) },
+                    schedule: Schedule::Constant { value: 10.0 },
+                },
+                ParameterDrift {
+                    parameter: DriftParameter::ReadoutError { qubit: None },
+                    schedule: Schedule::Constant { value: 0.02 },
+                },
+            ],
+        )
+        .unwrap();
+        let drifted = model.calibration_at(&[10.0, 0.02]);
+        assert_eq!(drifted.qubits[0].t1_us, 1e9);
+        assert_eq!(drifted.qubits[1].t1_us, 10.0);
+        // T2 follows T1 down to 2 T1
+        assert_eq!(drifted.qubits[1].t2_us, 20.0);
+        assert!(drifted
+            .qubits
+            .iter()
+            .all(|q| q.readout == [[0.98, 0.02], [0.02, 0.98]]));
+
+        // Missing qubits and invalid schedules are rejected on construction
+        let missing = ParameterDrift {
+            parameter: DriftParameter::T1 { qubit: Some(2) },
+            schedule: Schedule::Constant { value: 10.0 },
+        };
+        assert!(DriftModel::new(device(2), vec![missing]).is_err());
+        let json = r#"{
+            "calibration": {"name": "chip", "qubits": [], "durations":
+                {"single_qubit_ns": 30, "two_qubit_ns": 300, "measurement_ns": 1000}},
+            "drifts": [{"parameter": {"type": "gate_error"},
+                "schedule": {"type": "recalibrated", "period": 0,
+                    "schedule": {"type": "constant", "value": 0.01}}}]
+        }"#;
+        assert!(serde_json::from_str::<DriftModel>(json).is_ok());
+        assert!(DriftModel::from_json(json).is_err());
+        let unchecked: DriftModel = serde_json::from_str(json).unwrap();
+        assert!(DriftingNoise::new(unchecked, 0).is_err());
+    }
+
+    #[test]
+    fn test_gate_and_readout_drift_rejected_in_memory() {
+        for parameter in [
+            DriftParameter::SingleQubitGateError { qubit: None },
+            DriftParameter::ReadoutError { qubit: Some(0) },
+            DriftParameter::GateError { coupler: None },
+        ] {
+            let drift = ParameterDrift {
+                parameter,
+                schedule: Schedule::Constant { value: 0.01 },
+            };
+            let model = DriftModel::new(device(2), vec![drift]).unwrap();
+            assert!(matches!(
+                DriftingNoise::new(model, 2),
+                Err(NoiseError::InvalidParameter { .. })
+            ));
+        }
+        let t2 = ParameterDrift {
+            parameter: DriftParameter::T2 { qubit: None },
+            schedule: Schedule::Constant { value: 100.0 },
+        };
+        let model = DriftModel::new(device(2), vec![t2]).unwrap();
+        assert!(DriftingNoise::new(model, 2).is_ok());
+    }
+
+    #[test]
+    fn test_trajectory_recorded_with_samples() {
+        let code = get_stabilizer_code("surface_d3").unwrap();
+        // T1 collapses after the first round, leaving the qubits fully mixed
+        let model = DriftModel::new(
+            device(code.n_qubits),
+            vec![ParameterDrift {
+                parameter: DriftParameter::T1 { qubit: None },
+                schedule: Schedule::Linear {
+                    start: 1e9,
+                    slope: -1e9,
+                },
+            }],
+        )
+        .unwrap();
+        assert!(DriftingNoise::new(model.clone(), 7).is_err());
+        let mut noise = DriftingNoise::new(model, code.n_qubits).unwrap();
+        assert!(noise.noise_model().is_none());
+        let mut rng = SmallRng::seed_from_u64(3);
+        let sample = noise.sample_shot(4, &mut rng).unwrap();
+        assert_eq!(sample.trajectory.len(), 4);
+        assert_eq!(sample.trajectory[0], vec![1e9]);
+        assert!(sample.errors[0].is_empty());
+        assert!(!sample.errors[1].is_empty());
+        let idle = noise.noise_model().unwrap().unwrap().qubits[&0].idle;
+        assert!(idle.unwrap().probabilities()[0] > 0.2);
+
+        // Memory experiments leave the trajectory of the last shot behind
+        let decoder = LookupDecoder::new(&code, 1);
+        run_shot(&code, &mut noise, &decoder, 6, &mut rng).unwrap();
+        assert_eq!(noise.trajectory.len(), 6);
+        assert_eq!(noise.trajectory[5], vec![-4e9]);
+
+        let json = serde_json::to_string(noise.model()).unwrap();
+        assert_eq!(&DriftModel::from_json(&json).unwrap(), noise.model());
+    }
+}
@@ -0,0 +1,268 @@
+#![allow(dead_code)]
+use crate::codes::StabilizerCode;
+use crate::pauli::{Pauli, PauliString};
+use serde::{Deserialize, Serialize};
+
+/// Largest stabilizer group, as log2 of its size, that is enumerated
+/// element by element
+const MAX_ENUMERATED_LOG2: usize = 24;
+
+/// Shor-Laflamme weight enumerators of a stabilizer code
+/// A_w counts the stabilizers of weight w and B_w the elements of the
+/// normalizer N(S) of weight w, both up to sign. They satisfy the quantum
+/// MacWilliams identity B(x, y) = A(x + 3y, x - y) / 2^(n - k).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeightEnumerators {
+    pub n_qubits: usize,
+    pub n_logical: usize,
+    /// A_0..A_n
+    pub stabilizer: Vec<u64>,
+    /// B_0..B_n
+    pub normalizer: Vec<u64>,
+}
+
+impl WeightEnumerators {
+    /// Enumerators of a code, or None if its stabilizer group is too large
+    /// Only S is enumerated; B follows from the MacWilliams transform, which
+    /// avoids walking the 4^k times larger normalizer.
+    pub fn of(code: &StabilizerCode) -> Option<Self> {
+        let n = code.n_qubits;
+        let k = code.logical_x.len();
+        let stabilizer = stabilizer_weights(code)?;
+        let normalizer = macwilliams_transform(&stabilizer, n, n - k)?;
+        Some(WeightEnumerators {
+            n_qubits: n,
+            n_logical: k,
+            stabilizer,
+            normalizer,
+        })
+    }
+
+    /// Check B(x, y) = A(x + 3y, x - y) / 2^(n - k) coefficient by coefficient
+    pub fn satisfies_macwilliams(&self) -> bool {
+        macwilliams_transform(
+            &self.stabilizer,
+            self.n_qubits,
+            self.n_qubits - self.n_logical,
+        )
+        .is_some_and(|b| b == self.normalizer)
+    }
+
+    /// Number of nontrivial logical operators of each weight, B_w - A_w
+    pub fn logical_weights(&self) -> Vec<u64> {
+        self.normalizer
+            .iter()
+            .zip(&self.stabilizer)
+            .map(|(b, a)| b - a)
+            .collect()
+    }
+
+    /// Smallest weight w > 0 with B_w > A_w; None for k = 0
+    pub fn distance(&self) -> Option<usize> {
+        self.logical_weights()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find(|&(_, &count)| count > 0)
+            .map(|(w, _)| w)
+    }
+
+    /// Coefficients c_j of the code-capacity logical error rate Σ_j c_j p^j
+    /// under depolarizing noise of strength p
+    /// This is the probability that the error itself is a nontrivial logical
+    /// operator, Σ_w (B_w - A_w) (p/3)^w (1 - p)^(n - w): such errors have a
+    /// trivial syndrome and defeat every decoder.
+    pub fn logical_error_polynomial(&self) -> Vec<f64> {
+        let n = self.n_qubits;
+        let mut coefficients = vec![0.0; n + 1];
+        for (w, &count) in self.logical_weights().iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let scale = count as f64 / 3f64.powi(w as i32);
+            for i in 0..=n - w {
+                let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+                coefficients[w + i] += sign * scale * binomial(n - w, i) as f64;
+            }
+        }
+        coefficients
+    }
+
+    /// Logical error rate of `logical_error_polynomial` at physical rate `p`
+    pub fn logical_error_rate(&self, p: f64) -> f64 {
+        let n = self.n_qubits;
+        self.logical_weights()
+            .iter()
+            .enumerate()
+            .map(|(w, &count)| {
+                count as f64 * (p / 3.0).powi(w as i32) * (1.0 - p).powi((n - w) as i32)
+            })
+            .sum()
+    }
+}
+
+/// A_w by walking the stabilizer group
+pub fn stabilizer_weights(code: &StabilizerCode) -> Option<Vec<u64>> {
+    group_weights(&code.generators, code.n_qubits)
+}
+
+/// B_w by walking the normalizer, generated by the stabilizers and logicals
+pub fn normalizer_weights(code: &StabilizerCode) -> Option<Vec<u64>> {
+    let generators: Vec<PauliString> = code
+        .generators
+        .iter()
+        .chain(&code.logical_x)
+        .chain(&code.logical_z)
+        .cloned()
+        .collect();
+    group_weights(&generators, code.n_qubits)
+}
+
+/// Weight distribution of the group generated by independent `generators`
+/// Elements are visited in Gray code order, so each step multiplies in a
+/// single generator.
+fn group_weights(generators: &[PauliString], n_qubits: usize) -> Option<Vec<u64>> {
+    if generators.len() > MAX_ENUMERATED_LOG2 {
+        return None;
+    }
+    let words = n_qubits.div_ceil(64);
+    let packed: Vec<(Vec<u64>, Vec<u64>)> = generators
+        .iter()
+        .map(|g| {
+            let (mut x, mut z) = (vec![0u64; words], vec![0u64; words]);
+            for (q, p) in g.paulis.iter().enumerate() {
+                let bit = 1u64 << (q % 64);
+                if matches!(p, Pauli::X | Pauli::Y) {
+                    x[q / 64] |= bit;
+                }
+                if matches!(p, Pauli::Z | Pauli::Y) {
+                    z[q / 64] |= bit;
+                }
+            }
+            (x, z)
+        })
+        .collect();
+
+    let mut counts = vec![0u64; n_qubits + 1];
+    counts[0] = 1;
+    let (mut x, mut z) = (vec![0u64; words], vec![0u64; words]);
+    for step in 1u64..1 << generators.len() {
+        let (gx, gz) = &packed[step.trailing_zeros() as usize];
+        let mut weight = 0;
+        for i in 0..words {
+            x[i] ^= gx[i];
+            z[i] ^= gz[i];
+            weight += (x[i] | z[i]).count_ones() as usize;
+        }
+        counts[weight] += 1;
+    }
+    Some(counts)
+}
+
+/// Transform C'(x, y) = C(x + 3y, x - y) / 2^scale_log2 of a weight
+/// enumerator on n qubits
+/// With scale n - k this maps A to B, and with scale n + k B back to A.
+/// None if a coefficient is not a nonnegative integer or overflows.
+pub fn macwilliams_transform(
+    coefficients: &[u64],
+    n: usize,
+    scale_log2: usize,
+) -> Option<Vec<u64>> {
+    let mut out = vec![0i128; n + 1];
+    for (w, &count) in coefficients.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        // (x + 3y)^(n - w) (x - y)^w, collecting powers of y
+        for i in 0..=n - w {
+            let left = binomial(n - w, i).checked_mul(3i128.checked_pow(i as u32)?)?;
+            for j in 0..=w {
+                let term = (count as i128)
+                    .checked_mul(left)?
+                    .checked_mul(binomial(w, j))?;
+                let term = if j % 2 == 0 { term } else { -term };
+                out[i + j] = out[i + j].checked_add(term)?;
+            }
+        }
+    }
+    let scale = 1i128.checked_shl(scale_log2 as u32)?;
+    out.into_iter()
+        .map(|c| (c >= 0 && c % scale == 0).then(|| (c / scale) as u64))
+        .collect()
+}
+
+fn binomial(n: usize, k: usize) -> i128 {
+    (0..k).fold(1i128, |acc, i| acc * (n - i) as i128 / (i as i128 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::get_stabilizer_code;
+
+    #[test]
+    fn test_five_qubit_enumerators() {
+        let code = get_stabilizer_code("five_qubit").unwrap();
+        let enumerators = WeightEnumerators::of(&code).unwrap();
+        assert_eq!(enumerators.stabilizer, vec![1, 0, 0, 0, 15, 0]);
+        assert_eq!(enumerators.normalizer, vec![1, 0, 0, 30, 15, 18]);
+        assert_eq!(enumerators.distance(), Some(3));
+    }
+
+    #[test]
+    fn test_macwilliams_identity() {
+        for name in ["repetition_3", "five_qubit", "steane", "surface_d3"] {
+            let code = get_stabilizer_code(name).unwrap();
+            let n = code.n_qubits;
+            let k = code.logical_x.len();
+            // Both groups enumerated directly, independently of the transform
+            let enumerators = WeightEnumerators {
+                n_qubits: n,
+                n_logical: k,
+                stabilizer: stabilizer_weights(&code).unwrap(),
+                normalizer: normalizer_weights(&code).unwrap(),
+            };
+            assert!(enumerators.satisfies_macwilliams(), "{}", name);
+            assert_eq!(
+                WeightEnumerators::of(&code).unwrap(),
+                enumerators,
+                "{}",
+                name
+            );
+            assert_eq!(enumerators.distance(), Some(code.distance), "{}", name);
+            // The inverse transform recovers A from B
+            assert_eq!(
+                macwilliams_transform(&enumerators.normalizer, n, n + k),
+                Some(enumerators.stabilizer.clone()),
+                "{}",
+                name
+            );
+        }
+        // A wrong enumerator is caught
+        let mut broken = WeightEnumerators::of(&get_stabilizer_code("steane").unwrap()).unwrap();
+        broken.stabilizer[4] -= 1;
+        broken.stabilizer[6] += 1;
+        assert!(!broken.satisfies_macwilliams());
+    }
+
+    #[test]
+    fn test_logical_error_polynomial() {
+        let code = get_stabilizer_code("five_qubit").unwrap();
+        let enumerators = WeightEnumerators::of(&code).unwrap();
+        let polynomial = enumerators.logical_error_polynomial();
+        // Leading term: 30 weight-3 logicals, each with probability (p/3)^3
+        assert_eq!(&polynomial[..3], &[0.0, 0.0, 0.0]);
+        assert!((polynomial[3] - 30.0 / 27.0).abs() < 1e-12);
+        for p in [0.0f64, 0.01, 0.1, 0.5] {
+            let value: f64 = polynomial
+                .iter()
+                .enumerate()
+                .map(|(j, c)| c * p.powi(j as i32))
+                .sum();
+            assert!((value - enumerators.logical_error_rate(p)).abs() < 1e-12);
+        }
+        // At p = 3/4 every Pauli is equally likely: |N \ S| / 4^n
+        let uniform = enumerators.logical_error_rate(0.75);
+        assert!((uniform - (64.0 - 16.0) / 1024.0).abs() < 1e-12);
+    }
+}
@@ -1,6 +1,72 @@
-use crate::pauli::Pauli;
+use crate::codes::StabilizerCode;
+use crate::pauli::{Pauli, PauliString, Phase};
 use crate::stabilizer::StabilizerState;
+use crate::symplectic;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Reasons a noise channel, noise model or calibration is rejected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum NoiseError {
+    /// A parameter outside its allowed range
+    InvalidParameter {
+        model: String,
+        parameter: String,
+        reason: String,
+    },
+    /// A two-qubit table key that is not a two-qubit Pauli string
+    UnknownOutcome(String),
+    /// A noise model or calibration file that could not be parsed
+    InvalidFile { format: String, reason: String },
+    /// A preset name that no noise model is known by
+    UnknownPreset(String),
+    /// Noise defined on a different number of qubits than the code
+    QubitCountMismatch { expected: usize, found: usize },
+}
+
+impl NoiseError {
+    /// `InvalidParameter` error for `parameter` of `model`
+    pub fn invalid(
+        model: impl Into<String>,
+        parameter: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        NoiseError::InvalidParameter {
+            model: model.into(),
+            parameter: parameter.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for NoiseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoiseError::InvalidParameter {
+                model,
+                parameter,
+                reason,
+            } => write!(
+                f,
+                "Invalid parameter {} for {}: {}",
+                parameter, model, reason
+            ),
+            NoiseError::UnknownOutcome(s) => write!(f, "Not a two-qubit Pauli: {}", s),
+            NoiseError::InvalidFile { format, reason } => {
+                write!(f, "Invalid {} file: {}", format, reason)
+            }
+            NoiseError::UnknownPreset(name) => write!(f, "Unknown noise preset: {}", name),
+            NoiseError::QubitCountMismatch { expected, found } => {
+                write!(f, "Noise acts on {} qubits, expected {}", found, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NoiseError {}
 
 /// Error types that can be applied to qubits
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -10,8 +76,29 @@ pub enum ErrorType {
     Z, // Phase flip
 }
 
+impl ErrorType {
+    /// Single-qubit Pauli of this error
+    pub fn to_pauli(self) -> Pauli {
+        match self {
+            ErrorType::X => Pauli::X,
+            ErrorType::Y => Pauli::Y,
+            ErrorType::Z => Pauli::Z,
+        }
+    }
+
+    /// Error type of a non-identity Pauli
+    pub fn from_pauli(pauli: Pauli) -> Option<Self> {
+        match pauli {
+            Pauli::I => None,
+            Pauli::X => Some(ErrorType::X),
+            Pauli::Y => Some(ErrorType::Y),
+            Pauli::Z => Some(ErrorType::Z),
+        }
+    }
+}
+
 /// Represents an error on a specific qubit
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Error {
     pub qubit: usize,
     pub error_type: ErrorType,
@@ -22,48 +109,489 @@ impl Error {
         Error { qubit, error_type }
     }
 
-    /// Apply this error to a stabilizer state
-    pub fn apply_to_state(&self, state: &mut StabilizerState) {
-        // Errors are just Pauli operations
-        match self.error_type {
-            ErrorType::X => {
-                // X is H-Z-H, or we can implement directly
-                // For stabilizer formalism, X flips the sign of any stabilizer
-                // that has Z on this qubit
-                for i in 0..state.n_qubits() {
-                    if state.get_stabilizer(i).paulis[self.qubit] == Pauli::Z
-                        || state.get_stabilizer(i).paulis[self.qubit] == Pauli::Y
-                    {
-                        let mut stab = state.get_stabilizer(i);
-                        stab.phase = stab.phase.negate();
-                        state.set_stabilizer(i, &stab);
-                    }
-                }
+    /// This error as a Pauli string on `n_qubits` qubits
+    pub fn to_pauli_string(&self, n_qubits: usize) -> PauliString {
+        PauliString::single(n_qubits, self.qubit, self.error_type.to_pauli())
+    }
+}
+
+/// Independent single-qubit Pauli noise applied to every qubit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PauliChannel {
+    /// X, Y and Z each with probability p/3
+    Depolarizing { p: f64 },
+    /// X with probability p
+    BitFlip { p: f64 },
+    /// Z with probability p
+    PhaseFlip { p: f64 },
+    /// Y with probability p
+    YFlip { p: f64 },
+    /// X, Y and Z with independent probabilities
+    Pauli { px: f64, py: f64, pz: f64 },
+    /// Total probability p with pz / (px + py) = eta and px = py
+    /// eta = 0.5 is depolarizing noise, large eta approaches phase flips
+    Biased { p: f64, eta: f64 },
+}
+
+impl PauliChannel {
+    /// Name used in error messages and serialized as "type"
+    pub fn name(&self) -> &'static str {
+        match self {
+            PauliChannel::Depolarizing { .. } => "depolarizing",
+            PauliChannel::BitFlip { .. } => "bit_flip",
+            PauliChannel::PhaseFlip { .. } => "phase_flip",
+            PauliChannel::YFlip { .. } => "y_flip",
+            PauliChannel::Pauli { .. } => "pauli",
+            PauliChannel::Biased { .. } => "biased",
+        }
+    }
+
+    /// Probabilities (px, py, pz) of each error on one qubit
+    pub fn probabilities(&self) -> [f64; 3] {
+        match *self {
+            PauliChannel::Depolarizing { p } => [p / 3.0; 3],
+            PauliChannel::BitFlip { p } => [p, 0.0, 0.0],
+            PauliChannel::PhaseFlip { p } => [0.0, 0.0, p],
+            PauliChannel::YFlip { p } => [0.0, p, 0.0],
+            PauliChannel::Pauli { px, py, pz } => [px, py, pz],
+            PauliChannel::Biased { p, eta } => {
+                let transverse = p / (2.0 * (eta + 1.0));
+                [transverse, transverse, p * eta / (eta + 1.0)]
             }
-            ErrorType::Y => {
-                // Y error flips signs of stabilizers with X or Z
-                for i in 0..state.n_qubits() {
-                    let pauli_at_qubit = state.get_stabilizer(i).paulis[self.qubit];
-                    if pauli_at_qubit != Pauli::I && pauli_at_qubit != Pauli::Y {
-                        let mut stab = state.get_stabilizer(i);
-                        stab.phase = stab.phase.negate();
-                        state.set_stabilizer(i, &stab);
-                    }
-                }
+        }
+    }
+
+    /// Check that the parameters describe a valid probability distribution
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        if let PauliChannel::Biased { eta, .. } = *self {
+            if !(eta.is_finite() && eta >= 0.0) {
+                return Err(NoiseError::invalid(
+                    self.name(),
+                    "eta",
+                    "must be finite and non-negative",
+                ));
             }
-            ErrorType::Z => {
-                // Z error flips signs of stabilizers with X or Y
-                for i in 0..state.n_qubits() {
-                    if state.get_stabilizer(i).paulis[self.qubit] == Pauli::X
-                        || state.get_stabilizer(i).paulis[self.qubit] == Pauli::Y
-                    {
-                        let mut stab = state.get_stabilizer(i);
-                        stab.phase = stab.phase.negate();
-                        state.set_stabilizer(i, &stab);
+        }
+        let probabilities = self.probabilities();
+        if probabilities.iter().any(|p| !(0.0..=1.0).contains(p)) {
+            return Err(NoiseError::invalid(
+                self.name(),
+                "p",
+                "probabilities must lie in [0, 1]",
+            ));
+        }
+        if probabilities.iter().sum::<f64>() > 1.0 + 1e-12 {
+            return Err(NoiseError::invalid(
+                self.name(),
+                "p",
+                "probabilities sum to more than 1",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Draw the error on one qubit, or None for no error
+    pub fn sample_qubit<R: Rng>(&self, rng: &mut R) -> Result<Option<ErrorType>, NoiseError> {
+        self.validate()?;
+        Ok(self.draw(rng))
+    }
+
+    /// `sample_qubit` for a channel that is already validated
+    fn draw<R: Rng>(&self, rng: &mut R) -> Option<ErrorType> {
+        let [px, py, pz] = self.probabilities();
+        let r: f64 = rng.gen();
+        if r < px {
+            Some(ErrorType::X)
+        } else if r < px + py {
+            Some(ErrorType::Y)
+        } else if r < px + py + pz {
+            Some(ErrorType::Z)
+        } else {
+            None
+        }
+    }
+
+    /// Draw independent errors on qubits 0..n_qubits
+    pub fn sample<R: Rng>(&self, n_qubits: usize, rng: &mut R) -> Result<Vec<Error>, NoiseError> {
+        self.validate()?;
+        Ok((0..n_qubits)
+            .filter_map(|qubit| self.draw(rng).map(|t| Error::new(qubit, t)))
+            .collect())
+    }
+
+    /// Draw errors on every qubit of `code` from a `SmallRng` seeded with `seed`
+    pub fn sample_code(&self, code: &StabilizerCode, seed: u64) -> Result<Vec<Error>, NoiseError> {
+        self.sample(code.n_qubits, &mut SmallRng::seed_from_u64(seed))
+    }
+}
+
+/// Two-qubit Pauli noise acting jointly on a pair of qubits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TwoQubitChannel {
+    /// Each of the 15 non-identity two-qubit Paulis with probability p/15
+    Depolarizing { p: f64 },
+    /// Correlated ZZ error with probability p, as left by residual coupling
+    Crosstalk { p: f64 },
+    /// Arbitrary channel: probability of each two-qubit Pauli such as "XZ"
+    Table {
+        probabilities: BTreeMap<String, f64>,
+    },
+}
+
+impl TwoQubitChannel {
+    /// Name used in error messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            TwoQubitChannel::Depolarizing { .. } => "two_qubit_depolarizing",
+            TwoQubitChannel::Crosstalk { .. } => "crosstalk",
+            TwoQubitChannel::Table { .. } => "two_qubit_table",
+        }
+    }
+
+    /// Non-identity outcomes with their probabilities, or an error for table
+    /// entries that are not two-qubit Pauli strings
+    pub fn outcomes(&self) -> Result<Vec<([Pauli; 2], f64)>, NoiseError> {
+        match self {
+            TwoQubitChannel::Depolarizing { p } => Ok(Pauli::ALL
+                .iter()
+                .flat_map(|&a| Pauli::ALL.iter().map(move |&b| [a, b]))
+                .skip(1)
+                .map(|pair| (pair, p / 15.0))
+                .collect()),
+            TwoQubitChannel::Crosstalk { p } => Ok(vec![([Pauli::Z, Pauli::Z], *p)]),
+            TwoQubitChannel::Table { probabilities } => probabilities
+                .iter()
+                .map(|(key, &p)| {
+                    let paulis: Option<Vec<Pauli>> = key.chars().map(Pauli::from_char).collect();
+                    match paulis.as_deref() {
+                        Some(&[a, b]) => Ok(([a, b], p)),
+                        _ => Err(NoiseError::UnknownOutcome(key.clone())),
                     }
-                }
+                })
+                .filter(|outcome| !matches!(outcome, Ok(([Pauli::I, Pauli::I], _))))
+                .collect(),
+        }
+    }
+
+    /// Check that the outcomes form a valid probability distribution
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        let outcomes = self.outcomes()?;
+        if outcomes.iter().any(|(_, p)| !(0.0..=1.0).contains(p)) {
+            return Err(NoiseError::invalid(
+                self.name(),
+                "p",
+                "probabilities must lie in [0, 1]",
+            ));
+        }
+        if outcomes.iter().map(|(_, p)| p).sum::<f64>() > 1.0 + 1e-12 {
+            return Err(NoiseError::invalid(
+                self.name(),
+                "p",
+                "probabilities sum to more than 1",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Draw the error on one pair, or None for no error
+    pub fn sample_pair<R: Rng>(&self, rng: &mut R) -> Result<Option<[Pauli; 2]>, NoiseError> {
+        self.validate()?;
+        let outcomes = self.outcomes()?;
+        Ok(draw_outcome(&outcomes, rng))
+    }
+}
+
+/// Draw one of `outcomes`, or None with the remaining probability
+fn draw_outcome<R: Rng>(outcomes: &[([Pauli; 2], f64)], rng: &mut R) -> Option<[Pauli; 2]> {
+    let mut r: f64 = rng.gen();
+    for &(pair, p) in outcomes {
+        if r < p {
+            return Some(pair);
+        }
+        r -= p;
+    }
+    None
+}
+
+/// A two-qubit channel attached to specific qubit pairs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwoQubitNoise {
+    pub channel: TwoQubitChannel,
+    /// Pairs the channel acts on; empty means every pair of qubits that
+    /// share a stabilizer generator of the code
+    #[serde(default)]
+    pub pairs: Vec<[usize; 2]>,
+}
+
+/// Pairs of distinct qubits that appear together in some generator of `code`
+pub fn stabilizer_pairs(code: &StabilizerCode) -> Vec<[usize; 2]> {
+    let mut pairs: Vec<[usize; 2]> = Vec::new();
+    for generator in &code.generators {
+        let support: Vec<usize> = (0..code.n_qubits)
+            .filter(|&q| generator.paulis[q] != Pauli::I)
+            .collect();
+        for (i, &a) in support.iter().enumerate() {
+            pairs.extend(support[i + 1..].iter().map(|&b| [a, b]));
+        }
+    }
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+impl TwoQubitNoise {
+    /// Check the channel and that every pair is two distinct qubits of `code`
+    pub fn validate(&self, code: &StabilizerCode) -> Result<(), NoiseError> {
+        self.channel.validate()?;
+        if let Some([a, b]) = self
+            .pairs
+            .iter()
+            .find(|[a, b]| a == b || *a >= code.n_qubits || *b >= code.n_qubits)
+        {
+            return Err(NoiseError::invalid(
+                self.channel.name(),
+                "pairs",
+                format!("({}, {}) is not a pair of distinct qubits", a, b),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Pairs the channel acts on for `code`
+    pub fn pairs_for(&self, code: &StabilizerCode) -> Vec<[usize; 2]> {
+        if self.pairs.is_empty() {
+            stabilizer_pairs(code)
+        } else {
+            self.pairs.clone()
+        }
+    }
+
+    /// Draw independent errors on every pair
+    /// A qubit in several pairs may appear more than once; the errors
+    /// compose by multiplication, e.g. in a `PauliFrame`.
+    pub fn sample<R: Rng>(
+        &self,
+        code: &StabilizerCode,
+        rng: &mut R,
+    ) -> Result<Vec<Error>, NoiseError> {
+        self.validate(code)?;
+        let outcomes = self.channel.outcomes()?;
+        let mut errors = Vec::new();
+        for pair in self.pairs_for(code) {
+            if let Some(paulis) = draw_outcome(&outcomes, rng) {
+                errors.extend(
+                    pair.iter()
+                        .zip(paulis)
+                        .filter_map(|(&q, p)| ErrorType::from_pauli(p).map(|t| Error::new(q, t))),
+                );
+            }
+        }
+        Ok(errors)
+    }
+
+    /// Draw errors from a `SmallRng` seeded with `seed`
+    pub fn sample_code(&self, code: &StabilizerCode, seed: u64) -> Result<Vec<Error>, NoiseError> {
+        self.sample(code, &mut SmallRng::seed_from_u64(seed))
+    }
+}
+
+/// Noise that flags the qubits it hits, as in erasure-converted hardware
+/// An erased qubit is replaced by the maximally mixed state, i.e. I, X, Y or
+/// Z with probability 1/4 each, and its location is known to the decoder.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ErasureChannel {
+    /// Erase each qubit with probability p
+    Erasure { p: f64 },
+    /// Erasure conversion, e.g. dual-rail transmons: each qubit suffers a
+    /// fault with probability p, of which `erasure_fraction` are detected and
+    /// become erasures while the rest are silent depolarizing errors
+    Conversion { p: f64, erasure_fraction: f64 },
+}
+
+/// Errors and erasure flags drawn from an `ErasureChannel`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErasureSample {
+    /// One flag per qubit, `true` where the qubit was erased
+    pub erasures: Vec<bool>,
+    /// Pauli errors, both on erased qubits and silent ones elsewhere
+    pub errors: Vec<Error>,
+}
+
+impl ErasureChannel {
+    /// Name used in error messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErasureChannel::Erasure { .. } => "erasure",
+            ErasureChannel::Conversion { .. } => "erasure_conversion",
+        }
+    }
+
+    /// Probabilities (erasure, silent error) per qubit
+    pub fn probabilities(&self) -> (f64, f64) {
+        match *self {
+            ErasureChannel::Erasure { p } => (p, 0.0),
+            ErasureChannel::Conversion {
+                p,
+                erasure_fraction,
+            } => (p * erasure_fraction, p * (1.0 - erasure_fraction)),
+        }
+    }
+
+    /// Check that p and the erasure fraction are probabilities
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        let (p, fraction) = match *self {
+            ErasureChannel::Erasure { p } => (p, 1.0),
+            ErasureChannel::Conversion {
+                p,
+                erasure_fraction,
+            } => (p, erasure_fraction),
+        };
+        for (parameter, value) in [("p", p), ("erasure_fraction", fraction)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(NoiseError::invalid(
+                    self.name(),
+                    parameter,
+                    "must lie in [0, 1]",
+                ));
             }
         }
+        Ok(())
+    }
+
+    /// Draw erasures and errors on qubits 0..n_qubits
+    pub fn sample<R: Rng>(
+        &self,
+        n_qubits: usize,
+        rng: &mut R,
+    ) -> Result<ErasureSample, NoiseError> {
+        self.validate()?;
+        let (p_erasure, p_silent) = self.probabilities();
+        let silent = PauliChannel::Depolarizing { p: 1.0 };
+        let mut sample = ErasureSample {
+            erasures: vec![false; n_qubits],
+            errors: Vec::new(),
+        };
+        for qubit in 0..n_qubits {
+            let r: f64 = rng.gen();
+            let error = if r < p_erasure {
+                sample.erasures[qubit] = true;
+                ErrorType::from_pauli(Pauli::ALL[rng.gen_range(0..4)])
+            } else if r < p_erasure + p_silent {
+                silent.draw(rng)
+            } else {
+                None
+            };
+            sample.errors.extend(error.map(|t| Error::new(qubit, t)));
+        }
+        Ok(sample)
+    }
+
+    /// Draw erasures and errors on every qubit of `code` from a `SmallRng`
+    /// seeded with `seed`
+    pub fn sample_code(
+        &self,
+        code: &StabilizerCode,
+        seed: u64,
+    ) -> Result<ErasureSample, NoiseError> {
+        self.sample(code.n_qubits, &mut SmallRng::seed_from_u64(seed))
+    }
+}
+
+/// Accumulated Pauli error on every qubit, up to global phase
+/// Errors compose by multiplication, so X then Z on one qubit is Y and
+/// applying the same error twice cancels it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PauliFrame {
+    pauli: PauliString,
+}
+
+/// Coordinates of a frame in the symplectic basis of a code: the frame is
+/// the product of the destabilizers flagged by `syndrome`, the logical
+/// operators flagged by `logical_x` / `logical_z` and the generators flagged
+/// by `stabilizer`, up to phase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FrameDecomposition {
+    pub syndrome: Vec<bool>,
+    pub stabilizer: Vec<bool>,
+    pub logical_x: Vec<bool>,
+    pub logical_z: Vec<bool>,
+}
+
+impl PauliFrame {
+    /// Error-free frame on `n_qubits` qubits
+    pub fn new(n_qubits: usize) -> Self {
+        PauliFrame {
+            pauli: PauliString::identity(n_qubits),
+        }
+    }
+
+    /// Number of qubits
+    pub fn n_qubits(&self) -> usize {
+        self.pauli.n_qubits()
+    }
+
+    /// Accumulated error as a Pauli string with phase +1
+    pub fn pauli(&self) -> &PauliString {
+        &self.pauli
+    }
+
+    /// Compose an arbitrary Pauli error into the frame
+    pub fn apply(&mut self, error: &PauliString) {
+        self.pauli = error.multiply(&self.pauli);
+        self.pauli.phase = Phase::Plus;
+    }
+
+    /// Compose a single-qubit error into the frame
+    pub fn apply_error(&mut self, error: &Error) {
+        self.apply(&error.to_pauli_string(self.n_qubits()));
+    }
+
+    /// Replace the error on `qubit` (`Pauli::I` clears it), returning the
+    /// Pauli that takes the old frame to the new one
+    pub fn set_qubit(&mut self, qubit: usize, pauli: Pauli) -> PauliString {
+        let (_, change) = self.pauli.paulis[qubit].multiply(pauli);
+        self.pauli.paulis[qubit] = pauli;
+        PauliString::single(self.n_qubits(), qubit, change)
+    }
+
+    /// Non-identity single-qubit factors of the frame
+    pub fn errors(&self) -> Vec<Error> {
+        self.pauli
+            .paulis
+            .iter()
+            .enumerate()
+            .filter_map(|(qubit, &p)| ErrorType::from_pauli(p).map(|t| Error::new(qubit, t)))
+            .collect()
+    }
+
+    /// Decompose the frame in the basis of `code`'s generators, logical
+    /// operators and destabilizers
+    /// Each coordinate is a symplectic inner product with the dual basis
+    /// element: stabilizers give the syndrome and destabilizers the
+    /// stabilizer part, while logical X and Z swap roles.
+    pub fn decompose(&self, code: &StabilizerCode) -> FrameDecomposition {
+        assert_eq!(code.n_qubits, self.n_qubits());
+        let logicals: Vec<PauliString> = code
+            .logical_x
+            .iter()
+            .chain(&code.logical_z)
+            .cloned()
+            .collect();
+        let destabilizers = symplectic::destabilizers(&code.generators, &logicals);
+        let flips = |ops: &[PauliString]| {
+            ops.iter()
+                .map(|op| !op.commutes_with(&self.pauli))
+                .collect()
+        };
+        FrameDecomposition {
+            syndrome: flips(&code.generators),
+            stabilizer: flips(&destabilizers),
+            logical_x: flips(&code.logical_z),
+            logical_z: flips(&code.logical_x),
+        }
     }
 }
 
@@ -72,6 +600,9 @@ impl Error {
 pub struct Syndrome {
     /// Measurement outcomes for each stabilizer (+1 or -1)
     pub outcomes: Vec<i32>,
+    /// Erasure flag for each qubit, empty when erasures are not tracked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub erasures: Vec<bool>,
 }
 
 impl Syndrome {
@@ -85,12 +616,21 @@ impl Syndrome {
             })
             .collect();
 
-        Syndrome { outcomes }
+        Syndrome {
+            outcomes,
+            erasures: Vec::new(),
+        }
+    }
+
+    /// Attach erasure flags, one per qubit
+    pub fn with_erasures(mut self, erasures: Vec<bool>) -> Self {
+        self.erasures = erasures;
+        self
     }
 
     /// Check if syndrome indicates an error (any -1 outcome)
     pub fn has_error(&self) -> bool {
-        self.outcomes.iter().any(|&o| o == -1)
+        self.outcomes.contains(&-1)
     }
 
     /// Get indices of triggered stabilizers (outcomes = -1)
@@ -120,7 +660,7 @@ mod tests {
 
         // Apply X error on qubit 0
         let error = Error::new(0, ErrorType::X);
-        error.apply_to_state(&mut state);
+        state.apply_pauli(&error.to_pauli_string(3));
 
         // Should trigger stabilizer 0 (ZII)
         let syndrome = Syndrome::from_state(&state);
@@ -133,8 +673,10 @@ mod tests {
         let mut state = StabilizerState::new(3);
 
         // Apply errors on qubits 0 and 2
-        Error::new(0, ErrorType::Z).apply_to_state(&mut state);
-        Error::new(2, ErrorType::X).apply_to_state(&mut state);
+        let mut frame = PauliFrame::new(3);
+        frame.apply_error(&Error::new(0, ErrorType::Z));
+        frame.apply_error(&Error::new(2, ErrorType::X));
+        state.apply_pauli(frame.pauli());
 
         let syndrome = Syndrome::from_state(&state);
         let triggered = syndrome.triggered_stabilizers();
@@ -142,4 +684,277 @@ mod tests {
         // Should trigger stabilizers for qubits 0 and 2
         assert!(triggered.contains(&2));
     }
+
+    #[test]
+    fn test_frame_composition() {
+        let mut frame = PauliFrame::new(2);
+        frame.apply_error(&Error::new(0, ErrorType::X));
+        frame.apply_error(&Error::new(0, ErrorType::Z));
+        assert_eq!(frame.pauli().to_string(), "YI");
+
+        // Applying the same error twice cancels it
+        frame.apply(&PauliString::from_str("YX").unwrap());
+        assert_eq!(frame.pauli().to_string(), "IX");
+        let change = frame.set_qubit(1, Pauli::Z);
+        assert_eq!(change.paulis, vec![Pauli::I, Pauli::Y]);
+        frame.set_qubit(1, Pauli::I);
+        assert_eq!(frame.pauli().weight(), 0);
+    }
+
+    #[test]
+    fn test_multi_qubit_error_matches_gates() {
+        // GHZ-like state with stabilizers XXX, ZZI, IZZ
+        let stabs: Vec<PauliString> = ["XXX", "ZZI", "IZZ"]
+            .iter()
+            .map(|s| PauliString::from_str(s).unwrap())
+            .collect();
+        let mut state = StabilizerState::from_stabilizers(3, &stabs);
+        let mut expected = state.clone();
+
+        state.apply_pauli(&PauliString::from_str("-iXYZ").unwrap());
+        expected.apply_x(0);
+        expected.apply_y(1);
+        expected.apply_z(2);
+        let signs = |s: &StabilizerState| Syndrome::from_state(s).outcomes;
+        assert_eq!(signs(&state), signs(&expected));
+        assert_eq!(signs(&state), vec![1, 1, -1]);
+    }
+
+    #[test]
+    fn test_frame_decomposition() {
+        let code = crate::codes::get_stabilizer_code("steane").unwrap();
+        let mut frame = PauliFrame::new(code.n_qubits);
+        frame.apply(&code.logical_x[0]);
+        frame.apply(&code.generators[0]);
+        frame.apply_error(&Error::new(3, ErrorType::Y));
+        let parts = frame.decompose(&code);
+        assert_eq!(parts.syndrome, code.syndrome_of(frame.pauli()));
+
+        // Rebuilding the frame from its coordinates recovers it
+        let logicals: Vec<PauliString> = code
+            .logical_x
+            .iter()
+            .chain(&code.logical_z)
+            .cloned()
+            .collect();
+        let destabilizers = symplectic::destabilizers(&code.generators, &logicals);
+        let mut rebuilt = PauliFrame::new(code.n_qubits);
+        let terms = [
+            (&parts.syndrome, &destabilizers),
+            (&parts.stabilizer, &code.generators),
+            (&parts.logical_x, &code.logical_x),
+            (&parts.logical_z, &code.logical_z),
+        ];
+        for (flags, ops) in terms {
+            for (&flag, op) in flags.iter().zip(ops) {
+                if flag {
+                    rebuilt.apply(op);
+                }
+            }
+        }
+        assert_eq!(rebuilt, frame);
+    }
+
+    #[test]
+    fn test_channel_probabilities() {
+        let close = |a: [f64; 3], b: [f64; 3]| a.iter().zip(&b).all(|(x, y)| (x - y).abs() < 1e-12);
+        let p = 0.03;
+        assert!(close(
+            PauliChannel::Depolarizing { p }.probabilities(),
+            PauliChannel::Pauli {
+                px: 0.01,
+                py: 0.01,
+                pz: 0.01
+            }
+            .probabilities()
+        ));
+        // eta = 0.5 is depolarizing
+        assert!(close(
+            PauliChannel::Biased { p, eta: 0.5 }.probabilities(),
+            PauliChannel::Depolarizing { p }.probabilities()
+        ));
+        let [px, py, pz] = PauliChannel::Biased { p, eta: 100.0 }.probabilities();
+        assert!((pz / (px + py) - 100.0).abs() < 1e-9 && px == py);
+        assert_eq!(PauliChannel::YFlip { p }.probabilities(), [0.0, p, 0.0]);
+
+        assert!(PauliChannel::BitFlip { p: 1.5 }.validate().is_err());
+        assert!(PauliChannel::Biased { p, eta: -1.0 }.validate().is_err());
+        assert!(PauliChannel::Pauli {
+            px: 0.5,
+            py: 0.5,
+            pz: 0.5
+        }
+        .validate()
+        .is_err());
+        assert!(PauliChannel::Biased { p, eta: 10.0 }.validate().is_ok());
+
+        let json = serde_json::to_string(&PauliChannel::PhaseFlip { p }).unwrap();
+        assert_eq!(json, r#"{"type":"phase_flip","p":0.03}"#);
+    }
+
+    #[test]
+    fn test_channel_sampling() {
+        let code = crate::codes::get_stabilizer_code("surface_d3").unwrap();
+        let channel = PauliChannel::Pauli {
+            px: 0.1,
+            py: 0.05,
+            pz: 0.2,
+        };
+        // Same seed, same errors
+        let first = channel.sample_code(&code, 11).unwrap();
+        let second = channel.sample_code(&code, 11).unwrap();
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap()
+        );
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut counts = [0usize; 3];
+        let shots = 20000;
+        for _ in 0..shots {
+            if let Some(t) = channel.sample_qubit(&mut rng).unwrap() {
+                counts[t as usize] += 1;
+            }
+        }
+        for (count, expected) in counts.iter().zip(channel.probabilities()) {
+            let freq = *count as f64 / shots as f64;
+            assert!((freq - expected).abs() < 0.01, "{} vs {}", freq, expected);
+        }
+        assert!(PauliChannel::BitFlip { p: 0.0 }
+            .sample_code(&code, 1)
+            .unwrap()
+            .is_empty());
+        // Invalid probabilities are rejected instead of sampled
+        assert!(PauliChannel::BitFlip { p: 1.5 }
+            .sample_code(&code, 1)
+            .is_err());
+        assert!(PauliChannel::Depolarizing { p: -0.1 }
+            .sample_qubit(&mut rng)
+            .is_err());
+    }
+
+    #[test]
+    fn test_two_qubit_channels() {
+        let outcomes = TwoQubitChannel::Depolarizing { p: 0.15 }
+            .outcomes()
+            .unwrap();
+        assert_eq!(outcomes.len(), 15);
+        assert!(outcomes
+            .iter()
+            .all(|(pair, p)| *pair != [Pauli::I, Pauli::I] && (p - 0.01).abs() < 1e-12));
+
+        let table = TwoQubitChannel::Table {
+            probabilities: [("XZ".to_string(), 0.2), ("II".to_string(), 0.5)]
+                .into_iter()
+                .collect(),
+        };
+        assert_eq!(table.outcomes().unwrap(), vec![([Pauli::X, Pauli::Z], 0.2)]);
+        assert!(table.validate().is_ok());
+        let bad = TwoQubitChannel::Table {
+            probabilities: [("XQ".to_string(), 0.1)].into_iter().collect(),
+        };
+        assert!(bad.validate().is_err());
+        assert!(TwoQubitChannel::Crosstalk { p: 2.0 }.validate().is_err());
+
+        // Certain crosstalk always flips both qubits of the pair with Z
+        let mut rng = SmallRng::seed_from_u64(5);
+        let crosstalk = TwoQubitChannel::Crosstalk { p: 1.0 };
+        assert_eq!(
+            crosstalk.sample_pair(&mut rng).unwrap(),
+            Some([Pauli::Z, Pauli::Z])
+        );
+        // Invalid tables are rejected instead of panicking
+        assert!(bad.sample_pair(&mut rng).is_err());
+        assert!(TwoQubitChannel::Crosstalk { p: 2.0 }
+            .sample_pair(&mut rng)
+            .is_err());
+    }
+
+    #[test]
+    fn test_two_qubit_noise_on_pairs() {
+        let code = crate::codes::get_stabilizer_code("steane").unwrap();
+        let pairs = stabilizer_pairs(&code);
+        assert!(pairs.contains(&[0, 2]) && !pairs.contains(&[0, 1]));
+        assert!(pairs.iter().all(|[a, b]| a < b));
+
+        let noise = TwoQubitNoise {
+            channel: TwoQubitChannel::Crosstalk { p: 1.0 },
+            pairs: vec![[0, 3], [3, 5]],
+        };
+        assert!(noise.validate(&code).is_ok());
+        let mut frame = PauliFrame::new(code.n_qubits);
+        for error in noise.sample_code(&code, 9).unwrap() {
+            frame.apply_error(&error);
+        }
+        // Z on qubit 3 cancels between the two pairs
+        assert_eq!(frame.pauli().to_string(), "ZIIIIZI");
+
+        let json = r#"{"channel": {"type": "crosstalk", "p": 0.1}, "pairs": [[0, 7]]}"#;
+        let parsed: TwoQubitNoise = serde_json::from_str(json).unwrap();
+        assert!(parsed.validate(&code).is_err());
+        assert!(parsed.sample_code(&code, 9).is_err());
+    }
+
+    #[test]
+    fn test_erasure_sampling() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        // Certain erasure flags every qubit and leaves it maximally mixed
+        let sample = ErasureChannel::Erasure { p: 1.0 }
+            .sample(4000, &mut rng)
+            .unwrap();
+        assert!(sample.erasures.iter().all(|&e| e));
+        let mut counts = [0usize; 3];
+        for error in &sample.errors {
+            counts[error.error_type as usize] += 1;
+        }
+        for count in counts {
+            assert!((count as f64 / 4000.0 - 0.25).abs() < 0.03, "{}", count);
+        }
+
+        // Fully converted faults are all flagged, unconverted ones never
+        let converted = ErasureChannel::Conversion {
+            p: 1.0,
+            erasure_fraction: 1.0,
+        };
+        let sample = converted.sample(50, &mut rng).unwrap();
+        assert!(sample.erasures.iter().all(|&e| e));
+        let silent = ErasureChannel::Conversion {
+            p: 1.0,
+            erasure_fraction: 0.0,
+        }
+        .sample(50, &mut rng)
+        .unwrap();
+        assert!(silent.erasures.iter().all(|&e| !e) && silent.errors.len() == 50);
+        let invalid = ErasureChannel::Conversion {
+            p: 0.1,
+            erasure_fraction: 1.5,
+        };
+        assert!(invalid.validate().is_err());
+        assert!(invalid.sample(50, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_syndrome_with_erasures() {
+        let code = crate::codes::get_stabilizer_code("steane").unwrap();
+        let channel = ErasureChannel::Conversion {
+            p: 0.3,
+            erasure_fraction: 0.9,
+        };
+        let sample = channel.sample_code(&code, 21).unwrap();
+        assert_eq!(sample, channel.sample_code(&code, 21).unwrap());
+
+        let mut state = code.to_state();
+        let mut frame = PauliFrame::new(code.n_qubits);
+        for error in &sample.errors {
+            frame.apply_error(error);
+        }
+        state.apply_pauli(frame.pauli());
+        let syndrome = Syndrome::from_state(&state).with_erasures(sample.erasures.clone());
+        assert_eq!(syndrome.erasures, sample.erasures);
+        let json = serde_json::to_string(&syndrome).unwrap();
+        assert!(json.contains("erasures"));
+
+        let json = serde_json::to_string(&Syndrome::from_state(&state)).unwrap();
+        assert!(!json.contains("erasures"));
+    }
 }
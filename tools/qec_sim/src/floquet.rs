@@ -0,0 +1,292 @@
+use crate::pauli::{Pauli, PauliString, Phase, Qubit};
+use crate::stabilizer::StabilizerState;
+use crate::symplectic;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Measurement-sequence-driven (Floquet) code
+/// Instead of a fixed generator list, the code is defined by a periodic
+/// schedule of check measurements. The stabilizer group at any time (the
+/// instantaneous stabilizer group, ISG) depends on which round was measured last.
+#[derive(Debug, Clone)]
+pub struct FloquetCode {
+    pub n_qubits: usize,
+    /// Checks measured in each round of one period
+    pub rounds: Vec<Vec<PauliString>>,
+    /// Static stabilizers that commute with every check
+    pub plaquettes: Vec<PauliString>,
+}
+
+impl FloquetCode {
+    /// Number of rounds in one period of the schedule
+    pub fn period(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// Checks measured in round `round` (counted from 0, wraps around the period)
+    pub fn checks_at(&self, round: usize) -> &[PauliString] {
+        &self.rounds[round % self.period()]
+    }
+
+    /// Instantaneous stabilizer groups after each of the first `n_rounds`
+    /// rounds, starting from the maximally mixed state (empty group)
+    /// Signs depend on measurement outcomes and are not tracked here.
+    pub fn instantaneous_stabilizer_groups(&self, n_rounds: usize) -> Vec<Vec<PauliString>> {
+        let mut group: Vec<PauliString> = Vec::new();
+        let mut history = Vec::with_capacity(n_rounds);
+        for round in 0..n_rounds {
+            for check in self.checks_at(round) {
+                update_stabilizer_group(&mut group, check);
+            }
+            history.push(group.clone());
+        }
+        history
+    }
+
+    /// Number of logical qubits of the ISG after `n_rounds` rounds
+    pub fn n_logical_after(&self, n_rounds: usize) -> usize {
+        let isg = self
+            .instantaneous_stabilizer_groups(n_rounds)
+            .pop()
+            .unwrap_or_default();
+        self.n_qubits - isg.len()
+    }
+
+    /// Run the schedule on a state and return the outcomes of every check,
+    /// round by round (false = +1, true = -1)
+    pub fn simulate<R: Rng>(
+        &self,
+        state: &mut StabilizerState,
+        n_rounds: usize,
+        rng: &mut R,
+    ) -> Vec<Vec<bool>> {
+        (0..n_rounds)
+            .map(|round| {
+                self.checks_at(round)
+                    .iter()
+                    .map(|check| state.measure_pauli(check, rng))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Update a stabilizer group (independent generators, signs ignored) after
+/// measuring `measured`
+/// Anticommuting generators are merged into one that is then replaced by the
+/// measured operator; a commuting operator is added if it is independent.
+pub fn update_stabilizer_group(group: &mut Vec<PauliString>, measured: &PauliString) {
+    let anticommuting: Vec<usize> = (0..group.len())
+        .filter(|&i| !group[i].commutes_with(measured))
+        .collect();
+
+    if let Some((&pivot, rest)) = anticommuting.split_first() {
+        for &i in rest {
+            group[i] = normalized(&group[i].multiply(&group[pivot]));
+        }
+        group.remove(pivot);
+    }
+
+    if !symplectic::is_in_span(group, measured) {
+        group.push(normalized(measured));
+    }
+}
+
+fn normalized(p: &PauliString) -> PauliString {
+    PauliString {
+        dim: Qubit,
+        phase: Phase::Plus,
+        paulis: p.paulis.clone(),
+    }
+}
+
+/// Hastings-Haah honeycomb code on a torus
+///
+/// The honeycomb lattice is drawn as a brick wall with `rows` x `cols`
+/// vertices (qubits), qubit index `r * cols + c`. Every vertex has two
+/// horizontal edges (alternating XX and YY checks) and one vertical edge
+/// (ZZ check). Hexagonal plaquettes are 3-colored and each edge takes the
+/// color of the two plaquettes it connects; round t measures every edge of
+/// color t mod 3.
+///
+/// Returns None unless `rows` is even and `cols` is a multiple of 6, which
+/// the periodic 3-coloring requires.
+pub fn honeycomb_code(rows: usize, cols: usize) -> Option<FloquetCode> {
+    if rows < 2 || !rows.is_multiple_of(2) || cols < 6 || !cols.is_multiple_of(6) {
+        return None;
+    }
+
+    let n = rows * cols;
+    let vertex = |r: usize, c: usize| (r % rows) * cols + (c % cols);
+    let edge_key = |a: usize, b: usize| (a.min(b), a.max(b));
+
+    // Edge check operators
+    let mut checks: HashMap<(usize, usize), PauliString> = HashMap::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            let (a, b) = (vertex(r, c), vertex(r, c + 1));
+            let pauli = if (r + c) % 2 == 0 { Pauli::X } else { Pauli::Y };
+            checks.insert(edge_key(a, b), two_qubit_check(n, a, b, pauli));
+
+            if (r + c) % 2 == 0 {
+                let b = vertex(r + 1, c);
+                checks.insert(edge_key(a, b), two_qubit_check(n, a, b, Pauli::Z));
+            }
+        }
+    }
+
+    // Plaquettes: hexagon between rows r, r+1 and columns c..c+2
+    let mut plaquettes = Vec::new();
+    let mut edge_colors: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for r in 0..rows {
+        for c in (0..cols).filter(|c| (r + c) % 2 == 0) {
+            let color = ((c + 3 * r) / 2) % 3;
+            let edges = [
+                edge_key(vertex(r, c), vertex(r, c + 1)),
+                edge_key(vertex(r, c + 1), vertex(r, c + 2)),
+                edge_key(vertex(r + 1, c), vertex(r + 1, c + 1)),
+                edge_key(vertex(r + 1, c + 1), vertex(r + 1, c + 2)),
+                edge_key(vertex(r, c), vertex(r + 1, c)),
+                edge_key(vertex(r, c + 2), vertex(r + 1, c + 2)),
+            ];
+
+            let mut plaquette = PauliString::identity(n);
+            for edge in &edges {
+                plaquette = plaquette.multiply(&checks[edge]);
+                edge_colors.entry(*edge).or_default().push(color);
+            }
+            plaquettes.push(normalized(&plaquette));
+        }
+    }
+
+    // An edge gets the one color not used by its two neighboring plaquettes
+    let mut rounds = vec![Vec::new(); 3];
+    let mut edges: Vec<_> = checks.keys().copied().collect();
+    edges.sort();
+    for edge in edges {
+        let colors = &edge_colors[&edge];
+        let color = 3 - colors[0] - colors[1];
+        rounds[color].push(checks[&edge].clone());
+    }
+
+    Some(FloquetCode {
+        n_qubits: n,
+        rounds,
+        plaquettes,
+    })
+}
+
+fn two_qubit_check(n: usize, a: usize, b: usize, pauli: Pauli) -> PauliString {
+    let mut check = PauliString::identity(n);
+    check.paulis[a] = pauli;
+    check.paulis[b] = pauli;
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_honeycomb_structure() {
+        let code = honeycomb_code(4, 6).unwrap();
+        assert_eq!(code.n_qubits, 24);
+        assert_eq!(code.period(), 3);
+        assert_eq!(code.plaquettes.len(), 12);
+
+        // Each round is a perfect matching of the qubits
+        for round in &code.rounds {
+            assert_eq!(round.len(), 12);
+            let mut covered = [0; 24];
+            for check in round {
+                for (q, p) in check.paulis.iter().enumerate() {
+                    if *p != Pauli::I {
+                        covered[q] += 1;
+                    }
+                }
+            }
+            assert!(covered.iter().all(|&c| c == 1));
+        }
+
+        // Plaquettes commute with every check
+        for plaquette in &code.plaquettes {
+            assert_eq!(plaquette.weight(), 6);
+            for check in code.rounds.iter().flatten() {
+                assert!(plaquette.commutes_with(check));
+            }
+        }
+
+        assert!(honeycomb_code(3, 6).is_none());
+        assert!(honeycomb_code(4, 8).is_none());
+    }
+
+    #[test]
+    fn test_instantaneous_stabilizer_groups() {
+        let code = honeycomb_code(4, 6).unwrap();
+        let isgs = code.instantaneous_stabilizer_groups(9);
+
+        for (round, isg) in isgs.iter().enumerate().skip(3) {
+            // Steady state: two logical qubits, plaquettes and the latest checks
+            assert_eq!(code.n_qubits - isg.len(), 2);
+            for op in code.plaquettes.iter().chain(code.checks_at(round)) {
+                assert!(symplectic::is_in_span(isg, op));
+            }
+            // The next round's checks are not in the ISG: the group changes
+            for check in code.checks_at(round + 1) {
+                assert!(!symplectic::is_in_span(isg, check));
+            }
+        }
+        assert_eq!(code.n_logical_after(9), 2);
+    }
+
+    #[test]
+    fn test_plaquette_outcomes_repeat() {
+        let code = honeycomb_code(4, 6).unwrap();
+        let mut rng = SmallRng::seed_from_u64(42);
+        let mut state = StabilizerState::new(code.n_qubits);
+        let outcomes = code.simulate(&mut state, 12, &mut rng);
+
+        // A plaquette is the product of its six boundary checks, which are
+        // measured in two consecutive rounds; the inferred value must be the
+        // same in every period
+        let inside = |check: &PauliString, plaquette: &PauliString| {
+            check
+                .paulis
+                .iter()
+                .zip(&plaquette.paulis)
+                .all(|(c, p)| *c == Pauli::I || *p != Pauli::I)
+        };
+        for plaquette in &code.plaquettes {
+            let boundary_rounds: Vec<usize> = (0..3)
+                .filter(|&t| code.rounds[t].iter().any(|c| inside(c, plaquette)))
+                .collect();
+            assert_eq!(boundary_rounds.len(), 2);
+            let missing = 3 - boundary_rounds[0] - boundary_rounds[1];
+            let first = (missing + 1) % 3;
+
+            let values: Vec<bool> = (0..3)
+                .map(|period| {
+                    let start = 3 * period + first;
+                    let mut parity = false;
+                    for (round, round_outcomes) in outcomes.iter().enumerate().skip(start).take(2) {
+                        for (check, &outcome) in code.checks_at(round).iter().zip(round_outcomes) {
+                            if inside(check, plaquette) {
+                                parity ^= outcome;
+                            }
+                        }
+                    }
+                    parity
+                })
+                .collect();
+            assert_eq!(values[1], values[0]);
+            assert_eq!(values[2], values[0]);
+        }
+
+        // Checks of the latest round are deterministic when re-measured
+        for (check, &outcome) in code.checks_at(11).iter().zip(&outcomes[11]) {
+            assert_eq!(state.peek_pauli(check), Some(outcome));
+        }
+    }
+}
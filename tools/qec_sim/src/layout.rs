@@ -0,0 +1,225 @@
+#![allow(dead_code)]
+use crate::pauli::{Pauli, PauliString};
+use serde::{Deserialize, Serialize};
+
+/// Drawing coordinates of a qubit
+/// x grows to the right and y grows downwards, one unit per lattice spacing.
+/// `z` is only set for codes with a 3D embedding.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z: Option<f64>,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y, z: None }
+    }
+
+    pub fn new_3d(x: f64, y: f64, z: f64) -> Self {
+        Point { x, y, z: Some(z) }
+    }
+
+    /// Euclidean distance, treating a missing z as 0
+    pub fn distance(&self, other: &Point) -> f64 {
+        let dz = self.z.unwrap_or(0.0) - other.z.unwrap_or(0.0);
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2) + dz.powi(2)).sqrt()
+    }
+}
+
+/// Outline of a stabilizer drawn as a polygon over its support
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Plaquette {
+    /// Index of the stabilizer generator
+    pub stabilizer: usize,
+    /// Pauli type of the stabilizer, "X", "Y" or "Z", or "mixed"
+    pub pauli: String,
+    /// Polygon corners in drawing order
+    pub vertices: Vec<Point>,
+}
+
+/// Geometry of a code: data qubits, one measurement ancilla per stabilizer
+/// generator and the plaquette outline of each generator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    /// Position of each data qubit, indexed like the code's qubits
+    pub data_qubits: Vec<Point>,
+    /// Position of the ancilla measuring each stabilizer generator
+    #[serde(default)]
+    pub ancilla_qubits: Vec<Point>,
+    /// Plaquettes of planar codes; empty when the checks are not local
+    #[serde(default)]
+    pub plaquettes: Vec<Plaquette>,
+}
+
+impl Layout {
+    /// Layout with ancillas at the given positions and a plaquette for every
+    /// generator: its support in angular order around the ancilla, with the
+    /// ancilla as third corner of weight-2 checks
+    pub fn with_ancillas(
+        data_qubits: Vec<Point>,
+        ancilla_qubits: Vec<Point>,
+        generators: &[PauliString],
+    ) -> Self {
+        let plaquettes = generators
+            .iter()
+            .zip(&ancilla_qubits)
+            .enumerate()
+            .filter_map(|(s, (g, center))| {
+                let mut vertices: Vec<Point> = support(g).map(|q| data_qubits[q]).collect();
+                if vertices.len() < 2 {
+                    return None;
+                }
+                if vertices.len() == 2 {
+                    vertices.push(*center);
+                }
+                let angle = |p: &Point| (p.y - center.y).atan2(p.x - center.x);
+                vertices.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+                Some(Plaquette {
+                    stabilizer: s,
+                    pauli: pauli_type(g),
+                    vertices,
+                })
+            })
+            .collect();
+        Layout {
+            data_qubits,
+            ancilla_qubits,
+            plaquettes,
+        }
+    }
+
+    /// Layout with each ancilla at the centroid of its stabilizer's support
+    /// Ancillas that would overlap an earlier one are nudged aside, which
+    /// separates the X and Z checks of codes like Steane.
+    pub fn from_data_qubits(data_qubits: Vec<Point>, generators: &[PauliString]) -> Self {
+        let mut ancillas: Vec<Point> = Vec::with_capacity(generators.len());
+        for g in generators {
+            let points: Vec<Point> = support(g).map(|q| data_qubits[q]).collect();
+            let count = points.len().max(1) as f64;
+            let mut center = Point {
+                x: points.iter().map(|p| p.x).sum::<f64>() / count,
+                y: points.iter().map(|p| p.y).sum::<f64>() / count,
+                z: data_qubits
+                    .first()
+                    .and_then(|p| p.z)
+                    .map(|_| points.iter().filter_map(|p| p.z).sum::<f64>() / count),
+            };
+            while ancillas.iter().any(|a| a.distance(&center) < 0.05) {
+                center.x += 0.15;
+                center.y += 0.15;
+            }
+            ancillas.push(center);
+        }
+        Layout::with_ancillas(data_qubits, ancillas, generators)
+    }
+
+    /// Qubits on a horizontal line
+    pub fn line(n_qubits: usize) -> Vec<Point> {
+        (0..n_qubits).map(|q| Point::new(q as f64, 0.0)).collect()
+    }
+
+    /// Qubits on a `rows` x `cols` grid, index `row * cols + col`
+    pub fn grid(rows: usize, cols: usize) -> Vec<Point> {
+        (0..rows * cols)
+            .map(|q| Point::new((q % cols) as f64, (q / cols) as f64))
+            .collect()
+    }
+
+    /// Qubits evenly spaced on a unit circle, qubit 0 at the top
+    pub fn ring(n_qubits: usize) -> Vec<Point> {
+        let step = std::f64::consts::TAU / n_qubits as f64;
+        (0..n_qubits)
+            .map(|q| {
+                let angle = q as f64 * step;
+                Point::new(angle.sin(), -angle.cos())
+            })
+            .collect()
+    }
+
+    /// Number of data qubits
+    pub fn n_qubits(&self) -> usize {
+        self.data_qubits.len()
+    }
+
+    /// Whether any coordinate has a z component
+    pub fn is_3d(&self) -> bool {
+        self.data_qubits
+            .iter()
+            .chain(&self.ancilla_qubits)
+            .any(|p| p.z.is_some())
+    }
+}
+
+fn support(g: &PauliString) -> impl Iterator<Item = usize> + '_ {
+    (0..g.n_qubits()).filter(|&q| g.paulis[q] != Pauli::I)
+}
+
+fn pauli_type(g: &PauliString) -> String {
+    let mut kinds = g.paulis.iter().filter(|&&p| p != Pauli::I);
+    match kinds.next() {
+        Some(&first) if kinds.all(|&p| p == first) => first.to_char().to_string(),
+        Some(_) => "mixed".to_string(),
+        None => "I".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paulis(strings: &[&str]) -> Vec<PauliString> {
+        strings
+            .iter()
+            .map(|s| PauliString::from_str(s).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_plaquette_order() {
+        // Square plaquette listed in reading order must be drawn as a cycle
+        let layout = Layout::from_data_qubits(Layout::grid(2, 2), &paulis(&["XXXX"]));
+        assert_eq!(layout.ancilla_qubits, vec![Point::new(0.5, 0.5)]);
+        let corners: Vec<(f64, f64)> = layout.plaquettes[0]
+            .vertices
+            .iter()
+            .map(|p| (p.x, p.y))
+            .collect();
+        assert_eq!(
+            corners,
+            vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+        );
+        assert_eq!(layout.plaquettes[0].pauli, "X");
+    }
+
+    #[test]
+    fn test_overlapping_ancillas_are_separated() {
+        let layout = Layout::from_data_qubits(Layout::line(3), &paulis(&["XXI", "ZZI", "IZZ"]));
+        assert_eq!(layout.ancilla_qubits.len(), 3);
+        assert!(layout.ancilla_qubits[0].distance(&layout.ancilla_qubits[1]) > 0.1);
+        // Weight-2 checks get the ancilla as a third corner
+        assert_eq!(layout.plaquettes[2].vertices.len(), 3);
+        assert!(!layout.is_3d());
+    }
+
+    #[test]
+    fn test_3d_serialization() {
+        let layout = Layout::from_data_qubits(
+            vec![Point::new_3d(0.0, 0.0, 0.0), Point::new_3d(0.0, 0.0, 2.0)],
+            &paulis(&["ZZ"]),
+        );
+        assert!(layout.is_3d());
+        assert_eq!(layout.ancilla_qubits[0].z, Some(1.0));
+
+        let json = serde_json::to_string(&Layout::from_data_qubits(Layout::line(1), &[])).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data_qubits":[{"x":0.0,"y":0.0}],"ancilla_qubits":[],"plaquettes":[]}"#
+        );
+        let parsed: Layout =
+            serde_json::from_str(r#"{"data_qubits":[{"x":1,"y":2,"z":3}]}"#).unwrap();
+        assert_eq!(parsed.data_qubits[0], Point::new_3d(1.0, 2.0, 3.0));
+    }
+}
@@ -0,0 +1,398 @@
+// Transmon leakage to |2⟩
+//
+// The tableau only describes the computational subspace, so leakage is a
+// flag per qubit kept next to it. While a qubit is leaked its tableau
+// column is meaningless: entering and leaving leakage both replace the
+// qubit by the maximally mixed state (a uniformly random Pauli), and a
+// two-qubit gate with a leaked partner is not applied but depolarizes the
+// other qubit instead. Pauli noise from a `NoiseModel` only acts on qubits
+// that are not leaked.
+use crate::circuit::{Circuit, Gate};
+use crate::error::{Error, NoiseError};
+use crate::noise::NoiseModel;
+use crate::pauli::{Pauli, PauliString};
+use crate::stabilizer::StabilizerState;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Leakage rates
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LeakageModel {
+    /// Probability that a qubit leaks after each two-qubit gate on it
+    pub leak: f64,
+    /// Probability that a leaked qubit returns after each gate or time step
+    pub seep: f64,
+    /// Probability that a leakage reduction unit returns a leaked qubit
+    pub lru_efficiency: f64,
+}
+
+/// Outcome of a readout that discriminates |2⟩ from |0⟩ and |1⟩
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Readout {
+    Zero,
+    One,
+    Leaked,
+}
+
+impl LeakageModel {
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        let rates = [
+            ("leak", self.leak),
+            ("seep", self.seep),
+            ("lru_efficiency", self.lru_efficiency),
+        ];
+        for (parameter, value) in rates {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(NoiseError::invalid(
+                    "leakage",
+                    parameter,
+                    "must lie in [0, 1]",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stabilizer state with a leakage flag per qubit
+#[derive(Debug, Clone)]
+pub struct LeakyState {
+    pub state: StabilizerState,
+    pub leaked: Vec<bool>,
+    /// Validated on construction
+    model: LeakageModel,
+}
+
+impl LeakyState {
+    /// |0...0⟩ with no leaked qubits
+    pub fn new(n_qubits: usize, model: LeakageModel) -> Result<Self, NoiseError> {
+        Self::from_state(StabilizerState::new(n_qubits), model)
+    }
+
+    pub fn from_state(state: StabilizerState, model: LeakageModel) -> Result<Self, NoiseError> {
+        model.validate()?;
+        Ok(LeakyState {
+            leaked: vec![false; state.n_qubits()],
+            state,
+            model,
+        })
+    }
+
+    pub fn n_qubits(&self) -> usize {
+        self.state.n_qubits()
+    }
+
+    /// Indices of leaked qubits
+    pub fn leaked_qubits(&self) -> Vec<usize> {
+        (0..self.n_qubits()).filter(|&q| self.leaked[q]).collect()
+    }
+
+    /// Replace `qubit` by the maximally mixed state
+    fn depolarize<R: Rng>(&mut self, qubit: usize, rng: &mut R) {
+        let pauli = Pauli::ALL[rng.gen_range(0..4)];
+        let op = PauliString::single(self.n_qubits(), qubit, pauli);
+        self.state.apply_pauli(&op);
+    }
+
+    /// Move `qubit` into or out of leakage
+    fn set_leaked<R: Rng>(&mut self, qubit: usize, leaked: bool, rng: &mut R) {
+        if self.leaked[qubit] != leaked {
+            self.leaked[qubit] = leaked;
+            self.depolarize(qubit, rng);
+        }
+    }
+
+    /// Seep each leaked qubit in `qubits` back with the seep rate
+    fn seep<R: Rng>(&mut self, qubits: &[usize], rng: &mut R) {
+        for &q in qubits {
+            if self.leaked[q] && rng.gen::<f64>() < self.model.seep {
+                self.set_leaked(q, false, rng);
+            }
+        }
+    }
+
+    /// Apply a gate, then leak its qubits and seep back the ones that were
+    /// already leaked before it
+    /// Single-qubit gates on a leaked qubit do nothing, and two-qubit gates
+    /// with a leaked partner depolarize the other qubit instead.
+    pub fn apply_gate<R: Rng>(&mut self, gate: Gate, rng: &mut R) {
+        let qubits = gate.qubits();
+        let leaked_before: Vec<usize> =
+            qubits.iter().copied().filter(|&q| self.leaked[q]).collect();
+        match qubits[..] {
+            [q] if !self.leaked[q] => gate.apply(&mut self.state),
+            [a, b] => match (self.leaked[a], self.leaked[b]) {
+                (false, false) => gate.apply(&mut self.state),
+                (true, false) => self.depolarize(b, rng),
+                (false, true) => self.depolarize(a, rng),
+                (true, true) => {}
+            },
+            _ => {}
+        }
+        if qubits.len() == 2 {
+            for &q in &qubits {
+                if !self.leaked[q] && rng.gen::<f64>() < self.model.leak {
+                    self.set_leaked(q, true, rng);
+                }
+            }
+        }
+        self.seep(&leaked_before, rng);
+    }
+
+    /// One time step with `qubits` idle: leaked qubits may seep back
+    pub fn idle<R: Rng>(&mut self, qubits: &[usize], rng: &mut R) {
+        self.seep(qubits, rng);
+    }
+
+    /// Leakage reduction unit on `qubit`: returns a leaked qubit to the
+    /// computational subspace with probability `lru_efficiency`
+    pub fn lru<R: Rng>(&mut self, qubit: usize, rng: &mut R) {
+        if self.leaked[qubit] && rng.gen::<f64>() < self.model.lru_efficiency {
+            self.set_leaked(qubit, false, rng);
+        }
+    }
+
+    /// Three-level Z readout; a leaked qubit stays leaked
+    pub fn measure<R: Rng>(&mut self, qubit: usize, rng: &mut R) -> Readout {
+        if self.leaked[qubit] {
+            return Readout::Leaked;
+        }
+        let z = PauliString::single(self.n_qubits(), qubit, Pauli::Z);
+        if self.state.measure_pauli(&z, rng) {
+            Readout::One
+        } else {
+            Readout::Zero
+        }
+    }
+
+    /// Apply Pauli errors to the qubits that are not leaked
+    fn apply_errors(&mut self, errors: &[Error]) {
+        for error in errors.iter().filter(|e| !self.leaked[e.qubit]) {
+            self.state
+                .apply_pauli(&error.to_pauli_string(self.n_qubits()));
+        }
+    }
+
+    /// Run `circuit` moment by moment like `noise::run_noisy`, with leakage
+    /// on every gate and idle qubits seeping back each step
+    pub fn run_noisy<R: Rng>(
+        &mut self,
+        circuit: &Circuit,
+        noise: &NoiseModel,
+        rng: &mut R,
+    ) -> Result<(), NoiseError> {
+        for moment in circuit.moments() {
+            let mut busy = vec![false; self.n_qubits()];
+            for gate in moment {
+                self.apply_gate(gate, rng);
+                self.apply_errors(&noise.after_gate(gate, rng)?);
+                for q in gate.qubits() {
+                    busy[q] = true;
+                }
+            }
+            let idle: Vec<usize> = (0..self.n_qubits()).filter(|&q| !busy[q]).collect();
+            self.apply_errors(&noise.idle(&idle, rng)?);
+            self.idle(&idle, rng);
+        }
+        Ok(())
+    }
+}
+
+/// Leakage statistics of repeated noisy state preparations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeakageResult {
+    pub shots: usize,
+    /// Shots in which each qubit was leaked at the end of the circuit
+    pub leaked: Vec<usize>,
+    /// Shots in which each qubit still read out as leaked after the LRUs
+    pub readout_leaked: Vec<usize>,
+    /// Shots ending with no leaked qubit and every target stabilizer at +1
+    pub prepared: usize,
+}
+
+/// Run `circuit` from |0...0⟩ `shots` times under `noise` and `model`,
+/// optionally follow it with an LRU on every qubit, and read out every
+/// qubit with leakage discrimination
+/// A shot counts as prepared if, before the LRUs, no qubit is leaked and
+/// the state is a +1 eigenstate of every operator in `target`.
+pub fn run_preparation(
+    circuit: &Circuit,
+    target: &[PauliString],
+    noise: &NoiseModel,
+    model: LeakageModel,
+    lru: bool,
+    shots: usize,
+    seed: u64,
+) -> Result<LeakageResult, NoiseError> {
+    let n = circuit.n_qubits;
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut result = LeakageResult {
+        shots,
+        leaked: vec![0; n],
+        readout_leaked: vec![0; n],
+        prepared: 0,
+    };
+    for _ in 0..shots {
+        let mut state = LeakyState::new(n, model)?;
+        state.run_noisy(circuit, noise, &mut rng)?;
+        let leaked = state.leaked_qubits();
+        for &q in &leaked {
+            result.leaked[q] += 1;
+        }
+        if leaked.is_empty()
+            && target
+                .iter()
+                .all(|t| state.state.peek_pauli(t) == Some(false))
+        {
+            result.prepared += 1;
+        }
+        for q in 0..n {
+            if lru {
+                state.lru(q, &mut rng);
+            }
+            if state.measure(q, &mut rng) == Readout::Leaked {
+                result.readout_leaked[q] += 1;
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::prepare_logical_zero;
+    use crate::codes::get_stabilizer_code;
+
+    const NO_LEAKAGE: LeakageModel = LeakageModel {
+        leak: 0.0,
+        seep: 0.0,
+        lru_efficiency: 1.0,
+    };
+
+    #[test]
+    fn test_leak_readout_and_lru() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let model = LeakageModel {
+            leak: 1.0,
+            ..NO_LEAKAGE
+        };
+        let mut state = LeakyState::new(3, model).unwrap();
+        state.apply_gate(Gate::Cnot(0, 1), &mut rng);
+        assert_eq!(state.leaked_qubits(), vec![0, 1]);
+        assert_eq!(state.measure(0, &mut rng), Readout::Leaked);
+        assert_eq!(state.measure(2, &mut rng), Readout::Zero);
+
+        // Without seepage only the LRU brings the qubit back
+        state.idle(&[0, 1], &mut rng);
+        assert_eq!(state.leaked_qubits(), vec![0, 1]);
+        state.lru(0, &mut rng);
+        assert_eq!(state.leaked_qubits(), vec![1]);
+        assert_ne!(state.measure(0, &mut rng), Readout::Leaked);
+    }
+
+    #[test]
+    fn test_seepage() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        let model = LeakageModel {
+            seep: 1.0,
+            lru_efficiency: 0.0,
+            ..NO_LEAKAGE
+        };
+        let mut state = LeakyState::new(2, model).unwrap();
+        state.leaked[1] = true;
+        state.lru(1, &mut rng);
+        assert_eq!(state.leaked_qubits(), vec![1]);
+        state.idle(&[1], &mut rng);
+        assert!(state.leaked_qubits().is_empty());
+
+        // Qubits that leak during a gate cannot seep back in the same gate
+        let model = LeakageModel {
+            leak: 1.0,
+            seep: 1.0,
+            lru_efficiency: 0.0,
+        };
+        let mut state = LeakyState::new(2, model).unwrap();
+        state.apply_gate(Gate::Cnot(0, 1), &mut rng);
+        assert_eq!(state.leaked_qubits(), vec![0, 1]);
+        // but seep back after the next one
+        state.apply_gate(Gate::Cnot(0, 1), &mut rng);
+        assert!(state.leaked_qubits().is_empty());
+    }
+
+    #[test]
+    fn test_leaked_partner_randomizes_gate() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let trials = 2000;
+        let mut ones = 0;
+        for _ in 0..trials {
+            let mut state = LeakyState::new(2, NO_LEAKAGE).unwrap();
+            state.leaked[0] = true;
+            state.apply_gate(Gate::Cnot(0, 1), &mut rng);
+            if state.measure(1, &mut rng) == Readout::One {
+                ones += 1;
+            }
+        }
+        // The target is left maximally mixed
+        assert!((ones as f64 / trials as f64 - 0.5).abs() < 0.05, "{}", ones);
+
+        // Without leakage the CNOT acts normally on |10⟩
+        let mut state = LeakyState::new(2, NO_LEAKAGE).unwrap();
+        let mut circuit = Circuit::new(2);
+        circuit.push(Gate::X(0));
+        circuit.push(Gate::Cnot(0, 1));
+        state
+            .run_noisy(&circuit, &NoiseModel::noiseless(), &mut rng)
+            .unwrap();
+        assert_eq!(state.measure(1, &mut rng), Readout::One);
+    }
+
+    #[test]
+    fn test_model_validation() {
+        assert!(NO_LEAKAGE.validate().is_ok());
+        let bad = LeakageModel {
+            seep: -0.1,
+            ..NO_LEAKAGE
+        };
+        assert!(bad.validate().is_err());
+        assert!(LeakyState::new(2, bad).is_err());
+        let json = serde_json::to_string(&NO_LEAKAGE).unwrap();
+        let parsed: LeakageModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, NO_LEAKAGE);
+    }
+
+    #[test]
+    fn test_preparation_with_leakage() {
+        let code = get_stabilizer_code("steane").unwrap();
+        let circuit = prepare_logical_zero(&code).unwrap();
+        let mut target = code.generators.clone();
+        target.extend(code.logical_z.iter().cloned());
+        let noiseless = NoiseModel::noiseless();
+        let n = code.n_qubits;
+
+        let clean = run_preparation(&circuit, &target, &noiseless, NO_LEAKAGE, false, 5, 1);
+        let clean = clean.unwrap();
+        assert_eq!(clean.prepared, 5);
+        assert_eq!(clean.leaked, vec![0; n]);
+
+        // Every qubit touched by a two-qubit gate leaks; perfect LRUs
+        // return all of them before readout
+        let always = LeakageModel {
+            leak: 1.0,
+            ..NO_LEAKAGE
+        };
+        let leaky = run_preparation(&circuit, &target, &noiseless, always, true, 5, 1).unwrap();
+        assert_eq!(leaky.prepared, 0);
+        assert!(leaky.leaked.contains(&5));
+        assert_eq!(leaky.readout_leaked, vec![0; n]);
+        let no_lru = run_preparation(&circuit, &target, &noiseless, always, false, 5, 1).unwrap();
+        assert_eq!(no_lru.readout_leaked, no_lru.leaked);
+
+        let bad = LeakageModel {
+            leak: 2.0,
+            ..NO_LEAKAGE
+        };
+        assert!(run_preparation(&circuit, &target, &noiseless, bad, false, 5, 1).is_err());
+    }
+}
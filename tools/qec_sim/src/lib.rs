@@ -1,13 +1,61 @@
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
 use wasm_bindgen::prelude::*;
 
+mod automorphism;
+mod calibration;
+mod circuit;
+mod code_io;
 mod codes;
+mod concatenation;
+mod cosmic;
+mod decoder;
+mod defects;
+mod distance;
+mod drift;
+mod enumerator;
 mod error;
+mod floquet;
+mod layout;
+mod leakage;
+mod memory;
+mod noise;
 mod pauli;
+mod qudit;
+mod registry;
+mod search;
 mod stabilizer;
+mod surgery;
+mod symplectic;
+mod twirl;
+mod validation;
 
-use crate::codes::{available_codes, get_code_by_name, get_code_info};
-use crate::error::{Error, ErrorType, Syndrome};
+use crate::calibration::DeviceCalibration;
+use crate::circuit::{decoder, prepare_logical_plus, prepare_logical_zero};
+use crate::code_io::CodeDescription;
+use crate::codes::{
+    available_codes, get_code_info, get_logical_gates, get_logical_operators, get_stabilizer_code,
+    StabilizerCode,
+};
+use crate::cosmic::{cosmic_ray_study, CosmicRayModel};
+use crate::decoder::LookupDecoder;
+use crate::defects::{defective_surface_code, Defect};
+use crate::drift::{DriftModel, DriftingNoise, Schedule};
+use crate::enumerator::WeightEnumerators;
+use crate::error::{
+    ErasureChannel, Error, ErrorType, PauliChannel, PauliFrame, Syndrome, TwoQubitNoise,
+};
+use crate::floquet::honeycomb_code;
+use crate::leakage::{run_preparation, LeakageModel};
+use crate::memory::{run_memory, StationaryNoise};
+use crate::noise::NoiseModel;
+use crate::pauli::{Pauli, PauliString};
+use crate::qudit::Qudit;
+use crate::registry::with_registry;
+use crate::search::{search_codes, SearchConfig};
 use crate::stabilizer::StabilizerState;
+use crate::surgery::{Deformation, Lattice, Region};
+use crate::twirl::KrausChannel;
 
 /// Initialize panic hook for better error messages in browser console
 #[wasm_bindgen(start)]
@@ -19,8 +67,13 @@ pub fn init() {
 /// JavaScript-facing QEC simulator API
 #[wasm_bindgen]
 pub struct QECSimulator {
+    /// Code the simulator was created with, kept to rebuild the codeword
+    code: StabilizerCode,
     state: StabilizerState,
-    applied_errors: Vec<Error>,
+    /// Accumulated error, already applied to `state`
+    frame: PauliFrame,
+    /// Erasure flag per qubit
+    erasures: Vec<bool>,
 }
 
 #[wasm_bindgen]
@@ -28,12 +81,13 @@ impl QECSimulator {
     /// Create a new simulator with a specific code
     #[wasm_bindgen(constructor)]
     pub fn new(code_name: &str) -> Result<QECSimulator, JsValue> {
-        let state = get_code_by_name(code_name)
-            .ok_or_else(|| JsValue::from_str(&format!("Unknown code: {}", code_name)))?;
+        let code = get_stabilizer_code(code_name).map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         Ok(QECSimulator {
-            state,
-            applied_errors: Vec::new(),
+            state: code.to_state(),
+            frame: PauliFrame::new(code.n_qubits),
+            erasures: vec![false; code.n_qubits],
+            code,
         })
     }
 
@@ -56,26 +110,13 @@ impl QECSimulator {
     }
 
     /// Apply an error to a specific qubit
-    /// Note: This will automatically clear any existing error on this qubit first
+    /// Note: This replaces any existing error on this qubit
     #[wasm_bindgen(js_name = applyError)]
     pub fn apply_error(&mut self, qubit: usize, error_type: &str) -> Result<(), JsValue> {
         if qubit >= self.state.n_qubits() {
             return Err(JsValue::from_str("Qubit index out of bounds"));
         }
 
-        // First, remove any existing error on this qubit
-        self.applied_errors.retain(|e| e.qubit != qubit);
-
-        // Reset state and reapply all remaining errors
-        let code_name = self.get_code_name();
-        self.state = get_code_by_name(&code_name)
-            .ok_or_else(|| JsValue::from_str("Failed to reset state"))?;
-
-        for error in &self.applied_errors {
-            error.apply_to_state(&mut self.state);
-        }
-
-        // Now apply the new error
         let err_type = match error_type {
             "X" => ErrorType::X,
             "Y" => ErrorType::Y,
@@ -83,13 +124,95 @@ impl QECSimulator {
             _ => return Err(JsValue::from_str("Invalid error type")),
         };
 
-        let error = Error::new(qubit, err_type);
-        error.apply_to_state(&mut self.state);
-        self.applied_errors.push(error);
+        // Only the difference between the old and new error touches the state
+        let change = self.frame.set_qubit(qubit, err_type.to_pauli());
+        self.state.apply_pauli(&change);
+        Ok(())
+    }
 
+    /// Compose an arbitrary Pauli string error like "XIZ" into the current
+    /// errors, so X then Z on one qubit leaves Y
+    #[wasm_bindgen(js_name = applyPauliError)]
+    pub fn apply_pauli_error(&mut self, pauli: &str) -> Result<(), JsValue> {
+        let error = PauliString::from_str(pauli)
+            .filter(|p| p.n_qubits() == self.state.n_qubits())
+            .ok_or_else(|| JsValue::from_str("Invalid Pauli string"))?;
+        self.frame.apply(&error);
+        self.state.apply_pauli(&error);
         Ok(())
     }
 
+    /// Sample errors on every qubit from a JSON `PauliChannel`, e.g.
+    /// {"type": "depolarizing", "p": 0.01}, with a seeded RNG and compose
+    /// them into the current errors
+    /// Returns the sampled errors as JSON
+    #[wasm_bindgen(js_name = applyNoise)]
+    pub fn apply_noise(&mut self, channel_json: &str, seed: u64) -> Result<String, JsValue> {
+        let channel: PauliChannel =
+            serde_json::from_str(channel_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let errors = channel
+            .sample_code(&self.code, seed)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.compose_errors(&errors);
+        Ok(serde_json::to_string(&errors).unwrap())
+    }
+
+    /// Sample correlated errors from a JSON `TwoQubitNoise`, e.g.
+    /// {"channel": {"type": "crosstalk", "p": 0.01}, "pairs": [[0, 1]]},
+    /// with a seeded RNG and compose them into the current errors
+    /// Returns the sampled single-qubit factors as JSON
+    #[wasm_bindgen(js_name = applyTwoQubitNoise)]
+    pub fn apply_two_qubit_noise(
+        &mut self,
+        noise_json: &str,
+        seed: u64,
+    ) -> Result<String, JsValue> {
+        let noise: TwoQubitNoise =
+            serde_json::from_str(noise_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let errors = noise
+            .sample_code(&self.code, seed)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.compose_errors(&errors);
+        Ok(serde_json::to_string(&errors).unwrap())
+    }
+
+    /// Sample erasures and errors from a JSON `ErasureChannel`, e.g.
+    /// {"type": "conversion", "p": 0.01, "erasure_fraction": 0.95}, with a
+    /// seeded RNG, compose the errors and flag the erased qubits
+    /// Returns the sample as JSON {"erasures": [...], "errors": [...]}
+    #[wasm_bindgen(js_name = applyErasureNoise)]
+    pub fn apply_erasure_noise(
+        &mut self,
+        channel_json: &str,
+        seed: u64,
+    ) -> Result<String, JsValue> {
+        let channel: ErasureChannel =
+            serde_json::from_str(channel_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let sample = channel
+            .sample_code(&self.code, seed)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.compose_errors(&sample.errors);
+        for (flag, &erased) in self.erasures.iter_mut().zip(&sample.erasures) {
+            *flag |= erased;
+        }
+        Ok(serde_json::to_string(&sample).unwrap())
+    }
+
+    /// Indices of erased qubits
+    #[wasm_bindgen(js_name = getErasures)]
+    pub fn get_erasures(&self) -> Vec<usize> {
+        (0..self.erasures.len())
+            .filter(|&q| self.erasures[q])
+            .collect()
+    }
+
+    /// Syndrome with erasure flags as JSON {"outcomes": [...], "erasures": [...]}
+    #[wasm_bindgen(js_name = getErasureSyndrome)]
+    pub fn get_erasure_syndrome(&self) -> String {
+        let syndrome = Syndrome::from_state(&self.state).with_erasures(self.erasures.clone());
+        serde_json::to_string(&syndrome).unwrap()
+    }
+
     /// Get the current syndrome as JSON
     #[wasm_bindgen(js_name = getSyndrome)]
     pub fn get_syndrome(&self) -> String {
@@ -114,50 +237,330 @@ impl QECSimulator {
     /// Reset the simulator to initial state
     #[wasm_bindgen]
     pub fn reset(&mut self, code_name: &str) -> Result<(), JsValue> {
-        let state = get_code_by_name(code_name)
-            .ok_or_else(|| JsValue::from_str(&format!("Unknown code: {}", code_name)))?;
+        let code = get_stabilizer_code(code_name).map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        self.state = state;
-        self.applied_errors.clear();
+        self.state = code.to_state();
+        self.code = code;
+        self.frame = PauliFrame::new(self.code.n_qubits);
+        self.erasures = vec![false; self.code.n_qubits];
         Ok(())
     }
 
     /// Clear error from a specific qubit
     #[wasm_bindgen(js_name = clearQubitError)]
     pub fn clear_qubit_error(&mut self, qubit: usize) -> Result<(), JsValue> {
-        // Remove all errors affecting this qubit
-        self.applied_errors.retain(|e| e.qubit != qubit);
+        if qubit >= self.state.n_qubits() {
+            return Err(JsValue::from_str("Qubit index out of bounds"));
+        }
 
-        // Reset state and reapply remaining errors
-        let code_name = self.get_code_name();
-        self.state = get_code_by_name(&code_name)
-            .ok_or_else(|| JsValue::from_str("Failed to reset state"))?;
+        let change = self.frame.set_qubit(qubit, Pauli::I);
+        self.state.apply_pauli(&change);
+        self.erasures[qubit] = false;
+        Ok(())
+    }
+
+    /// Get applied errors as JSON
+    #[wasm_bindgen(js_name = getAppliedErrors)]
+    pub fn get_applied_errors(&self) -> String {
+        serde_json::to_string(&self.frame.errors()).unwrap()
+    }
+
+    /// Applied errors in the code's basis as JSON {"syndrome": [...],
+    /// "stabilizer": [...], "logical_x": [...], "logical_z": [...]}, flagging
+    /// the destabilizers, generators and logical operators whose product is
+    /// the error; any logical flag means the error changes the encoded state
+    #[wasm_bindgen(js_name = getErrorDecomposition)]
+    pub fn get_error_decomposition(&self) -> String {
+        serde_json::to_string(&self.frame.decompose(&self.code)).unwrap()
+    }
+}
 
-        // Reapply remaining errors
-        for error in &self.applied_errors {
-            error.apply_to_state(&mut self.state);
+impl QECSimulator {
+    /// Compose sampled errors into the frame and the state
+    fn compose_errors(&mut self, errors: &[Error]) {
+        for error in errors {
+            let pauli = error.to_pauli_string(self.code.n_qubits);
+            self.frame.apply(&pauli);
+            self.state.apply_pauli(&pauli);
         }
+    }
+}
 
-        Ok(())
+/// JavaScript-facing stabilizer simulator for qudits of prime dimension d,
+/// e.g. qutrits on the three lowest transmon levels
+#[wasm_bindgen]
+pub struct QuditSimulator {
+    state: StabilizerState<Qudit>,
+}
+
+#[wasm_bindgen]
+impl QuditSimulator {
+    /// |0...0⟩ on `n_qudits` qudits of prime dimension `dim`
+    #[wasm_bindgen(constructor)]
+    pub fn new(n_qudits: usize, dim: u32) -> Result<QuditSimulator, JsValue> {
+        let dim = Qudit::new(dim).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(QuditSimulator {
+            state: StabilizerState::with_dim(dim, n_qudits),
+        })
+    }
+
+    /// Get stabilizers as JSON string, e.g. ["Z I", "I Z"]
+    #[wasm_bindgen(js_name = getStabilizers)]
+    pub fn get_stabilizers(&self) -> String {
+        let stabs: Vec<String> = self
+            .state
+            .get_all_stabilizers()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        serde_json::to_string(&stabs).unwrap()
+    }
+
+    /// Apply "X", "Z", "F" (Fourier) or "P" (phase) to one qudit, or "SUM"
+    /// (control first) or "CZ" to two
+    #[wasm_bindgen(js_name = applyGate)]
+    pub fn apply_gate(&mut self, gate: &str, qudits: Vec<usize>) -> Result<(), JsValue> {
+        self.state
+            .apply_gate(gate, &qudits)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Outcome m (eigenvalue ω^m) of measuring a Pauli string like "X Z2 I"
+    /// if the state determines it, or undefined
+    #[wasm_bindgen(js_name = peekPauli)]
+    pub fn peek_pauli(&self, pauli: &str) -> Result<Option<u32>, JsValue> {
+        let op = PauliString::from_tokens(pauli, self.state.dim())
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.state
+            .peek_pauli(&op)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
-    /// Helper to get current code name
-    fn get_code_name(&self) -> String {
-        // Infer code name from number of qubits and stabilizers
-        let n_qubits = self.state.n_qubits();
-        match n_qubits {
-            3 => "repetition_3".to_string(),
-            5 => "five_qubit".to_string(),
-            7 => "steane".to_string(),
-            9 => "surface_d3".to_string(),
-            _ => "repetition_3".to_string(), // default
+    /// Measure a Pauli string with a seeded RNG, collapsing the state, and
+    /// return the outcome m of the eigenvalue ω^m
+    #[wasm_bindgen(js_name = measurePauli)]
+    pub fn measure_pauli(&mut self, pauli: &str, seed: u64) -> Result<u32, JsValue> {
+        let op = PauliString::from_tokens(pauli, self.state.dim())
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut rng = SmallRng::seed_from_u64(seed);
+        self.state
+            .measure_pauli(&op, &mut rng)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+/// JavaScript-facing lattice surgery on rotated surface code patches that
+/// share one grid of data qubits
+#[wasm_bindgen]
+pub struct SurgerySimulator {
+    lattice: Lattice,
+}
+
+/// Single Pauli from "I", "X", "Y" or "Z"
+fn parse_single_pauli(name: &str) -> Result<Pauli, JsValue> {
+    let mut chars = name.chars();
+    match (chars.next().and_then(Pauli::from_char), chars.next()) {
+        (Some(pauli), None) => Ok(pauli),
+        _ => Err(JsValue::from_str("Invalid Pauli")),
+    }
+}
+
+/// Stages of a deformation with their signed checks, and the logical outcome
+fn deformation_json(deformation: &Deformation) -> serde_json::Value {
+    let stages: Vec<serde_json::Value> = deformation
+        .stages
+        .iter()
+        .map(|stage| {
+            let stabilizers: Vec<String> =
+                stage.stabilizers.iter().map(|s| s.to_string()).collect();
+            serde_json::json!({ "label": stage.label, "stabilizers": stabilizers })
+        })
+        .collect();
+    serde_json::json!({ "stages": stages, "outcome": deformation.outcome })
+}
+
+#[wasm_bindgen]
+impl SurgerySimulator {
+    /// Empty `rows` x `cols` grid with seeded measurement randomness
+    #[wasm_bindgen(constructor)]
+    pub fn new(rows: usize, cols: usize, seed: u64) -> SurgerySimulator {
+        SurgerySimulator {
+            lattice: Lattice::new(rows, cols, seed),
         }
     }
 
-    /// Get applied errors as JSON
-    #[wasm_bindgen(js_name = getAppliedErrors)]
-    pub fn get_applied_errors(&self) -> String {
-        serde_json::to_string(&self.applied_errors).unwrap()
+    /// Add a distance-d patch with its top-left qubit at (row, col), in |0̄⟩
+    /// for `basis` "Z" or |+̄⟩ for "X"; returns the patch index
+    #[wasm_bindgen(js_name = addPatch)]
+    pub fn add_patch(
+        &mut self,
+        name: &str,
+        row: usize,
+        col: usize,
+        d: usize,
+        basis: &str,
+    ) -> Result<usize, JsValue> {
+        let basis = parse_single_pauli(basis)?;
+        if !matches!(basis, Pauli::X | Pauli::Z) {
+            return Err(JsValue::from_str(
+                "Patches are prepared in the X or Z basis",
+            ));
+        }
+        self.lattice
+            .add_patch(name, Region::square(row, col, d), basis)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Patch names and regions as JSON
+    #[wasm_bindgen(js_name = getPatches)]
+    pub fn get_patches(&self) -> String {
+        let patches: Vec<serde_json::Value> = self
+            .lattice
+            .patches
+            .iter()
+            .map(|p| serde_json::json!({ "name": p.name, "region": p.region }))
+            .collect();
+        serde_json::to_string(&patches).unwrap()
+    }
+
+    /// Signed checks of every patch as JSON
+    #[wasm_bindgen(js_name = getStabilizers)]
+    pub fn get_stabilizers(&self) -> Result<String, JsValue> {
+        let stabilizers: Vec<String> = self
+            .lattice
+            .stabilizers()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        Ok(serde_json::to_string(&stabilizers).unwrap())
+    }
+
+    /// Merge two patches; returns the deformation and the measured joint
+    /// logical as JSON
+    pub fn merge(&mut self, a: usize, b: usize) -> Result<String, JsValue> {
+        self.lattice
+            .merge(a, b)
+            .map(|d| deformation_json(&d).to_string())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Split a merged pair; returns the deformation as JSON
+    pub fn split(&mut self, a: usize, b: usize) -> Result<String, JsValue> {
+        self.lattice
+            .split(a, b)
+            .map(|d| deformation_json(&d).to_string())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Merge and split: X̄X̄ for side by side patches, Z̄Z̄ for stacked ones
+    #[wasm_bindgen(js_name = measureJoint)]
+    pub fn measure_joint(&mut self, a: usize, b: usize) -> Result<String, JsValue> {
+        self.lattice
+            .measure_joint(a, b)
+            .map(|d| deformation_json(&d).to_string())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Grow a patch to the given region
+    #[wasm_bindgen(js_name = extendPatch)]
+    pub fn extend_patch(
+        &mut self,
+        p: usize,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Result<String, JsValue> {
+        self.lattice
+            .extend(p, Region::new(row, col, rows, cols))
+            .map(|d| deformation_json(&d).to_string())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Shrink a patch to the given region
+    #[wasm_bindgen(js_name = shrinkPatch)]
+    pub fn shrink_patch(
+        &mut self,
+        p: usize,
+        row: usize,
+        col: usize,
+        rows: usize,
+        cols: usize,
+    ) -> Result<String, JsValue> {
+        self.lattice
+            .shrink(p, Region::new(row, col, rows, cols))
+            .map(|d| deformation_json(&d).to_string())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Move a patch to a region of the same size along a row or column
+    #[wasm_bindgen(js_name = movePatch)]
+    pub fn move_patch(&mut self, p: usize, row: usize, col: usize) -> Result<String, JsValue> {
+        let old = self
+            .lattice
+            .patches
+            .get(p)
+            .map(|patch| patch.region)
+            .ok_or_else(|| JsValue::from_str("Unknown patch"))?;
+        self.lattice
+            .move_patch(p, Region::new(row, col, old.rows, old.cols))
+            .map(|d| deformation_json(&d).to_string())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Logical CNOT through an ancilla patch stacked with the control and
+    /// beside the target; returns both joint measurements and the final
+    /// ancilla outcome as JSON
+    pub fn cnot(
+        &mut self,
+        control: usize,
+        ancilla: usize,
+        target: usize,
+    ) -> Result<String, JsValue> {
+        let record = self
+            .lattice
+            .cnot(control, ancilla, target)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(serde_json::json!({
+            "zz": deformation_json(&record.zz),
+            "xx": deformation_json(&record.xx),
+            "ancilla_z": record.ancilla_z,
+        })
+        .to_string())
+    }
+
+    /// Measure "X", "Y" or "Z" of a patch (true for -1)
+    #[wasm_bindgen(js_name = measureLogical)]
+    pub fn measure_logical(&mut self, p: usize, pauli: &str) -> Result<bool, JsValue> {
+        let pauli = parse_single_pauli(pauli)?;
+        self.lattice
+            .measure_logical(p, pauli)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Apply a logical "X", "Y" or "Z" to a patch
+    #[wasm_bindgen(js_name = applyLogical)]
+    pub fn apply_logical(&mut self, p: usize, pauli: &str) -> Result<(), JsValue> {
+        let pauli = parse_single_pauli(pauli)?;
+        self.lattice
+            .apply_logical(p, pauli)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Value of a product of logical Paulis given as JSON pairs, e.g.
+    /// [[0, "Z"], [1, "Z"]] (true for -1), or undefined if it is random
+    #[wasm_bindgen(js_name = peekLogical)]
+    pub fn peek_logical(&self, paulis_json: &str) -> Result<Option<bool>, JsValue> {
+        let pairs: Vec<(usize, String)> =
+            serde_json::from_str(paulis_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let paulis = pairs
+            .iter()
+            .map(|(p, pauli)| parse_single_pauli(pauli).map(|pauli| (*p, pauli)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.lattice
+            .peek_logical(&paulis)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 }
 
@@ -170,9 +573,390 @@ pub fn get_available_codes() -> String {
 /// Get information about a specific code (module-level function)
 #[wasm_bindgen(js_name = getCodeInfo)]
 pub fn get_code_info_js(code_name: &str) -> String {
-    if let Some(info) = get_code_info(code_name) {
+    if let Ok(info) = get_code_info(code_name) {
         serde_json::to_string(&info).unwrap()
     } else {
         "null".to_string()
     }
 }
+
+/// Get logical operators of a specific code as JSON {"x": [...], "z": [...]}
+#[wasm_bindgen(js_name = getLogicalOperators)]
+pub fn get_logical_operators_js(code_name: &str) -> String {
+    if let Ok(logicals) = get_logical_operators(code_name) {
+        serde_json::to_string(&logicals).unwrap()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Logical gates implemented by automorphisms of a code as a JSON list of
+/// names, e.g. ["transversal H"]; computed on first use and then cached
+/// Returns "null" for unknown codes and codes too large to search.
+#[wasm_bindgen(js_name = getLogicalGates)]
+pub fn get_logical_gates_js(code_name: &str) -> String {
+    if let Ok(gates) = get_logical_gates(code_name) {
+        serde_json::to_string(&gates).unwrap()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Drawing layout of a code as JSON: data and ancilla coordinates and
+/// plaquette outlines, or "null" if the code has no natural geometry
+#[wasm_bindgen(js_name = getCodeLayout)]
+pub fn get_code_layout_js(code_name: &str) -> String {
+    match with_registry(|registry| registry.get(code_name)).map(|code| code.layout()) {
+        Ok(Some(layout)) => serde_json::to_string(&layout).unwrap(),
+        _ => "null".to_string(),
+    }
+}
+
+/// Circuit for a code as text, one gate per line ("H 0", "CNOT 0 1")
+/// `kind` is "zero" or "plus" for logical state preparation from |0...0⟩,
+/// or "decoder" for the unencoding circuit; "null" for unknown codes or kinds
+/// and codes the synthesis rejects
+#[wasm_bindgen(js_name = getEncodingCircuit)]
+pub fn get_encoding_circuit_js(code_name: &str, kind: &str) -> String {
+    let Ok(code) = get_stabilizer_code(code_name) else {
+        return "null".to_string();
+    };
+    let circuit = match kind {
+        "zero" => prepare_logical_zero(&code),
+        "plus" => prepare_logical_plus(&code),
+        "decoder" => decoder(&code),
+        _ => return "null".to_string(),
+    };
+    circuit.map_or_else(|_| "null".to_string(), |circuit| circuit.to_string())
+}
+
+/// Shor-Laflamme weight enumerators of a code as JSON, with the distance
+/// they imply, or "null" if the stabilizer group is too large to enumerate
+#[wasm_bindgen(js_name = getWeightEnumerators)]
+pub fn get_weight_enumerators_js(code_name: &str) -> String {
+    let Some(enumerators) = get_stabilizer_code(code_name)
+        .ok()
+        .and_then(|code| WeightEnumerators::of(&code))
+    else {
+        return "null".to_string();
+    };
+    serde_json::json!({
+        "stabilizer": enumerators.stabilizer,
+        "normalizer": enumerators.normalizer,
+        "distance": enumerators.distance(),
+        "logical_error_polynomial": enumerators.logical_error_polynomial(),
+    })
+    .to_string()
+}
+
+/// Run the honeycomb Floquet code on a `rows` x `cols` torus from |0...0⟩
+/// for `n_rounds` rounds with a seeded RNG
+/// Returns JSON with the check schedule, the plaquettes, the number of
+/// logical qubits after `n_rounds` rounds and the outcomes of every check
+/// (true = -1), or an error unless `rows` is even and `cols` a multiple of 6.
+#[wasm_bindgen(js_name = simulateFloquetCode)]
+pub fn simulate_floquet_code_js(
+    rows: usize,
+    cols: usize,
+    n_rounds: usize,
+    seed: u64,
+) -> Result<String, JsValue> {
+    let code = honeycomb_code(rows, cols).ok_or_else(|| {
+        JsValue::from_str("Honeycomb code needs an even number of rows and a multiple of 6 columns")
+    })?;
+    let strings = |ops: &[PauliString]| ops.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    let mut state = StabilizerState::new(code.n_qubits);
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let outcomes = code.simulate(&mut state, n_rounds, &mut rng);
+    Ok(serde_json::json!({
+        "n_qubits": code.n_qubits,
+        "rounds": code.rounds.iter().map(|r| strings(r)).collect::<Vec<_>>(),
+        "plaquettes": strings(&code.plaquettes),
+        "n_logical": code.n_logical_after(n_rounds),
+        "outcomes": outcomes,
+    })
+    .to_string())
+}
+
+/// Register a code from a JSON description under `key`
+/// Returns the code's info as JSON, with `distance_exact` false if the
+/// claimed distance was too costly to check, or an error if the file is
+/// invalid or `key` is taken
+#[wasm_bindgen(js_name = loadCodeJson)]
+pub fn load_code_json(key: &str, json: &str) -> Result<String, JsValue> {
+    let description =
+        CodeDescription::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let info = with_registry(|registry| {
+        registry.register_description(key, description)?;
+        registry.get(key).map(|code| code.info())
+    })
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_json::to_string(&info).unwrap())
+}
+
+/// Register a CSS code from H_X and H_Z alist files under `key`
+#[wasm_bindgen(js_name = loadCodeAlist)]
+pub fn load_code_alist(key: &str, hx: &str, hz: &str) -> Result<String, JsValue> {
+    let description = CodeDescription::from_css_alist(key, hx, hz)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let info = with_registry(|registry| {
+        registry.register_description(key, description)?;
+        registry.get(key).map(|code| code.info())
+    })
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_json::to_string(&info).unwrap())
+}
+
+/// JSON description of a registered code, or "null"
+#[wasm_bindgen(js_name = exportCodeJson)]
+pub fn export_code_json(code_name: &str) -> String {
+    match with_registry(|registry| CodeDescription::from_registry(registry, code_name)) {
+        Ok(description) => description.to_json(),
+        Err(_) => "null".to_string(),
+    }
+}
+
+/// H_X and H_Z alist files of a registered CSS code as JSON {"hx": ..., "hz": ...},
+/// or "null" for unknown and non-CSS codes
+#[wasm_bindgen(js_name = exportCodeAlist)]
+pub fn export_code_alist(code_name: &str) -> String {
+    let alist = with_registry(|registry| CodeDescription::from_registry(registry, code_name))
+        .and_then(|description| description.to_css_alist());
+    match alist {
+        Ok(Some((hx, hz))) => serde_json::json!({ "hx": hx, "hz": hz }).to_string(),
+        _ => "null".to_string(),
+    }
+}
+
+/// Search for codes as configured by a JSON `SearchConfig` and return the
+/// Pareto front of (n, k, d) as a JSON array of code descriptions, named
+/// "search_0", "search_1", ... in order of n
+#[wasm_bindgen(js_name = searchCodes)]
+pub fn search_codes_js(config_json: &str) -> Result<String, JsValue> {
+    let config: SearchConfig =
+        serde_json::from_str(config_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let descriptions: Vec<CodeDescription> = search_codes(&config)
+        .candidates
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| candidate.description(&format!("search_{}", i)))
+        .collect();
+    Ok(serde_json::to_string(&descriptions).unwrap())
+}
+
+/// Noise model preset ("noiseless", "sd6", "si1000" or "uniform") with
+/// strength `p` as JSON, for reproducible experiments
+#[wasm_bindgen(js_name = getNoisePreset)]
+pub fn get_noise_preset_js(name: &str, p: f64) -> Result<String, JsValue> {
+    NoiseModel::preset(name, p)
+        .map(|model| model.to_json())
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Pauli-twirled noise model of a chip as JSON, derived from a JSON
+/// `DeviceCalibration` snapshot (T1, T2, gate errors, readout, durations)
+#[wasm_bindgen(js_name = noiseModelFromCalibration)]
+pub fn noise_model_from_calibration_js(calibration_json: &str) -> Result<String, JsValue> {
+    DeviceCalibration::from_json(calibration_json)
+        .and_then(|calibration| calibration.noise_model())
+        .map(|model| model.to_json())
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Pauli twirl of a one- or two-qubit channel given as a JSON
+/// `KrausChannel` {"n_qubits": 1, "operators": [[[[re, im], ...], ...], ...]}
+/// Returns a JSON `PauliChannel` for one qubit and a `TwoQubitChannel`
+/// probability table for two, ready to use in noise models.
+#[wasm_bindgen(js_name = twirlKrausChannel)]
+pub fn twirl_kraus_channel_js(kraus_json: &str) -> Result<String, JsValue> {
+    let channel: KrausChannel =
+        serde_json::from_str(kraus_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let twirled = if channel.n_qubits() == 1 {
+        channel
+            .pauli_channel()
+            .map(|c| serde_json::to_string(&c).unwrap())
+    } else {
+        channel
+            .two_qubit_channel()
+            .map(|c| serde_json::to_string(&c).unwrap())
+    };
+    twirled.map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Memory experiment on a code with a layout under cosmic ray bursts from a
+/// JSON `CosmicRayModel`, decoded each round by a lookup table up to weight
+/// (d - 1) / 2, lowered for large codes until the table has at most
+/// `MAX_LOOKUP_ERRORS` entries; returns failure counts with and without
+/// impacts as JSON
+#[wasm_bindgen(js_name = simulateCosmicRays)]
+pub fn simulate_cosmic_rays_js(
+    code_name: &str,
+    model_json: &str,
+    rounds: usize,
+    shots: usize,
+    seed: u64,
+) -> Result<String, JsValue> {
+    let model: CosmicRayModel =
+        serde_json::from_str(model_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    model
+        .validate()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let code = get_stabilizer_code(code_name).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let layout = with_registry(|registry| registry.get(code_name))
+        .ok()
+        .and_then(|code| code.layout())
+        .ok_or_else(|| JsValue::from_str("Code has no layout"))?;
+    let weight =
+        LookupDecoder::affordable_weight(code.n_qubits, code.distance.saturating_sub(1) / 2);
+    let decoder = LookupDecoder::new(&code, weight);
+    let study = cosmic_ray_study(&code, &layout, model, &decoder, rounds, shots, seed)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_json::to_string(&study).unwrap())
+}
+
+/// Memory experiment on a code under the drifting T1 and T2 of a JSON
+/// `DriftModel`, one calibrated qubit per code qubit, decoded each round by a
+/// lookup table as in `simulateCosmicRays`
+/// Returns the failure count and the parameter values of each round of the
+/// last shot, one per drift, as JSON {"memory": ..., "trajectory": [[...]]}.
+#[wasm_bindgen(js_name = simulateDrift)]
+pub fn simulate_drift_js(
+    code_name: &str,
+    model_json: &str,
+    rounds: usize,
+    shots: usize,
+    seed: u64,
+) -> Result<String, JsValue> {
+    let model = DriftModel::from_json(model_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let code = get_stabilizer_code(code_name).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let mut noise =
+        DriftingNoise::new(model, code.n_qubits).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let weight =
+        LookupDecoder::affordable_weight(code.n_qubits, code.distance.saturating_sub(1) / 2);
+    let decoder = LookupDecoder::new(&code, weight);
+    let result = run_memory(&code, &mut noise, &decoder, rounds, shots, seed)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let parameters: Vec<&str> = noise
+        .model()
+        .drifts
+        .iter()
+        .map(|drift| drift.parameter.name())
+        .collect();
+    let last_round = noise
+        .noise_model()
+        .transpose()
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_json::json!({
+        "memory": result,
+        "parameters": parameters,
+        "trajectory": noise.trajectory,
+        "last_round_noise": last_round,
+    })
+    .to_string())
+}
+
+/// One shot of `rounds` rounds of the errors a JSON `DriftModel` causes on
+/// its qubits, as JSON {"trajectory": [[...]], "errors": [[...]]} with the
+/// parameter values and the errors of each round
+#[wasm_bindgen(js_name = sampleDrift)]
+pub fn sample_drift_js(model_json: &str, rounds: usize, seed: u64) -> Result<String, JsValue> {
+    let model = DriftModel::from_json(model_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let n_qubits = model.calibration.qubits.len();
+    let mut noise =
+        DriftingNoise::new(model, n_qubits).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let sample = noise
+        .sample_shot(rounds, &mut SmallRng::seed_from_u64(seed))
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_json::to_string(&sample).unwrap())
+}
+
+/// Values of a JSON `Schedule`, e.g. {"type": "telegraph", "low": 0.001,
+/// "high": 0.01, "switch_rate": 0.05}, for rounds 0..rounds as a JSON array
+#[wasm_bindgen(js_name = realizeSchedule)]
+pub fn realize_schedule_js(
+    schedule_json: &str,
+    rounds: usize,
+    seed: u64,
+) -> Result<String, JsValue> {
+    let schedule: Schedule =
+        serde_json::from_str(schedule_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let values = schedule
+        .realize(rounds, &mut SmallRng::seed_from_u64(seed))
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_json::to_string(&values).unwrap())
+}
+
+/// Noisy preparation of a code's |0̄⟩ on transmons that leak to |2⟩, under
+/// a JSON `NoiseModel` and a JSON `LeakageModel`, e.g. {"leak": 0.001,
+/// "seep": 0.01, "lru_efficiency": 0.99}, optionally followed by an LRU on
+/// every qubit
+/// Returns per-qubit leakage counts at the end of the circuit and at
+/// readout, and the number of cleanly prepared shots, as JSON.
+#[wasm_bindgen(js_name = simulateLeakage)]
+pub fn simulate_leakage_js(
+    code_name: &str,
+    noise_json: &str,
+    leakage_json: &str,
+    lru: bool,
+    shots: usize,
+    seed: u64,
+) -> Result<String, JsValue> {
+    let code = get_stabilizer_code(code_name).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let noise = NoiseModel::from_json(noise_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let model: LeakageModel =
+        serde_json::from_str(leakage_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let circuit = prepare_logical_zero(&code).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let mut target = code.generators.clone();
+    target.extend(code.logical_z.iter().cloned());
+    let result = run_preparation(&circuit, &target, &noise, model, lru, shots, seed)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_json::to_string(&result).unwrap())
+}
+
+/// Memory experiment on a distance-d rotated surface code adapted around a
+/// JSON list of defects, e.g. [{"data_qubit": 12}, {"ancilla": 3},
+/// {"coupler": {"ancilla": 5, "data": 7}}], under a JSON `PauliChannel`
+/// Only flips of the encoded qubit count as failures, not of the gauge
+/// qubits left by the defects. Returns the adapted code (working and
+/// disabled qubits, gauges, super-stabilizers, layout, dressed distance) and the
+/// failure count as JSON.
+#[wasm_bindgen(js_name = simulateDefectiveSurfaceCode)]
+pub fn simulate_defective_surface_code_js(
+    d: usize,
+    defects_json: &str,
+    channel_json: &str,
+    rounds: usize,
+    shots: usize,
+    seed: u64,
+) -> Result<String, JsValue> {
+    let defects: Vec<Defect> =
+        serde_json::from_str(defects_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let channel: PauliChannel =
+        serde_json::from_str(channel_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let adapted =
+        defective_surface_code(d, &defects).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let n = adapted.code.n_qubits;
+    let weight =
+        LookupDecoder::affordable_weight(n, adapted.distance.distance.saturating_sub(1) / 2);
+    let decoder = LookupDecoder::new(&adapted.code, weight);
+    let mut noise = StationaryNoise {
+        channel,
+        n_qubits: n,
+    };
+    let result = adapted
+        .run_memory(&mut noise, &decoder, rounds, shots, seed)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let strings = |ops: &[PauliString]| ops.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    Ok(serde_json::json!({
+        "data_qubits": adapted.data_qubits,
+        "disabled_qubits": adapted.disabled_qubits,
+        "gauge_operators": strings(&adapted.gauge_operators),
+        "stabilizers": strings(&adapted.code.generators),
+        "super_stabilizers": adapted.super_stabilizers,
+        "n_gauge_qubits": adapted.n_gauge_qubits(),
+        "layout": adapted.layout(),
+        "distance": adapted.distance,
+        "memory": result,
+    })
+    .to_string())
+}
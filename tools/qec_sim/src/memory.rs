@@ -0,0 +1,212 @@
+#![allow(dead_code)]
+// Multi-round memory experiments
+//
+// Each round a `RoundNoise` source adds Pauli errors to the frame, the
+// syndrome is read out perfectly and the decoder's correction is applied.
+// After the last round the shot fails if the residual error is a nontrivial
+// logical operator. Noise sources that change over time (bursts, drifts)
+// keep their own per-shot state and see the round index.
+use crate::codes::StabilizerCode;
+use crate::decoder::Decoder;
+use crate::error::{Error, NoiseError, PauliChannel, PauliFrame};
+use crate::pauli::PauliString;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+/// Noise injected once per round of syndrome extraction
+pub trait RoundNoise {
+    /// Forget any state from the previous shot
+    fn reset(&mut self) {}
+
+    /// Errors occurring in `round` of the current shot
+    fn sample_round(&mut self, round: usize, rng: &mut SmallRng) -> Result<Vec<Error>, NoiseError>;
+}
+
+/// The same channel on every qubit in every round
+pub struct StationaryNoise {
+    pub channel: PauliChannel,
+    pub n_qubits: usize,
+}
+
+impl RoundNoise for StationaryNoise {
+    fn sample_round(
+        &mut self,
+        _round: usize,
+        rng: &mut SmallRng,
+    ) -> Result<Vec<Error>, NoiseError> {
+        self.channel.sample(self.n_qubits, rng)
+    }
+}
+
+/// Outcome of a memory experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryResult {
+    pub shots: usize,
+    pub rounds: usize,
+    pub failures: usize,
+}
+
+impl MemoryResult {
+    /// Fraction of shots ending in a logical error
+    pub fn logical_error_rate(&self) -> f64 {
+        self.failures as f64 / self.shots.max(1) as f64
+    }
+
+    /// Logical error rate per round, treating rounds as independent flips:
+    /// P = (1 - (1 - 2 p)^r) / 2 solved for p
+    pub fn per_round_error_rate(&self) -> f64 {
+        let p = self.logical_error_rate().min(0.5);
+        (1.0 - (1.0 - 2.0 * p).powf(1.0 / self.rounds.max(1) as f64)) / 2.0
+    }
+}
+
+/// Whether a residual error (error times correction) is a logical error
+pub fn is_logical_failure(code: &StabilizerCode, residual: &PauliString) -> bool {
+    code.logical_x
+        .iter()
+        .chain(&code.logical_z)
+        .any(|l| !l.commutes_with(residual))
+}
+
+/// Run one shot of `rounds` rounds and report whether it failed
+pub fn run_shot<N: RoundNoise + ?Sized, D: Decoder + ?Sized>(
+    code: &StabilizerCode,
+    noise: &mut N,
+    decoder: &D,
+    rounds: usize,
+    rng: &mut SmallRng,
+) -> Result<bool, NoiseError> {
+    run_shot_with(code, noise, decoder, rounds, rng, |residual| {
+        is_logical_failure(code, residual)
+    })
+}
+
+/// `run_shot` with a custom test of the final residual, for codes where
+/// some logical qubits are gauge qubits whose flips are harmless
+pub fn run_shot_with<N, D, F>(
+    code: &StabilizerCode,
+    noise: &mut N,
+    decoder: &D,
+    rounds: usize,
+    rng: &mut SmallRng,
+    is_failure: F,
+) -> Result<bool, NoiseError>
+where
+    N: RoundNoise + ?Sized,
+    D: Decoder + ?Sized,
+    F: Fn(&PauliString) -> bool,
+{
+    noise.reset();
+    let mut frame = PauliFrame::new(code.n_qubits);
+    for round in 0..rounds {
+        for error in noise.sample_round(round, rng)? {
+            frame.apply_error(&error);
+        }
+        let correction = decoder.decode(&code.syndrome_of(frame.pauli()));
+        frame.apply(&correction);
+    }
+    Ok(is_failure(frame.pauli()))
+}
+
+/// Run `shots` independent shots with a `SmallRng` seeded with `seed`
+pub fn run_memory<N: RoundNoise + ?Sized, D: Decoder + ?Sized>(
+    code: &StabilizerCode,
+    noise: &mut N,
+    decoder: &D,
+    rounds: usize,
+    shots: usize,
+    seed: u64,
+) -> Result<MemoryResult, NoiseError> {
+    run_memory_with(code, noise, decoder, rounds, shots, seed, |residual| {
+        is_logical_failure(code, residual)
+    })
+}
+
+/// `run_memory` with a custom failure test, see `run_shot_with`
+pub fn run_memory_with<N, D, F>(
+    code: &StabilizerCode,
+    noise: &mut N,
+    decoder: &D,
+    rounds: usize,
+    shots: usize,
+    seed: u64,
+    is_failure: F,
+) -> Result<MemoryResult, NoiseError>
+where
+    N: RoundNoise + ?Sized,
+    D: Decoder + ?Sized,
+    F: Fn(&PauliString) -> bool,
+{
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut failures = 0;
+    for _ in 0..shots {
+        failures += run_shot_with(code, noise, decoder, rounds, &mut rng, &is_failure)? as usize;
+    }
+    Ok(MemoryResult {
+        shots,
+        rounds,
+        failures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::get_stabilizer_code;
+    use crate::decoder::LookupDecoder;
+
+    #[test]
+    fn test_noiseless_memory() {
+        let code = get_stabilizer_code("surface_d3").unwrap();
+        let decoder = LookupDecoder::new(&code, 1);
+        let mut noise = StationaryNoise {
+            channel: PauliChannel::Depolarizing { p: 0.0 },
+            n_qubits: code.n_qubits,
+        };
+        let result = run_memory(&code, &mut noise, &decoder, 5, 20, 1).unwrap();
+        assert_eq!(result.failures, 0);
+        assert_eq!(result.per_round_error_rate(), 0.0);
+
+        // An invalid channel fails the experiment instead of sampling
+        noise.channel = PauliChannel::Depolarizing { p: 1.5 };
+        assert!(run_memory(&code, &mut noise, &decoder, 5, 20, 1).is_err());
+    }
+
+    #[test]
+    fn test_correction_each_round() {
+        // Weight-one errors every round are always corrected at d = 3
+        struct OneFlip;
+        impl RoundNoise for OneFlip {
+            fn sample_round(
+                &mut self,
+                round: usize,
+                _rng: &mut SmallRng,
+            ) -> Result<Vec<Error>, NoiseError> {
+                Ok(vec![Error::new(round % 9, crate::error::ErrorType::Y)])
+            }
+        }
+        let code = get_stabilizer_code("surface_d3").unwrap();
+        let decoder = LookupDecoder::new(&code, 1);
+        let result = run_memory(&code, &mut OneFlip, &decoder, 9, 3, 2).unwrap();
+        assert_eq!(result.failures, 0);
+    }
+
+    #[test]
+    fn test_error_rate_grows_with_noise() {
+        let code = get_stabilizer_code("surface_d3").unwrap();
+        let decoder = LookupDecoder::new(&code, 1);
+        let rate = |p: f64| {
+            let mut noise = StationaryNoise {
+                channel: PauliChannel::Depolarizing { p },
+                n_qubits: code.n_qubits,
+            };
+            run_memory(&code, &mut noise, &decoder, 3, 400, 7)
+                .unwrap()
+                .logical_error_rate()
+        };
+        let (low, high) = (rate(0.01), rate(0.1));
+        assert!(low < high, "{} vs {}", low, high);
+        assert!(high > 0.05);
+    }
+}
@@ -0,0 +1,453 @@
+#![allow(dead_code)]
+// Circuit-level noise models
+//
+// A `NoiseModel` names the channel applied at each kind of circuit location.
+// Simulators built on `StabilizerState` call the hooks below at the matching
+// points (after a gate, after a reset, ...) and apply the sampled errors;
+// measurement noise flips the recorded outcome instead. `run_noisy` does so
+// for a `Circuit` scheduled into moments.
+use crate::circuit::{Circuit, Gate};
+use crate::error::{Error, ErrorType, NoiseError, PauliChannel, TwoQubitChannel, TwoQubitNoise};
+use crate::pauli::{Pauli, PauliString};
+use crate::stabilizer::StabilizerState;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Error channels per operation class; a missing channel means no noise there
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseModel {
+    pub name: String,
+    /// After every single-qubit gate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub single_qubit_gate: Option<PauliChannel>,
+    /// After every two-qubit gate, on its pair of qubits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub two_qubit_gate: Option<TwoQubitChannel>,
+    /// On every Z-basis measurement; an X or Y error flips the recorded
+    /// outcome and leaves the state alone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measurement: Option<PauliChannel>,
+    /// After every reset to |0⟩
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset: Option<PauliChannel>,
+    /// On every qubit left idle during a time step of gates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle: Option<PauliChannel>,
+    /// On every qubit left idle while others are measured or reset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measurement_idle: Option<PauliChannel>,
+    /// Per-qubit channels replacing the ones above on that qubit
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub qubits: BTreeMap<usize, QubitNoise>,
+    /// Two-qubit gate channels replacing `two_qubit_gate` on specific pairs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub couplers: Vec<TwoQubitNoise>,
+}
+
+/// Single-qubit channels of one qubit; a missing channel falls back to the
+/// model-wide one
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QubitNoise {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub single_qubit_gate: Option<PauliChannel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measurement: Option<PauliChannel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reset: Option<PauliChannel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle: Option<PauliChannel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measurement_idle: Option<PauliChannel>,
+}
+
+impl NoiseModel {
+    /// Model without any noise
+    pub fn noiseless() -> Self {
+        NoiseModel {
+            name: "noiseless".to_string(),
+            single_qubit_gate: None,
+            two_qubit_gate: None,
+            measurement: None,
+            reset: None,
+            idle: None,
+            measurement_idle: None,
+            qubits: BTreeMap::new(),
+            couplers: Vec::new(),
+        }
+    }
+
+    /// Standard depolarizing (SD6): depolarizing gates and idles, flipped
+    /// resets and measurements, all with strength p
+    pub fn sd6(p: f64) -> Self {
+        NoiseModel {
+            name: "sd6".to_string(),
+            single_qubit_gate: Some(PauliChannel::Depolarizing { p }),
+            two_qubit_gate: Some(TwoQubitChannel::Depolarizing { p }),
+            measurement: Some(PauliChannel::BitFlip { p }),
+            reset: Some(PauliChannel::BitFlip { p }),
+            idle: Some(PauliChannel::Depolarizing { p }),
+            measurement_idle: None,
+            ..NoiseModel::noiseless()
+        }
+    }
+
+    /// Superconducting-inspired (SI1000): cheap single-qubit gates and
+    /// idles (p/10), two-qubit gates at p, resets 2p, measurements 5p and
+    /// 2p depolarizing on qubits waiting for a measurement or reset
+    pub fn si1000(p: f64) -> Self {
+        NoiseModel {
+            name: "si1000".to_string(),
+            single_qubit_gate: Some(PauliChannel::Depolarizing { p: p / 10.0 }),
+            two_qubit_gate: Some(TwoQubitChannel::Depolarizing { p }),
+            measurement: Some(PauliChannel::BitFlip { p: 5.0 * p }),
+            reset: Some(PauliChannel::BitFlip { p: 2.0 * p }),
+            idle: Some(PauliChannel::Depolarizing { p: p / 10.0 }),
+            measurement_idle: Some(PauliChannel::Depolarizing { p: 2.0 * p }),
+            ..NoiseModel::noiseless()
+        }
+    }
+
+    /// Uniform depolarizing: the depolarizing channel with strength p at
+    /// every location, including resets, measurements and measurement idles
+    pub fn uniform(p: f64) -> Self {
+        let channel = Some(PauliChannel::Depolarizing { p });
+        NoiseModel {
+            name: "uniform".to_string(),
+            single_qubit_gate: channel,
+            two_qubit_gate: Some(TwoQubitChannel::Depolarizing { p }),
+            measurement: channel,
+            reset: channel,
+            idle: channel,
+            measurement_idle: channel,
+            ..NoiseModel::noiseless()
+        }
+    }
+
+    /// Preset by name: "noiseless", "sd6", "si1000" or "uniform"
+    pub fn preset(name: &str, p: f64) -> Result<Self, NoiseError> {
+        let model = match name {
+            "noiseless" => NoiseModel::noiseless(),
+            "sd6" => NoiseModel::sd6(p),
+            "si1000" => NoiseModel::si1000(p),
+            "uniform" => NoiseModel::uniform(p),
+            _ => return Err(NoiseError::UnknownPreset(name.to_string())),
+        };
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Check every channel
+    pub fn validate(&self) -> Result<(), NoiseError> {
+        let single = [
+            &self.single_qubit_gate,
+            &self.measurement,
+            &self.reset,
+            &self.idle,
+            &self.measurement_idle,
+        ];
+        let per_qubit = self.qubits.values().flat_map(|noise| {
+            [
+                &noise.single_qubit_gate,
+                &noise.measurement,
+                &noise.reset,
+                &noise.idle,
+                &noise.measurement_idle,
+            ]
+        });
+        for channel in single.into_iter().chain(per_qubit).flatten() {
+            channel.validate()?;
+        }
+        if let Some(channel) = &self.two_qubit_gate {
+            channel.validate()?;
+        }
+        for coupler in &self.couplers {
+            coupler.channel.validate()?;
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, NoiseError> {
+        let model: NoiseModel =
+            serde_json::from_str(json).map_err(|e| NoiseError::InvalidFile {
+                format: "noise model".to_string(),
+                reason: e.to_string(),
+            })?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// Channel of `qubit` chosen by `select`, falling back to `default`
+    fn qubit_channel(
+        &self,
+        qubit: usize,
+        select: fn(&QubitNoise) -> Option<PauliChannel>,
+        default: Option<PauliChannel>,
+    ) -> Option<PauliChannel> {
+        self.qubits.get(&qubit).and_then(select).or(default)
+    }
+
+    /// Two-qubit gate channel of the pair (a, b), in either order
+    pub fn coupler_channel(&self, a: usize, b: usize) -> Option<&TwoQubitChannel> {
+        self.couplers
+            .iter()
+            .find(|coupler| coupler.pairs.contains(&[a, b]) || coupler.pairs.contains(&[b, a]))
+            .map(|coupler| &coupler.channel)
+            .or(self.two_qubit_gate.as_ref())
+    }
+
+    /// Errors after `gate`
+    pub fn after_gate<R: Rng>(&self, gate: Gate, rng: &mut R) -> Result<Vec<Error>, NoiseError> {
+        match gate.qubits()[..] {
+            [q] => {
+                let channel =
+                    self.qubit_channel(q, |n| n.single_qubit_gate, self.single_qubit_gate);
+                Ok(sample_on(channel, q, rng)?.into_iter().collect())
+            }
+            [a, b] => match self.coupler_channel(a, b) {
+                Some(channel) => Ok(channel
+                    .sample_pair(rng)?
+                    .map(|paulis| errors_on(&[a, b], &paulis))
+                    .unwrap_or_default()),
+                None => Ok(Vec::new()),
+            },
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Whether the recorded outcome of a Z measurement of `qubit` is flipped
+    pub fn measurement_flip<R: Rng>(&self, qubit: usize, rng: &mut R) -> Result<bool, NoiseError> {
+        let channel = self.qubit_channel(qubit, |n| n.measurement, self.measurement);
+        let error = sample_on(channel, qubit, rng)?;
+        Ok(error.is_some_and(|e| e.error_type != ErrorType::Z))
+    }
+
+    /// Errors after resetting `qubit`
+    pub fn after_reset<R: Rng>(&self, qubit: usize, rng: &mut R) -> Result<Vec<Error>, NoiseError> {
+        let channel = self.qubit_channel(qubit, |n| n.reset, self.reset);
+        Ok(sample_on(channel, qubit, rng)?.into_iter().collect())
+    }
+
+    /// Errors on `qubits` idling through one time step of gates
+    pub fn idle<R: Rng>(&self, qubits: &[usize], rng: &mut R) -> Result<Vec<Error>, NoiseError> {
+        let mut errors = Vec::new();
+        for &q in qubits {
+            errors.extend(sample_on(
+                self.qubit_channel(q, |n| n.idle, self.idle),
+                q,
+                rng,
+            )?);
+        }
+        Ok(errors)
+    }
+
+    /// Errors on `qubits` idling while others are measured or reset
+    pub fn measurement_idle<R: Rng>(
+        &self,
+        qubits: &[usize],
+        rng: &mut R,
+    ) -> Result<Vec<Error>, NoiseError> {
+        let mut errors = Vec::new();
+        for &q in qubits {
+            let channel = self.qubit_channel(q, |n| n.measurement_idle, self.measurement_idle);
+            errors.extend(sample_on(channel, q, rng)?);
+        }
+        Ok(errors)
+    }
+
+    /// Noisy Z-basis measurement of `qubit`
+    /// Returns false for outcome +1 and true for outcome -1; the state
+    /// collapses to the actual outcome, which the noise may misreport.
+    pub fn measure<R: Rng>(
+        &self,
+        state: &mut StabilizerState,
+        qubit: usize,
+        rng: &mut R,
+    ) -> Result<bool, NoiseError> {
+        let z = PauliString::single(state.n_qubits(), qubit, Pauli::Z);
+        let outcome = state.measure_pauli(&z, rng);
+        Ok(outcome ^ self.measurement_flip(qubit, rng)?)
+    }
+
+    /// Noisy reset of `qubit` to |0⟩
+    pub fn reset<R: Rng>(
+        &self,
+        state: &mut StabilizerState,
+        qubit: usize,
+        rng: &mut R,
+    ) -> Result<(), NoiseError> {
+        let z = PauliString::single(state.n_qubits(), qubit, Pauli::Z);
+        if state.measure_pauli(&z, rng) {
+            state.apply_x(qubit);
+        }
+        apply_errors(state, &self.after_reset(qubit, rng)?);
+        Ok(())
+    }
+}
+
+fn sample_on<R: Rng>(
+    channel: Option<PauliChannel>,
+    qubit: usize,
+    rng: &mut R,
+) -> Result<Option<Error>, NoiseError> {
+    match channel {
+        Some(channel) => Ok(channel.sample_qubit(rng)?.map(|t| Error::new(qubit, t))),
+        None => Ok(None),
+    }
+}
+
+fn errors_on(qubits: &[usize], paulis: &[Pauli]) -> Vec<Error> {
+    qubits
+        .iter()
+        .zip(paulis)
+        .filter_map(|(&q, &p)| ErrorType::from_pauli(p).map(|t| Error::new(q, t)))
+        .collect()
+}
+
+/// Apply sampled errors to a state
+pub fn apply_errors(state: &mut StabilizerState, errors: &[Error]) {
+    for error in errors {
+        state.apply_pauli(&error.to_pauli_string(state.n_qubits()));
+    }
+}
+
+/// Run `circuit` on `state` with noise after each gate and on the qubits
+/// idle in each moment
+pub fn run_noisy<R: Rng>(
+    circuit: &Circuit,
+    model: &NoiseModel,
+    state: &mut StabilizerState,
+    rng: &mut R,
+) -> Result<(), NoiseError> {
+    for moment in circuit.moments() {
+        let mut busy = vec![false; circuit.n_qubits];
+        for gate in moment {
+            gate.apply(state);
+            apply_errors(state, &model.after_gate(gate, rng)?);
+            for q in gate.qubits() {
+                busy[q] = true;
+            }
+        }
+        let idle: Vec<usize> = (0..circuit.n_qubits).filter(|&q| !busy[q]).collect();
+        apply_errors(state, &model.idle(&idle, rng)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::prepare_logical_zero;
+    use crate::codes::get_stabilizer_code;
+    use crate::error::Syndrome;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_presets_round_trip() {
+        for name in ["noiseless", "sd6", "si1000", "uniform"] {
+            let model = NoiseModel::preset(name, 0.001).unwrap();
+            assert_eq!(model.name, name);
+            assert_eq!(NoiseModel::from_json(&model.to_json()).unwrap(), model);
+        }
+        let si = NoiseModel::si1000(0.001);
+        assert_eq!(si.measurement, Some(PauliChannel::BitFlip { p: 0.005 }));
+        assert_eq!(
+            NoiseModel::preset("sd7", 0.001),
+            Err(NoiseError::UnknownPreset("sd7".to_string()))
+        );
+        assert!(NoiseModel::preset("si1000", 0.5).is_err());
+        // Missing channels default to none
+        let partial = NoiseModel::from_json(
+            r#"{"name": "idle_only", "idle": {"type": "phase_flip", "p": 0.01}}"#,
+        )
+        .unwrap();
+        assert!(partial.two_qubit_gate.is_none());
+    }
+
+    #[test]
+    fn test_noisy_run() {
+        let code = get_stabilizer_code("steane").unwrap();
+        let circuit = prepare_logical_zero(&code).unwrap();
+        let mut rng = SmallRng::seed_from_u64(1);
+
+        // Without noise the circuit prepares the codeword
+        let mut state = StabilizerState::new(code.n_qubits);
+        run_noisy(&circuit, &NoiseModel::noiseless(), &mut state, &mut rng).unwrap();
+        assert!(code
+            .generators
+            .iter()
+            .all(|g| state.peek_pauli(g) == Some(false)));
+
+        // A certain bit flip after the gate flips the Z stabilizer of |0⟩
+        let flips = NoiseModel {
+            name: "flips".to_string(),
+            single_qubit_gate: Some(PauliChannel::BitFlip { p: 1.0 }),
+            ..NoiseModel::noiseless()
+        };
+        let mut state = StabilizerState::new(1);
+        let mut gate = Circuit::new(1);
+        gate.push(Gate::Z(0));
+        run_noisy(&gate, &flips, &mut state, &mut rng).unwrap();
+        assert!(Syndrome::from_state(&state).has_error());
+
+        // Channels built by hand are checked when sampled,
+        let invalid = NoiseModel {
+            name: "invalid".to_string(),
+            single_qubit_gate: Some(PauliChannel::BitFlip { p: 2.0 }),
+            ..NoiseModel::noiseless()
+        };
+        let mut state = StabilizerState::new(2);
+        assert!(run_noisy(&gate, &invalid, &mut state, &mut rng).is_err());
+
+        // as are invalid two-qubit tables
+        let table = NoiseModel {
+            name: "table".to_string(),
+            two_qubit_gate: Some(TwoQubitChannel::Table {
+                probabilities: [("XQ".to_string(), 0.1)].into_iter().collect(),
+            }),
+            ..NoiseModel::noiseless()
+        };
+        assert!(table.after_gate(Gate::Cnot(0, 1), &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_noisy_measurement_and_reset() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        let flip = NoiseModel {
+            name: "flip".to_string(),
+            measurement: Some(PauliChannel::BitFlip { p: 1.0 }),
+            reset: Some(PauliChannel::BitFlip { p: 1.0 }),
+            ..NoiseModel::noiseless()
+        };
+        let mut state = StabilizerState::new(2);
+        assert!(flip.measure(&mut state, 0, &mut rng).unwrap());
+        // Only the record is flipped: the qubit is still in |0⟩
+        assert!(!NoiseModel::noiseless()
+            .measure(&mut state, 0, &mut rng)
+            .unwrap());
+        // Z errors commute with the measurement
+        let dephased = NoiseModel {
+            measurement: Some(PauliChannel::PhaseFlip { p: 1.0 }),
+            ..flip.clone()
+        };
+        assert!(!dephased.measure(&mut state, 0, &mut rng).unwrap());
+
+        // Resetting |0⟩ leaves it, and the reset noise flips it to |1⟩
+        flip.reset(&mut state, 0, &mut rng).unwrap();
+        assert!(NoiseModel::noiseless()
+            .measure(&mut state, 0, &mut rng)
+            .unwrap());
+        NoiseModel::noiseless()
+            .reset(&mut state, 0, &mut rng)
+            .unwrap();
+        assert_eq!(
+            state.peek_pauli(&PauliString::from_str("ZI").unwrap()),
+            Some(false)
+        );
+    }
+}
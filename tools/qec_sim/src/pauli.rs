@@ -1,4 +1,68 @@
 #![allow(dead_code)]
+use std::fmt::Debug;
+
+/// Local dimension of the sites that Pauli strings and stabilizer states act on
+/// `Qubit` is the d = 2 case, with the `Pauli` and `Phase` enums and a binary
+/// tableau; `Qudit` carries a prime d chosen at runtime.
+pub trait Dimension: Copy + Eq + Debug {
+    /// Single-site Pauli operator
+    type Pauli: Copy + Eq + Debug;
+    /// Phase in front of a Pauli string
+    type Phase: Copy + Eq + Debug;
+    /// Stabilizer tableau entry, holding X and Z exponents and row phases;
+    /// `From<bool>` gives the exponents 0 and 1
+    type Digit: Copy + Eq + Debug + Default + From<bool>;
+
+    /// The local dimension d
+    fn dim(self) -> u32;
+
+    fn identity(self) -> Self::Pauli;
+
+    fn one(self) -> Self::Phase;
+
+    /// Product a·b of single-site Paulis as (phase, Pauli)
+    fn multiply(self, a: Self::Pauli, b: Self::Pauli) -> (Self::Phase, Self::Pauli);
+
+    fn multiply_phases(self, a: Self::Phase, b: Self::Phase) -> Self::Phase;
+
+    /// Exponent s with a·b = ω^s b·a, where ω = e^{2πi/d}
+    fn commutator(self, a: Self::Pauli, b: Self::Pauli) -> u32;
+}
+
+/// Qubits, the d = 2 specialization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Qubit;
+
+impl Dimension for Qubit {
+    type Pauli = Pauli;
+    type Phase = Phase;
+    type Digit = bool;
+
+    fn dim(self) -> u32 {
+        2
+    }
+
+    fn identity(self) -> Pauli {
+        Pauli::I
+    }
+
+    fn one(self) -> Phase {
+        Phase::Plus
+    }
+
+    fn multiply(self, a: Pauli, b: Pauli) -> (Phase, Pauli) {
+        a.multiply(b)
+    }
+
+    fn multiply_phases(self, a: Phase, b: Phase) -> Phase {
+        a.multiply(b)
+    }
+
+    fn commutator(self, a: Pauli, b: Pauli) -> u32 {
+        !a.commutes_with(b) as u32
+    }
+}
+
 /// Pauli operator types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Pauli {
@@ -18,6 +82,9 @@ pub enum Phase {
 }
 
 impl Pauli {
+    /// The four single-qubit Paulis in I, X, Y, Z order
+    pub const ALL: [Pauli; 4] = [Pauli::I, Pauli::X, Pauli::Y, Pauli::Z];
+
     /// Multiply two Pauli operators, returning (phase, result)
     /// Uses the multiplication table:
     /// I*X=X, I*Y=Y, I*Z=Z
@@ -105,71 +172,119 @@ impl Phase {
 }
 
 /// Multi-qubit Pauli string (tensor product of Pauli operators)
+/// Generic over the local dimension; `PauliString` alone means qubits.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct PauliString {
-    pub phase: Phase,
-    pub paulis: Vec<Pauli>,
+pub struct PauliString<D: Dimension = Qubit> {
+    pub dim: D,
+    pub phase: D::Phase,
+    pub paulis: Vec<D::Pauli>,
 }
 
-impl PauliString {
-    /// Create a new Pauli string with identity on all qubits
-    pub fn identity(n_qubits: usize) -> Self {
+impl<D: Dimension> PauliString<D> {
+    /// Identity on `n_qubits` sites of dimension `dim`
+    pub fn with_dim(dim: D, n_qubits: usize) -> Self {
         PauliString {
-            phase: Phase::Plus,
-            paulis: vec![Pauli::I; n_qubits],
+            dim,
+            phase: dim.one(),
+            paulis: vec![dim.identity(); n_qubits],
         }
     }
 
-    /// Create from a string like "IXYZ"
-    pub fn from_str(s: &str) -> Option<Self> {
-        let paulis: Option<Vec<Pauli>> = s.chars().map(Pauli::from_char).collect();
-        paulis.map(|p| PauliString {
-            phase: Phase::Plus,
-            paulis: p,
-        })
-    }
-
     /// Number of qubits
     pub fn n_qubits(&self) -> usize {
         self.paulis.len()
     }
 
     /// Multiply two Pauli strings
-    pub fn multiply(&self, other: &PauliString) -> PauliString {
+    pub fn multiply(&self, other: &PauliString<D>) -> PauliString<D> {
+        assert_eq!(self.dim, other.dim);
         assert_eq!(self.n_qubits(), other.n_qubits());
 
-        let mut result_phase = self.phase;
+        let dim = self.dim;
+        let mut result_phase = dim.multiply_phases(self.phase, other.phase);
         let mut result_paulis = Vec::with_capacity(self.n_qubits());
 
         for (p1, p2) in self.paulis.iter().zip(other.paulis.iter()) {
-            let (phase, pauli) = p1.multiply(*p2);
-            result_phase = result_phase.multiply(phase);
+            let (phase, pauli) = dim.multiply(*p1, *p2);
+            result_phase = dim.multiply_phases(result_phase, phase);
             result_paulis.push(pauli);
         }
 
         PauliString {
+            dim,
             phase: result_phase,
             paulis: result_paulis,
         }
     }
 
-    /// Check if two Pauli strings commute
-    pub fn commutes_with(&self, other: &PauliString) -> bool {
+    /// Exponent s of the commutator: self · other = ω^s other · self
+    pub fn symplectic_product(&self, other: &PauliString<D>) -> u32 {
         assert_eq!(self.n_qubits(), other.n_qubits());
 
-        let mut anticommute_count = 0;
-        for (p1, p2) in self.paulis.iter().zip(other.paulis.iter()) {
-            if !p1.commutes_with(*p2) {
-                anticommute_count += 1;
-            }
-        }
+        let d = self.dim.dim();
+        self.paulis
+            .iter()
+            .zip(other.paulis.iter())
+            .fold(0, |s, (p1, p2)| (s + self.dim.commutator(*p1, *p2)) % d)
+    }
 
-        // Two Pauli strings commute if they anticommute on an even number of qubits
-        anticommute_count % 2 == 0
+    /// Check if two Pauli strings commute
+    /// For qubits they commute if they anticommute on an even number of qubits
+    pub fn commutes_with(&self, other: &PauliString<D>) -> bool {
+        self.symplectic_product(other) == 0
+    }
+
+    /// Number of qubits on which the string acts non-trivially
+    pub fn weight(&self) -> usize {
+        let identity = self.dim.identity();
+        self.paulis.iter().filter(|&&p| p != identity).count()
     }
 
-    /// Convert to string representation
-    pub fn to_string(&self) -> String {
+    /// Place this string on `n_qubits` qubits starting at `offset`
+    pub fn embed(&self, n_qubits: usize, offset: usize) -> PauliString<D> {
+        let mut s = PauliString::with_dim(self.dim, n_qubits);
+        s.phase = self.phase;
+        s.paulis[offset..offset + self.n_qubits()].copy_from_slice(&self.paulis);
+        s
+    }
+}
+
+impl PauliString {
+    /// Create a new Pauli string with identity on all qubits
+    pub fn identity(n_qubits: usize) -> Self {
+        PauliString::with_dim(Qubit, n_qubits)
+    }
+
+    /// Create from a string like "IXYZ", optionally with a sign prefix
+    /// "+", "-", "+i" or "-i" as written by `Display`
+    pub fn from_str(s: &str) -> Option<Self> {
+        let (phase, rest) = [
+            ("+i", Phase::PlusI),
+            ("-i", Phase::MinusI),
+            ("+", Phase::Plus),
+            ("-", Phase::Minus),
+        ]
+        .iter()
+        .find_map(|(prefix, phase)| s.strip_prefix(prefix).map(|rest| (*phase, rest)))
+        .unwrap_or((Phase::Plus, s));
+        let paulis: Option<Vec<Pauli>> = rest.chars().map(Pauli::from_char).collect();
+        paulis.map(|p| PauliString {
+            dim: Qubit,
+            phase,
+            paulis: p,
+        })
+    }
+
+    /// Create a string with a single Pauli on one qubit
+    pub fn single(n_qubits: usize, qubit: usize, pauli: Pauli) -> Self {
+        let mut s = PauliString::identity(n_qubits);
+        s.paulis[qubit] = pauli;
+        s
+    }
+}
+
+impl std::fmt::Display for PauliString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let phase_str = match self.phase {
             Phase::Plus => "",
             Phase::Minus => "-",
@@ -178,7 +293,7 @@ impl PauliString {
         };
 
         let pauli_str: String = self.paulis.iter().map(|p| p.to_char()).collect();
-        format!("{}{}", phase_str, pauli_str)
+        write!(f, "{}{}", phase_str, pauli_str)
     }
 }
 
@@ -206,9 +321,14 @@ mod tests {
         let p2 = PauliString::from_str("YZ").unwrap();
         let result = p1.multiply(&p2);
 
-        // XY * YZ = X(YY)Z = X(I)Z = -iXZ
-        assert_eq!(result.phase, Phase::MinusI);
+        // XY * YZ = (XY)(YZ) = (iZ)(iX) = -ZX
+        assert_eq!(result.phase, Phase::Minus);
         assert_eq!(result.paulis, vec![Pauli::Z, Pauli::X]);
+
+        // Phases of both factors are kept
+        let mut p3 = PauliString::from_str("ZI").unwrap();
+        p3.phase = Phase::Minus;
+        assert_eq!(p1.multiply(&p3).phase, Phase::PlusI);
     }
 
     #[test]
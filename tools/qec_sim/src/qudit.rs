@@ -0,0 +1,693 @@
+// Qudits of prime dimension d
+//
+// The generalized Paulis are X|j⟩ = |j + 1⟩ and Z|j⟩ = ω^j |j⟩ with
+// ω = e^{2πi/d}, so ZX = ω XZ. Operators are stored in product form
+// ζ^k X^x Z^z with ζ = e^{iπ/d}: ζ² = ω, and for d = 2 ζ = i, which makes
+// qubits the d = 2 case (Y = i XZ).
+//
+// `Qudit` is the runtime-dimension `Dimension` for `PauliString` and
+// `StabilizerState`; this module holds its Pauli algebra and the tableau
+// algorithms on exponents modulo d. `Qubit` keeps the binary tableau, so
+// `Qudit::new(2)` is only the slow path for the same states.
+use crate::pauli::{Dimension, PauliString};
+use crate::stabilizer::StabilizerState;
+use rand::Rng;
+use std::fmt;
+
+/// Largest supported dimension, keeping products of exponents within u32
+pub const MAX_DIM: u32 = 1 << 14;
+
+/// Whether d is a prime, the dimensions for which Z_d is a field
+pub fn is_prime(d: u32) -> bool {
+    d >= 2
+        && (2..)
+            .take_while(|&p| p as u64 * p as u64 <= d as u64)
+            .all(|p| !d.is_multiple_of(p))
+}
+
+/// Error raised for operators, gates or dimensions the qudit types do not support
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuditError {
+    /// A dimension that is not prime
+    NotPrime(u32),
+    /// A prime dimension above `MAX_DIM`
+    TooLarge(u32),
+    /// Text that is not a qudit Pauli string
+    InvalidString(String),
+    /// An unknown gate or one applied to the wrong qudits
+    InvalidGate(String),
+    /// An operator of another dimension or length than the state
+    Mismatch {
+        expected: (u32, usize),
+        found: (u32, usize),
+    },
+    /// An operator whose eigenvalues are not d-th roots of unity
+    NotObservable(String),
+}
+
+impl fmt::Display for QuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuditError::NotPrime(d) => write!(f, "Qudit dimension {} is not prime", d),
+            QuditError::TooLarge(d) => write!(
+                f,
+                "Qudit dimension {} exceeds the maximum of {}",
+                d, MAX_DIM
+            ),
+            QuditError::InvalidString(s) => write!(f, "Invalid qudit Pauli string: {}", s),
+            QuditError::InvalidGate(g) => write!(f, "Invalid qudit gate: {}", g),
+            QuditError::Mismatch { expected, found } => write!(
+                f,
+                "Expected {} qudits of dimension {}, found {} of dimension {}",
+                expected.1, expected.0, found.1, found.0
+            ),
+            QuditError::NotObservable(op) => {
+                write!(f, "{} does not have d-th roots of unity as eigenvalues", op)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QuditError {}
+
+/// Prime local dimension d
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Qudit {
+    d: u32,
+}
+
+impl Qudit {
+    pub fn new(d: u32) -> Result<Self, QuditError> {
+        if !is_prime(d) {
+            return Err(QuditError::NotPrime(d));
+        }
+        if d > MAX_DIM {
+            return Err(QuditError::TooLarge(d));
+        }
+        Ok(Qudit { d })
+    }
+}
+
+/// Single-qudit Pauli X^x Z^z
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QuditPauli {
+    pub x: u32,
+    pub z: u32,
+}
+
+impl QuditPauli {
+    pub const I: QuditPauli = QuditPauli { x: 0, z: 0 };
+
+    pub fn is_identity(self) -> bool {
+        self.x == 0 && self.z == 0
+    }
+
+    /// Token such as "I", "X", "Z2" or "X2Z", as read by `from_token`
+    pub fn to_token(self) -> String {
+        let power = |letter: char, e: u32| match e {
+            0 => String::new(),
+            1 => letter.to_string(),
+            _ => format!("{}{}", letter, e),
+        };
+        if self.is_identity() {
+            "I".to_string()
+        } else {
+            power('X', self.x) + &power('Z', self.z)
+        }
+    }
+
+    pub fn from_token(token: &str, d: u32) -> Option<Self> {
+        if token == "I" {
+            return Some(QuditPauli::I);
+        }
+        let mut pauli = QuditPauli::I;
+        let mut rest = token;
+        for (letter, field) in [('X', 0), ('Z', 1)] {
+            let Some(tail) = rest.strip_prefix(letter) else {
+                continue;
+            };
+            let digits = tail.len() - tail.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let exponent = if digits == 0 {
+                1
+            } else {
+                tail[..digits].parse::<u32>().ok()? % d
+            };
+            if field == 0 {
+                pauli.x = exponent;
+            } else {
+                pauli.z = exponent;
+            }
+            rest = &tail[digits..];
+        }
+        rest.is_empty().then_some(pauli)
+    }
+}
+
+impl Dimension for Qudit {
+    type Pauli = QuditPauli;
+    /// Exponent of ζ = e^{iπ/d}, modulo 2d
+    type Phase = u32;
+    type Digit = u32;
+
+    fn dim(self) -> u32 {
+        self.d
+    }
+
+    fn identity(self) -> QuditPauli {
+        QuditPauli::I
+    }
+
+    fn one(self) -> u32 {
+        0
+    }
+
+    /// Uses Z^b X^c = ω^{bc} X^c Z^b
+    fn multiply(self, a: QuditPauli, b: QuditPauli) -> (u32, QuditPauli) {
+        let d = self.d;
+        let pauli = QuditPauli {
+            x: (a.x + b.x) % d,
+            z: (a.z + b.z) % d,
+        };
+        (2 * (a.z * b.x % d), pauli)
+    }
+
+    fn multiply_phases(self, a: u32, b: u32) -> u32 {
+        (a + b) % (2 * self.d)
+    }
+
+    fn commutator(self, a: QuditPauli, b: QuditPauli) -> u32 {
+        let d = self.d;
+        (a.z * b.x + (d - a.x) * b.z) % d
+    }
+}
+
+impl PauliString<Qudit> {
+    /// Parse whitespace-separated tokens such as "X Z2 I", optionally
+    /// preceded by a phase token "ζ^k" as written by `Display`
+    pub fn from_tokens(s: &str, dim: Qudit) -> Result<Self, QuditError> {
+        let d = dim.dim();
+        let invalid = || QuditError::InvalidString(s.to_string());
+        let mut phase = 0;
+        let mut paulis = Vec::new();
+        for (i, token) in s.split_whitespace().enumerate() {
+            match token.strip_prefix("ζ^") {
+                Some(exponent) if i == 0 => {
+                    phase = exponent.parse::<u32>().map_err(|_| invalid())? % (2 * d)
+                }
+                _ => paulis.push(QuditPauli::from_token(token, d).ok_or_else(invalid)?),
+            }
+        }
+        Ok(PauliString { dim, phase, paulis })
+    }
+
+    /// self^k by repeated multiplication
+    pub fn pow(&self, k: u32) -> PauliString<Qudit> {
+        (0..k).fold(
+            PauliString::with_dim(self.dim, self.n_qubits()),
+            |acc, _| acc.multiply(self),
+        )
+    }
+
+    /// Whether the eigenvalues are d-th roots of unity: an even ζ exponent,
+    /// where for qubits each XZ = -iY counts as one more factor of ζ
+    pub fn is_observable(&self) -> bool {
+        let twists: u32 = if self.dim.dim() == 2 {
+            self.paulis.iter().map(|p| p.x * p.z).sum()
+        } else {
+            0
+        };
+        (self.phase + twists).is_multiple_of(2)
+    }
+}
+
+impl fmt::Display for PauliString<Qudit> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tokens: Vec<String> = self.paulis.iter().map(|p| p.to_token()).collect();
+        if self.phase != 0 {
+            write!(f, "ζ^{} ", self.phase)?;
+        }
+        write!(f, "{}", tokens.join(" "))
+    }
+}
+
+/// Each row of the tableau stabilizes the state with eigenvalue 1.
+impl StabilizerState<Qudit> {
+    /// Row `index` of the tableau as a Pauli string
+    fn row(&self, index: usize) -> PauliString<Qudit> {
+        PauliString {
+            dim: self.dim(),
+            phase: self.get_phase(index),
+            paulis: (0..self.n_qubits())
+                .map(|q| QuditPauli {
+                    x: self.get_x(index, q),
+                    z: self.get_z(index, q),
+                })
+                .collect(),
+        }
+    }
+
+    fn set_row(&mut self, index: usize, row: &PauliString<Qudit>) {
+        for (q, p) in row.paulis.iter().enumerate() {
+            self.set_x(index, q, p.x);
+            self.set_z(index, q, p.z);
+        }
+        self.set_phase(index, row.phase);
+    }
+
+    pub fn get_all_stabilizers(&self) -> Vec<PauliString<Qudit>> {
+        (0..self.n_qubits()).map(|i| self.row(i)).collect()
+    }
+
+    /// Conjugate every row on one qudit: the Pauli X^x Z^z becomes
+    /// `f(x, z) = (x', z', extra phase)`
+    fn conjugate(&mut self, qudit: usize, f: impl Fn(u32, u32) -> (u32, u32, u32)) {
+        let d = self.dim().dim();
+        for row in 0..self.n_qubits() {
+            let (x, z, phase) = f(self.get_x(row, qudit), self.get_z(row, qudit));
+            self.set_x(row, qudit, x % d);
+            self.set_z(row, qudit, z % d);
+            self.set_phase(row, (self.get_phase(row) + phase) % (2 * d));
+        }
+    }
+
+    /// Shift X: X Z^z X† = ω^{-z} Z^z
+    pub fn apply_x(&mut self, qudit: usize) {
+        let d = self.dim().dim();
+        self.conjugate(qudit, |x, z| (x, z, 2 * (d - z)));
+    }
+
+    /// Clock Z: Z X^x Z† = ω^x X^x
+    pub fn apply_z(&mut self, qudit: usize) {
+        self.conjugate(qudit, |x, z| (x, z, 2 * x));
+    }
+
+    /// Fourier gate, the generalized Hadamard: X → Z, Z → X^{-1}
+    /// X^x Z^z maps to Z^x X^{-z} = ω^{-xz} X^{-z} Z^x.
+    pub fn apply_fourier(&mut self, qudit: usize) {
+        let d = self.dim().dim();
+        self.conjugate(qudit, |x, z| (d - z, x, 2 * (d - x * z % d)));
+    }
+
+    /// Phase gate, the generalized S: X → XZ (iXZ = Y for qubits), Z → Z
+    /// (XZ)^x = ω^{x(x-1)/2} X^x Z^x.
+    pub fn apply_phase(&mut self, qudit: usize) {
+        let d = self.dim().dim();
+        let qubit_phase = if d == 2 { 1 } else { 0 };
+        self.conjugate(qudit, |x, z| {
+            (x, z + x, qubit_phase * x + x * (x + 2 * d - 1) % (2 * d))
+        });
+    }
+
+    /// SUM, the generalized CNOT |i, j⟩ → |i, i + j⟩:
+    /// X_c → X_c X_t and Z_t → Z_c^{-1} Z_t, with no reordering of factors
+    pub fn apply_sum(&mut self, control: usize, target: usize) {
+        let d = self.dim().dim();
+        for row in 0..self.n_qubits() {
+            let (cx, cz) = (self.get_x(row, control), self.get_z(row, control));
+            let (tx, tz) = (self.get_x(row, target), self.get_z(row, target));
+            self.set_x(row, target, (tx + cx) % d);
+            self.set_z(row, control, (cz + d - tz) % d);
+        }
+    }
+
+    /// Controlled phase |i, j⟩ → ω^{ij} |i, j⟩: X_c → X_c Z_t, X_t → Z_c X_t
+    pub fn apply_cz(&mut self, qudit1: usize, qudit2: usize) {
+        let d = self.dim().dim();
+        for row in 0..self.n_qubits() {
+            let (ax, az) = (self.get_x(row, qudit1), self.get_z(row, qudit1));
+            let (bx, bz) = (self.get_x(row, qudit2), self.get_z(row, qudit2));
+            self.set_z(row, qudit1, (az + bx) % d);
+            self.set_z(row, qudit2, (bz + ax) % d);
+            // Z_2^{x_1} lands left of X_2^{x_2} and is moved past it
+            let phase = (self.get_phase(row) + 2 * (ax * bx % d)) % (2 * d);
+            self.set_phase(row, phase);
+        }
+    }
+
+    /// Apply a gate by name: "X", "Z", "F" (Fourier) or "P" (phase) on one
+    /// qudit, or "SUM" (control first) or "CZ" on two distinct qudits
+    pub fn apply_gate(&mut self, name: &str, qudits: &[usize]) -> Result<(), QuditError> {
+        let invalid = || QuditError::InvalidGate(format!("{} {:?}", name, qudits));
+        if qudits.iter().any(|&q| q >= self.n_qubits()) {
+            return Err(invalid());
+        }
+        match (name, qudits) {
+            ("X", &[q]) => self.apply_x(q),
+            ("Z", &[q]) => self.apply_z(q),
+            ("F", &[q]) => self.apply_fourier(q),
+            ("P", &[q]) => self.apply_phase(q),
+            ("SUM", &[c, t]) if c != t => self.apply_sum(c, t),
+            ("CZ", &[a, b]) if a != b => self.apply_cz(a, b),
+            _ => return Err(invalid()),
+        }
+        Ok(())
+    }
+
+    /// Product of the rows raised to `coefficients`
+    fn combine(&self, coefficients: &[u32]) -> PauliString<Qudit> {
+        (0..self.n_qubits()).zip(coefficients).fold(
+            PauliString::with_dim(self.dim(), self.n_qubits()),
+            |acc, (row, &c)| acc.multiply(&self.row(row).pow(c)),
+        )
+    }
+
+    /// Check that `op` is an observable on the qudits of this state
+    fn check_observable(&self, op: &PauliString<Qudit>) -> Result<(), QuditError> {
+        let expected = (self.dim().dim(), self.n_qubits());
+        let found = (op.dim.dim(), op.n_qubits());
+        if expected != found {
+            return Err(QuditError::Mismatch { expected, found });
+        }
+        if !op.is_observable() {
+            return Err(QuditError::NotObservable(op.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Outcome m of measuring `op` if the state determines it, where the
+    /// outcome means eigenvalue ω^m; for qubits m = 1 is the outcome -1
+    pub fn peek_pauli(&self, op: &PauliString<Qudit>) -> Result<Option<u32>, QuditError> {
+        self.check_observable(op)?;
+        let d = self.dim().dim();
+        let Some(coefficients) = solve_mod(&self.get_all_stabilizers(), op, d) else {
+            return Ok(None);
+        };
+        // product = ζ^{s - p} op stabilizes the state, so op has eigenvalue
+        // ζ^{p - s}; the rows are observables, so the exponent is even
+        let product = self.combine(&coefficients);
+        let exponent = (op.phase + 2 * d - product.phase) % (2 * d);
+        Ok(Some(exponent / 2))
+    }
+
+    /// Measure `op`, collapsing the state; returns the outcome m of the
+    /// eigenvalue ω^m, uniformly random if the state does not determine it
+    pub fn measure_pauli<R: Rng>(
+        &mut self,
+        op: &PauliString<Qudit>,
+        rng: &mut R,
+    ) -> Result<u32, QuditError> {
+        self.check_observable(op)?;
+        let d = self.dim().dim();
+        let rows = self.get_all_stabilizers();
+        let products: Vec<u32> = rows.iter().map(|r| op.symplectic_product(r)).collect();
+        let Some(pivot) = products.iter().position(|&s| s != 0) else {
+            return Ok(self
+                .peek_pauli(op)?
+                .expect("operator commuting with a full stabilizer group is in the group"));
+        };
+        // Clear the commutator of every other row with powers of the pivot
+        let inverse = mod_inverse(products[pivot], d);
+        for (i, &s) in products.iter().enumerate() {
+            if i != pivot && s != 0 {
+                let k = (d - s) * inverse % d;
+                self.set_row(i, &rows[i].multiply(&rows[pivot].pow(k)));
+            }
+        }
+        let outcome = rng.gen_range(0..d);
+        // ω^{-m} op has eigenvalue 1 after the measurement
+        let mut row = op.clone();
+        row.phase = (row.phase + 2 * (d - outcome)) % (2 * d);
+        self.set_row(pivot, &row);
+        Ok(outcome)
+    }
+}
+
+/// Multiplicative inverse modulo a prime
+fn mod_inverse(a: u32, p: u32) -> u32 {
+    (1..p).find(|&b| a * b % p == 1).expect("no inverse")
+}
+
+/// Coefficients c with Σ c_i rows_i = op on the (x | z) exponents mod d,
+/// or None if op is not in the span
+fn solve_mod(rows: &[PauliString<Qudit>], op: &PauliString<Qudit>, d: u32) -> Option<Vec<u32>> {
+    let n = op.n_qubits();
+    let m = rows.len();
+    let vector = |s: &PauliString<Qudit>| -> Vec<u32> {
+        s.paulis
+            .iter()
+            .map(|p| p.x)
+            .chain(s.paulis.iter().map(|p| p.z))
+            .collect()
+    };
+    // Augmented system: one equation per column, unknowns c_0..c_{m-1}
+    let columns: Vec<Vec<u32>> = rows.iter().map(vector).collect();
+    let target = vector(op);
+    let mut equations: Vec<Vec<u32>> = (0..2 * n)
+        .map(|col| {
+            let mut eq: Vec<u32> = columns.iter().map(|c| c[col]).collect();
+            eq.push(target[col]);
+            eq
+        })
+        .collect();
+
+    let mut pivots = Vec::new();
+    let mut r = 0;
+    for c in 0..m {
+        let Some(p) = (r..equations.len()).find(|&i| equations[i][c] != 0) else {
+            continue;
+        };
+        equations.swap(r, p);
+        let inverse = mod_inverse(equations[r][c], d);
+        for v in equations[r].iter_mut() {
+            *v = *v * inverse % d;
+        }
+        let pivot_row = equations[r].clone();
+        for (i, eq) in equations.iter_mut().enumerate() {
+            if i != r && eq[c] != 0 {
+                let factor = eq[c];
+                for (v, &p) in eq.iter_mut().zip(&pivot_row) {
+                    *v = (*v + (d - factor) * p) % d;
+                }
+            }
+        }
+        pivots.push(c);
+        r += 1;
+    }
+    if equations[r..].iter().any(|eq| eq[m] != 0) {
+        return None;
+    }
+    let mut solution = vec![0; m];
+    for (i, &c) in pivots.iter().enumerate() {
+        solution[c] = equations[i][m];
+    }
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pauli::{Pauli, Phase, Qubit};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    /// Conversions between qubit strings and d = 2 qudit strings, used to check
+    /// the general tableau against the binary one
+    impl PauliString<Qudit> {
+        /// Qubit string as the d = 2 case
+        fn from_qubit(op: &PauliString) -> Self {
+            let mut phase = match op.phase {
+                Phase::Plus => 0,
+                Phase::PlusI => 1,
+                Phase::Minus => 2,
+                Phase::MinusI => 3,
+            };
+            let paulis = op
+                .paulis
+                .iter()
+                .map(|&p| match p {
+                    Pauli::I => QuditPauli::I,
+                    Pauli::X => QuditPauli { x: 1, z: 0 },
+                    Pauli::Z => QuditPauli { x: 0, z: 1 },
+                    Pauli::Y => {
+                        phase += 1;
+                        QuditPauli { x: 1, z: 1 }
+                    }
+                })
+                .collect();
+            PauliString {
+                dim: Qudit { d: 2 },
+                phase: phase % 4,
+                paulis,
+            }
+        }
+
+        /// Qubit string of a d = 2 string, or None for other dimensions
+        fn to_qubit(&self) -> Option<PauliString> {
+            if self.dim.dim() != 2 {
+                return None;
+            }
+            // Each XZ = -iY takes one factor of i back out of the phase
+            let mut phase = self.phase;
+            let paulis = self
+                .paulis
+                .iter()
+                .map(|p| match (p.x, p.z) {
+                    (0, 0) => Pauli::I,
+                    (1, 0) => Pauli::X,
+                    (0, _) => Pauli::Z,
+                    _ => {
+                        phase += 3;
+                        Pauli::Y
+                    }
+                })
+                .collect();
+            let phase =
+                [Phase::Plus, Phase::PlusI, Phase::Minus, Phase::MinusI][phase as usize % 4];
+            Some(PauliString {
+                dim: Qubit,
+                phase,
+                paulis,
+            })
+        }
+    }
+
+    fn qutrit(s: &str) -> PauliString<Qudit> {
+        PauliString::from_tokens(s, Qudit::new(3).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_qudit_pauli_algebra() {
+        let op = qutrit;
+        // ZX = ω XZ
+        assert_eq!(op("Z").multiply(&op("X")), op("ζ^2 XZ"));
+        assert_eq!(op("X").pow(3), op("I"));
+        assert_eq!(op("X Z2").to_string(), "X Z2");
+        assert_eq!(op("X").symplectic_product(&op("Z")), 2);
+        assert!(op("X X").commutes_with(&op("Z Z2")));
+        assert!(!op("X X").commutes_with(&op("Z Z")));
+        assert_eq!(op("X Z2 I").weight(), 2);
+        assert_eq!(Qudit::new(4), Err(QuditError::NotPrime(4)));
+        assert_eq!(Qudit::new(65537), Err(QuditError::TooLarge(65537)));
+
+        // Qubits are the d = 2 case
+        for s in ["XYZ", "-iYY", "+iIZ"] {
+            let qubit = PauliString::from_str(s).unwrap();
+            let qudit = PauliString::from_qubit(&qubit);
+            assert_eq!(qudit.to_qubit().unwrap(), qubit);
+        }
+        let (a, b) = (
+            PauliString::from_str("XY").unwrap(),
+            PauliString::from_str("YZ").unwrap(),
+        );
+        let product = PauliString::from_qubit(&a).multiply(&PauliString::from_qubit(&b));
+        assert_eq!(product.to_qubit().unwrap(), a.multiply(&b));
+        assert_eq!(
+            PauliString::from_qubit(&a).commutes_with(&PauliString::from_qubit(&b)),
+            a.commutes_with(&b)
+        );
+    }
+
+    #[test]
+    fn test_qubit_gates_match_stabilizer_state() {
+        let stabilizers = [
+            PauliString::from_str("YY").unwrap(),
+            PauliString::from_str("ZZ").unwrap(),
+        ];
+        let mut qubits = StabilizerState::from_stabilizers(2, &stabilizers);
+        let mut qudits = StabilizerState::with_dim(Qudit::new(2).unwrap(), 2);
+        for (i, s) in stabilizers.iter().enumerate() {
+            qudits.set_row(i, &PauliString::from_qubit(s));
+        }
+        qubits.apply_cnot(0, 1);
+        qudits.apply_sum(0, 1);
+        qubits.apply_s(1);
+        qudits.apply_phase(1);
+        qubits.apply_h(0);
+        qudits.apply_fourier(0);
+        qubits.apply_cz(0, 1);
+        qudits.apply_cz(0, 1);
+        qubits.apply_x(1);
+        qudits.apply_x(1);
+        let converted: Vec<PauliString> = qudits
+            .get_all_stabilizers()
+            .iter()
+            .map(|r| r.to_qubit().unwrap())
+            .collect();
+        assert_eq!(converted, qubits.get_all_stabilizers());
+    }
+
+    #[test]
+    fn test_qutrit_ghz_measurements() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let op = qutrit;
+        let mut state = StabilizerState::with_dim(Qudit::new(3).unwrap(), 3);
+        state.apply_gate("F", &[0]).unwrap();
+        state.apply_gate("SUM", &[0, 1]).unwrap();
+        state.apply_gate("SUM", &[0, 2]).unwrap();
+        // (|000⟩ + |111⟩ + |222⟩)/√3
+        assert_eq!(state.peek_pauli(&op("X X X")).unwrap(), Some(0));
+        assert_eq!(state.peek_pauli(&op("Z Z2 I")).unwrap(), Some(0));
+        assert_eq!(state.peek_pauli(&op("Z I I")).unwrap(), None);
+
+        let outcome = state.measure_pauli(&op("Z I I"), &mut rng).unwrap();
+        assert_eq!(state.peek_pauli(&op("I Z I")).unwrap(), Some(outcome));
+        assert_eq!(state.peek_pauli(&op("I I Z")).unwrap(), Some(outcome));
+        assert_eq!(
+            state.peek_pauli(&op("I I Z2")).unwrap(),
+            Some(2 * outcome % 3)
+        );
+
+        // Each X shifts the clock outcome by one
+        state.apply_x(2);
+        assert_eq!(
+            state.peek_pauli(&op("I I Z")).unwrap(),
+            Some((outcome + 1) % 3)
+        );
+    }
+
+    #[test]
+    fn test_qutrit_phase_gate() {
+        let op = qutrit;
+        let mut state = StabilizerState::with_dim(Qudit::new(3).unwrap(), 1);
+        state.apply_fourier(0);
+        assert_eq!(state.peek_pauli(&op("X")).unwrap(), Some(0));
+        state.apply_phase(0);
+        // X → XZ; three applications return to X
+        assert_eq!(state.peek_pauli(&op("XZ")).unwrap(), Some(0));
+        state.apply_phase(0);
+        state.apply_phase(0);
+        assert_eq!(state.peek_pauli(&op("X")).unwrap(), Some(0));
+        // F^4 = I
+        for _ in 0..4 {
+            state.apply_fourier(0);
+        }
+        assert_eq!(state.peek_pauli(&op("X")).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn test_invalid_operators() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        let qutrits = Qudit::new(3).unwrap();
+        assert!(matches!(
+            PauliString::from_tokens("X Y", qutrits),
+            Err(QuditError::InvalidString(_))
+        ));
+        let mut state = StabilizerState::with_dim(qutrits, 2);
+        // ζ Z has eigenvalues ζ ω^m, which are not cube roots of unity
+        let odd = qutrit("ζ^1 Z I");
+        assert!(!odd.is_observable());
+        assert!(matches!(
+            state.peek_pauli(&odd),
+            Err(QuditError::NotObservable(_))
+        ));
+        assert!(state.measure_pauli(&odd, &mut rng).is_err());
+        // Y = ζ XZ is a qubit observable, XZ = -iY is not
+        let y = PauliString::from_qubit(&PauliString::from_str("Y").unwrap());
+        assert!(y.is_observable());
+        let xz = PauliString::from_tokens("XZ", Qudit::new(2).unwrap()).unwrap();
+        assert!(!xz.is_observable());
+        assert_eq!(
+            state.peek_pauli(&qutrit("Z")),
+            Err(QuditError::Mismatch {
+                expected: (3, 2),
+                found: (3, 1)
+            })
+        );
+        assert!(matches!(
+            state.apply_gate("SUM", &[1, 1]),
+            Err(QuditError::InvalidGate(_))
+        ));
+        assert!(state.apply_gate("F", &[2]).is_err());
+        assert!(state.apply_gate("H", &[0]).is_err());
+    }
+}
@@ -0,0 +1,842 @@
+#![allow(dead_code)]
+use crate::codes::{
+    bivariate_bicycle_checks, bivariate_bicycle_generators, five_qubit_code, repetition_code,
+    rotated_surface_code, steane_code, surface_plaquettes, CodeInfo, Monomial, StabilizerCode,
+};
+use crate::distance;
+use crate::layout::{Layout, Point};
+use crate::pauli::PauliString;
+use crate::symplectic;
+use crate::validation::{validate_info, CodeError};
+use std::cell::RefCell;
+
+/// Common interface of every code the simulator can load
+pub trait QuantumCode {
+    /// Registry key that rebuilds this code, e.g. "steane" or "surface:d=5"
+    fn key(&self) -> String;
+
+    /// Name, description and claimed [[n, k, d]]
+    fn info(&self) -> CodeInfo;
+
+    /// Independent stabilizer generators
+    fn generators(&self) -> Result<Vec<PauliString>, CodeError>;
+
+    /// Logical (X̄, Z̄) operators; defaults to a computed symplectic basis
+    fn logicals(&self) -> Result<(Vec<PauliString>, Vec<PauliString>), CodeError> {
+        let generators = self.generators()?;
+        Ok(symplectic::logical_basis(&generators, self.info().n_qubits))
+    }
+
+    /// Qubit coordinates for drawing, if the code has a natural geometry
+    fn layout(&self) -> Option<Layout> {
+        None
+    }
+
+    /// Build the code, compute its distance and check it against `info`
+    /// Families with an analytically known distance override this to skip
+    /// the distance search, but still check the rest of `info`.
+    fn stabilizer_code(&self) -> Result<StabilizerCode, CodeError> {
+        let info = self.info();
+        let (logical_x, logical_z) = self.logicals()?;
+        let code = StabilizerCode::new(info.n_qubits, self.generators()?, logical_x, logical_z)?;
+        validate_info(&code, &info)?;
+        Ok(code)
+    }
+}
+
+/// Parsed registry key "family:name=value,name=value"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeParams {
+    pub family: String,
+    pub values: Vec<(String, String)>,
+}
+
+impl CodeParams {
+    /// Split a key into its family name and parameters
+    pub fn parse(key: &str) -> Result<Self, CodeError> {
+        let (family, rest) = key.split_once(':').unwrap_or((key, ""));
+        let mut values = Vec::new();
+        for pair in rest.split(',').filter(|p| !p.trim().is_empty()) {
+            let (name, value) = pair.split_once('=').ok_or_else(|| {
+                CodeError::invalid_parameter(family, pair.trim(), "expected name=value")
+            })?;
+            values.push((name.trim().to_string(), value.trim().to_string()));
+        }
+        Ok(CodeParams {
+            family: family.trim().to_string(),
+            values,
+        })
+    }
+
+    /// Raw value of a parameter
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Required integer parameter
+    pub fn usize(&self, name: &str) -> Result<usize, CodeError> {
+        let value = self
+            .get(name)
+            .ok_or_else(|| self.invalid(name, "missing"))?;
+        value
+            .parse()
+            .map_err(|_| self.invalid(name, "expected a non-negative integer"))
+    }
+
+    /// Error for parameter `name` of this family
+    pub fn invalid(&self, name: &str, reason: &str) -> CodeError {
+        CodeError::invalid_parameter(self.family.as_str(), name, reason)
+    }
+}
+
+/// Builds a code of one family from its parameters
+pub type CodeFactory = Box<dyn Fn(&CodeParams) -> Result<Box<dyn QuantumCode>, CodeError>>;
+
+/// A named family of codes and the parameters it accepts
+pub struct CodeFamily {
+    pub name: String,
+    pub parameters: Vec<&'static str>,
+    /// Keys listed by `CodeRegistry::available`
+    pub examples: Vec<String>,
+    pub build: CodeFactory,
+}
+
+/// Lookup table from registry keys to codes
+/// The single source of truth for the code names accepted by the simulator.
+#[derive(Default)]
+pub struct CodeRegistry {
+    families: Vec<CodeFamily>,
+    /// Legacy names mapped to full keys, e.g. "surface_d3" -> "surface:d=3"
+    aliases: Vec<(String, String)>,
+}
+
+impl CodeRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        CodeRegistry::default()
+    }
+
+    /// Registry with the built-in code families
+    pub fn builtin() -> Self {
+        let mut registry = CodeRegistry::new();
+        registry.register(CodeFamily {
+            name: "repetition".to_string(),
+            parameters: vec!["n"],
+            examples: vec!["repetition_3".to_string()],
+            build: Box::new(|params| {
+                let n = params.usize("n")?;
+                repetition_code(n)?;
+                Ok(Box::new(Repetition { n }))
+            }),
+        });
+        registry.register(BuiltinCode::family(BuiltinCode {
+            key: "five_qubit",
+            name: "5-qubit Perfect Code",
+            description: "Smallest code correcting any single-qubit error",
+            n_qubits: 5,
+            n_logical: 1,
+            distance: 3,
+            x_distance: None,
+            z_distance: None,
+            build: five_qubit_code,
+            data_qubits: || Layout::ring(5),
+        }));
+        registry.register(BuiltinCode::family(BuiltinCode {
+            key: "steane",
+            name: "Steane Code",
+            description: "7-qubit CSS code, corrects any single error",
+            n_qubits: 7,
+            n_logical: 1,
+            distance: 3,
+            x_distance: Some(3),
+            z_distance: Some(3),
+            build: steane_code,
+            data_qubits: steane_data_qubits,
+        }));
+        registry.register(CodeFamily {
+            name: "surface".to_string(),
+            parameters: vec!["d"],
+            examples: vec!["surface_d3".to_string(), "surface:d=5".to_string()],
+            build: Box::new(|params| {
+                let d = params.usize("d")?;
+                if d < 2 {
+                    return Err(params.invalid("d", "must be at least 2"));
+                }
+                Ok(Box::new(RotatedSurface { d }))
+            }),
+        });
+        registry.register(CodeFamily {
+            name: "bb".to_string(),
+            parameters: vec!["l", "m", "a", "b"],
+            examples: vec!["bb:l=6,m=6".to_string()],
+            build: Box::new(|params| Ok(Box::new(BivariateBicycle::from_params(params)?))),
+        });
+        registry.alias("repetition_3", "repetition:n=3");
+        registry.alias("surface_d3", "surface:d=3");
+        registry
+    }
+
+    /// Add a code family; a family with the same name is replaced
+    pub fn register(&mut self, family: CodeFamily) {
+        self.families.retain(|f| f.name != family.name);
+        self.families.push(family);
+    }
+
+    /// Make `alias` resolve to `key`
+    pub fn alias(&mut self, alias: &str, key: &str) {
+        self.aliases.retain(|(a, _)| a != alias);
+        self.aliases.push((alias.to_string(), key.to_string()));
+    }
+
+    /// Whether `name` is a family name or an alias
+    pub fn is_taken(&self, name: &str) -> bool {
+        self.families.iter().any(|f| f.name == name) || self.aliases.iter().any(|(a, _)| a == name)
+    }
+
+    /// Look up a code by key or alias
+    pub fn get(&self, key: &str) -> Result<Box<dyn QuantumCode>, CodeError> {
+        let resolved = self
+            .aliases
+            .iter()
+            .find(|(a, _)| a == key)
+            .map_or(key, |(_, k)| k.as_str());
+        let params = CodeParams::parse(resolved)?;
+        let family = self
+            .families
+            .iter()
+            .find(|f| f.name == params.family)
+            .ok_or_else(|| CodeError::UnknownCode(key.to_string()))?;
+        if let Some((name, _)) = params
+            .values
+            .iter()
+            .find(|(name, _)| !family.parameters.contains(&name.as_str()))
+        {
+            return Err(params.invalid(name, "unknown parameter"));
+        }
+        (family.build)(&params)
+    }
+
+    /// Example keys of every registered family, in registration order
+    pub fn available(&self) -> Vec<String> {
+        self.families
+            .iter()
+            .flat_map(|f| f.examples.iter().cloned())
+            .collect()
+    }
+}
+
+thread_local! {
+    /// Registry behind the free functions in `codes` and the WASM bindings
+    /// Codes loaded from files are added here.
+    static REGISTRY: RefCell<CodeRegistry> = RefCell::new(CodeRegistry::builtin());
+}
+
+/// Run `f` with the shared registry
+pub fn with_registry<T>(f: impl FnOnce(&mut CodeRegistry) -> T) -> T {
+    REGISTRY.with(|registry| f(&mut registry.borrow_mut()))
+}
+
+/// Hand-written code without parameters
+#[derive(Clone, Copy)]
+struct BuiltinCode {
+    key: &'static str,
+    name: &'static str,
+    description: &'static str,
+    n_qubits: usize,
+    n_logical: usize,
+    distance: usize,
+    x_distance: Option<usize>,
+    z_distance: Option<usize>,
+    build: fn() -> Result<StabilizerCode, CodeError>,
+    /// Positions of the data qubits; ancillas go to the plaquette centers
+    data_qubits: fn() -> Vec<Point>,
+}
+
+impl BuiltinCode {
+    fn family(self) -> CodeFamily {
+        CodeFamily {
+            name: self.key.to_string(),
+            parameters: Vec::new(),
+            examples: vec![self.key.to_string()],
+            build: Box::new(move |_| Ok(Box::new(self))),
+        }
+    }
+}
+
+impl QuantumCode for BuiltinCode {
+    fn key(&self) -> String {
+        self.key.to_string()
+    }
+
+    fn info(&self) -> CodeInfo {
+        CodeInfo {
+            name: self.name.to_string(),
+            description: self.description.to_string(),
+            n_qubits: self.n_qubits,
+            n_logical: self.n_logical,
+            distance: self.distance,
+            x_distance: self.x_distance,
+            z_distance: self.z_distance,
+            distance_exact: true,
+            logical_gates: None,
+        }
+    }
+
+    fn generators(&self) -> Result<Vec<PauliString>, CodeError> {
+        (self.build)().map(|code| code.generators)
+    }
+
+    fn logicals(&self) -> Result<(Vec<PauliString>, Vec<PauliString>), CodeError> {
+        (self.build)().map(|code| (code.logical_x, code.logical_z))
+    }
+
+    fn layout(&self) -> Option<Layout> {
+        let generators = self.generators().ok()?;
+        Some(Layout::from_data_qubits((self.data_qubits)(), &generators))
+    }
+
+    fn stabilizer_code(&self) -> Result<StabilizerCode, CodeError> {
+        let code = (self.build)()?;
+        validate_info(&code, &self.info())?;
+        Ok(code)
+    }
+}
+
+/// Steane code drawn as a triangle of three plaquettes around qubit 6
+fn steane_data_qubits() -> Vec<Point> {
+    let h = 3f64.sqrt() / 2.0;
+    [
+        (0.0, 2.0 * h),
+        (2.0, 2.0 * h),
+        (1.0, 2.0 * h),
+        (1.0, 0.0),
+        (0.5, h),
+        (1.5, h),
+        (1.0, 4.0 * h / 3.0),
+    ]
+    .iter()
+    .map(|&(x, y)| Point::new(x, y))
+    .collect()
+}
+
+/// n-qubit bit-flip repetition code, key "repetition:n=N"
+struct Repetition {
+    n: usize,
+}
+
+impl QuantumCode for Repetition {
+    fn key(&self) -> String {
+        format!("repetition:n={}", self.n)
+    }
+
+    fn info(&self) -> CodeInfo {
+        CodeInfo {
+            name: format!("{}-qubit Repetition Code", self.n),
+            description: format!(
+                "Bit-flip code, corrects up to {} X error(s)",
+                (self.n - 1) / 2
+            ),
+            n_qubits: self.n,
+            n_logical: 1,
+            distance: 1,
+            x_distance: Some(self.n),
+            z_distance: Some(1),
+            distance_exact: true,
+            logical_gates: None,
+        }
+    }
+
+    fn generators(&self) -> Result<Vec<PauliString>, CodeError> {
+        repetition_code(self.n).map(|code| code.generators)
+    }
+
+    fn logicals(&self) -> Result<(Vec<PauliString>, Vec<PauliString>), CodeError> {
+        repetition_code(self.n).map(|code| (code.logical_x, code.logical_z))
+    }
+
+    /// Ancillas sit below the line, between the two qubits they compare
+    fn layout(&self) -> Option<Layout> {
+        let ancillas = (0..self.n - 1)
+            .map(|i| Point::new(i as f64 + 0.5, 0.5))
+            .collect();
+        let generators = self.generators().ok()?;
+        Some(Layout::with_ancillas(
+            Layout::line(self.n),
+            ancillas,
+            &generators,
+        ))
+    }
+
+    fn stabilizer_code(&self) -> Result<StabilizerCode, CodeError> {
+        let code = repetition_code(self.n)?;
+        validate_info(&code, &self.info())?;
+        Ok(code)
+    }
+}
+
+/// Distance-d rotated surface code, key "surface:d=D"
+struct RotatedSurface {
+    d: usize,
+}
+
+impl QuantumCode for RotatedSurface {
+    fn key(&self) -> String {
+        format!("surface:d={}", self.d)
+    }
+
+    fn info(&self) -> CodeInfo {
+        let d = self.d;
+        CodeInfo {
+            name: format!("Surface Code (d={})", d),
+            description: format!("{}-qubit rotated surface code on {}x{} grid", d * d, d, d),
+            n_qubits: d * d,
+            n_logical: 1,
+            distance: d,
+            x_distance: Some(d),
+            z_distance: Some(d),
+            distance_exact: true,
+            logical_gates: None,
+        }
+    }
+
+    fn generators(&self) -> Result<Vec<PauliString>, CodeError> {
+        rotated_surface_code(self.d).map(|code| code.generators)
+    }
+
+    fn logicals(&self) -> Result<(Vec<PauliString>, Vec<PauliString>), CodeError> {
+        rotated_surface_code(self.d).map(|code| (code.logical_x, code.logical_z))
+    }
+
+    /// Data qubits on the grid, ancillas at the face centers
+    fn layout(&self) -> Option<Layout> {
+        let ancillas = surface_plaquettes(self.d)
+            .iter()
+            .map(|p| Point::new(p.face.1 as f64 + 0.5, p.face.0 as f64 + 0.5))
+            .collect();
+        let generators = self.generators().ok()?;
+        Some(Layout::with_ancillas(
+            Layout::grid(self.d, self.d),
+            ancillas,
+            &generators,
+        ))
+    }
+
+    fn stabilizer_code(&self) -> Result<StabilizerCode, CodeError> {
+        let code = rotated_surface_code(self.d)?;
+        validate_info(&code, &self.info())?;
+        Ok(code)
+    }
+}
+
+/// Bivariate bicycle codes with a known distance, (l, m, A, B, d)
+/// From Bravyi et al., "High-threshold and low-overhead fault-tolerant
+/// quantum memory" (2024)
+const KNOWN_BIVARIATE_BICYCLE: &[(usize, usize, &str, &str, usize)] = &[
+    (6, 6, "x3+y+y2", "y3+x+x2", 6),
+    (15, 3, "x9+y+y2", "1+x2+x7", 10),
+    (9, 6, "x3+y+y2", "y3+x+x2", 10),
+    (12, 6, "x3+y+y2", "y3+x+x2", 12),
+    (12, 12, "x3+y2+y7", "y3+x+x2", 18),
+];
+
+/// Bivariate bicycle code, key "bb:l=L,m=M" with polynomials
+/// "a=x3+y+y2,b=y3+x+x2", which may be left out for the tori in
+/// `KNOWN_BIVARIATE_BICYCLE`
+struct BivariateBicycle {
+    l: usize,
+    m: usize,
+    a: Vec<Monomial>,
+    b: Vec<Monomial>,
+    generators: Vec<PauliString>,
+    distance: usize,
+    x_distance: Option<usize>,
+    z_distance: Option<usize>,
+    /// False when the distances are random upper bounds
+    distance_exact: bool,
+}
+
+impl BivariateBicycle {
+    /// Parse l, m and the polynomials; the distance comes from the table of
+    /// known codes or, failing that, from `distance::compute_distance`, which
+    /// only gives an upper bound for all but the smallest tori
+    fn from_params(params: &CodeParams) -> Result<Self, CodeError> {
+        let l = params.usize("l")?;
+        let m = params.usize("m")?;
+        if l == 0 || m == 0 {
+            return Err(params.invalid("l", "l and m must be positive"));
+        }
+        let defaults = default_polynomials(l, m);
+        let polynomial = |name: &str, default: Option<&str>| {
+            let text = params
+                .get(name)
+                .or(default)
+                .ok_or_else(|| params.invalid(name, "no default for this l and m, give a and b"))?;
+            parse_polynomial(text, l, m).map_err(|reason| params.invalid(name, reason))
+        };
+        let a = polynomial("a", defaults.map(|(a, _)| a))?;
+        let b = polynomial("b", defaults.map(|(_, b)| b))?;
+
+        let generators = bivariate_bicycle_generators(l, m, &a, &b);
+        let mut code = BivariateBicycle {
+            l,
+            m,
+            a,
+            b,
+            generators,
+            distance: 0,
+            x_distance: None,
+            z_distance: None,
+            distance_exact: true,
+        };
+
+        let known_distance = KNOWN_BIVARIATE_BICYCLE
+            .iter()
+            .find_map(|&(kl, km, ka, kb, d)| {
+                let same = (kl, km) == (l, m)
+                    && parse_polynomial(ka, l, m).as_ref() == Ok(&code.a)
+                    && parse_polynomial(kb, l, m).as_ref() == Ok(&code.b);
+                same.then_some(d)
+            });
+        match known_distance {
+            Some(d) => {
+                code.distance = d;
+                code.x_distance = Some(d);
+                code.z_distance = Some(d);
+            }
+            None => {
+                let report = distance::compute_distance(&code.unverified()?);
+                code.distance = report.distance;
+                code.x_distance = report.x_distance;
+                code.z_distance = report.z_distance;
+                code.distance_exact = report.exact;
+            }
+        }
+        Ok(code)
+    }
+
+    fn n_qubits(&self) -> usize {
+        2 * self.l * self.m
+    }
+
+    /// Code with computed logicals, before the distance is known
+    fn unverified(&self) -> Result<StabilizerCode, CodeError> {
+        let (logical_x, logical_z) = self.logicals()?;
+        StabilizerCode::with_distance(
+            self.n_qubits(),
+            self.generators.clone(),
+            logical_x,
+            logical_z,
+            self.distance,
+        )
+    }
+}
+
+impl QuantumCode for BivariateBicycle {
+    fn key(&self) -> String {
+        let mut key = format!("bb:l={},m={}", self.l, self.m);
+        let is_default = default_polynomials(self.l, self.m).is_some_and(|(a, b)| {
+            parse_polynomial(a, self.l, self.m).as_ref() == Ok(&self.a)
+                && parse_polynomial(b, self.l, self.m).as_ref() == Ok(&self.b)
+        });
+        if !is_default {
+            key += &format!(
+                ",a={},b={}",
+                format_polynomial(&self.a),
+                format_polynomial(&self.b)
+            );
+        }
+        key
+    }
+
+    fn info(&self) -> CodeInfo {
+        let n = self.n_qubits();
+        let k = n - self.generators.len();
+        let bound = if self.distance_exact { "" } else { "≤" };
+        CodeInfo {
+            name: format!(
+                "Bivariate Bicycle Code [[{},{},{}{}]]",
+                n, k, bound, self.distance
+            ),
+            description: format!(
+                "Bivariate bicycle code on a {}x{} torus with A = {}, B = {}",
+                self.l,
+                self.m,
+                format_polynomial(&self.a),
+                format_polynomial(&self.b)
+            ),
+            n_qubits: n,
+            n_logical: k,
+            distance: self.distance,
+            x_distance: self.x_distance,
+            z_distance: self.z_distance,
+            distance_exact: self.distance_exact,
+            logical_gates: None,
+        }
+    }
+
+    fn generators(&self) -> Result<Vec<PauliString>, CodeError> {
+        Ok(self.generators.clone())
+    }
+
+    /// Unrolled torus as in the Tanner graph drawings of the paper: on a
+    /// 2l x 2m grid, left qubits sit on (even, even) sites, right qubits on
+    /// (odd, odd), X checks on (even, odd) and Z checks on (odd, even).
+    /// The checks are not local in the plane, so there are no plaquettes.
+    fn layout(&self) -> Option<Layout> {
+        let (l, m) = (self.l, self.m);
+        let block = l * m;
+        let site = |index: usize, row_offset: usize, col_offset: usize| {
+            let (i, j) = (index / m, index % m);
+            Point::new((2 * j + col_offset) as f64, (2 * i + row_offset) as f64)
+        };
+        let data_qubits = (0..block)
+            .map(|q| site(q, 0, 0))
+            .chain((0..block).map(|q| site(q, 1, 1)))
+            .collect();
+        let checks = bivariate_bicycle_checks(l, m, &self.a, &self.b);
+        let ancilla_qubits = symplectic::independent_indices(&checks)
+            .into_iter()
+            .map(|c| {
+                if c < block {
+                    site(c, 0, 1)
+                } else {
+                    site(c - block, 1, 0)
+                }
+            })
+            .collect();
+        Some(Layout {
+            data_qubits,
+            ancilla_qubits,
+            plaquettes: Vec::new(),
+        })
+    }
+
+    fn stabilizer_code(&self) -> Result<StabilizerCode, CodeError> {
+        let code = self.unverified()?;
+        validate_info(&code, &self.info())?;
+        Ok(code)
+    }
+}
+
+/// Polynomials of the known code on the l x m torus, if there is one
+fn default_polynomials(l: usize, m: usize) -> Option<(&'static str, &'static str)> {
+    KNOWN_BIVARIATE_BICYCLE
+        .iter()
+        .find(|&&(kl, km, ..)| (kl, km) == (l, m))
+        .map(|&(_, _, a, b, _)| (a, b))
+}
+
+/// Parse "x3+y+y2" or "1+x2y" into monomials reduced mod (l, m), sorted
+/// Monomials that coincide after the reduction would cancel over GF(2), so
+/// they are rejected rather than silently giving a different code.
+pub fn parse_polynomial(text: &str, l: usize, m: usize) -> Result<Vec<Monomial>, &'static str> {
+    const SYNTAX: &str = "expected a sum of monomials like x3+y+y2";
+    let mut monomials = Vec::new();
+    for term in text.split('+').map(str::trim) {
+        if term == "1" {
+            monomials.push((0, 0));
+            continue;
+        }
+        let mut exponents = [0usize; 2];
+        let mut seen = [false; 2];
+        let mut rest = term;
+        while let Some(variable) = rest.chars().next() {
+            let axis = match variable {
+                'x' => 0,
+                'y' => 1,
+                _ => return Err(SYNTAX),
+            };
+            if seen[axis] {
+                return Err(SYNTAX);
+            }
+            seen[axis] = true;
+            rest = &rest[1..];
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            exponents[axis] = if digits == 0 {
+                1
+            } else {
+                rest[..digits].parse().map_err(|_| SYNTAX)?
+            };
+            rest = &rest[digits..];
+        }
+        if !seen[0] && !seen[1] {
+            return Err(SYNTAX);
+        }
+        monomials.push((exponents[0] % l, exponents[1] % m));
+    }
+    monomials.sort_unstable();
+    if monomials.windows(2).any(|w| w[0] == w[1]) {
+        return Err("monomials coincide after reducing exponents mod l and m");
+    }
+    Ok(monomials)
+}
+
+/// Inverse of `parse_polynomial`
+pub fn format_polynomial(monomials: &[Monomial]) -> String {
+    let power = |variable: char, exponent: usize| match exponent {
+        0 => String::new(),
+        1 => variable.to_string(),
+        e => format!("{}{}", variable, e),
+    };
+    monomials
+        .iter()
+        .map(|&(a, b)| match (a, b) {
+            (0, 0) => "1".to_string(),
+            _ => power('x', a) + &power('y', b),
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        let params = CodeParams::parse("bb:l=12,m=6").unwrap();
+        assert_eq!(params.family, "bb");
+        assert_eq!(params.usize("l"), Ok(12));
+        assert_eq!(params.usize("m"), Ok(6));
+        assert_eq!(params.get("a"), None);
+        assert_eq!(CodeParams::parse("steane").unwrap().values, vec![]);
+
+        assert_eq!(
+            parse_polynomial("x3+y+y2", 12, 6),
+            Ok(vec![(0, 1), (0, 2), (3, 0)])
+        );
+        assert_eq!(parse_polynomial("1+x2y7", 3, 6), Ok(vec![(0, 0), (2, 1)]));
+        assert!(parse_polynomial("x+z", 3, 3).is_err());
+        // x3 = 1 on a torus with l = 3
+        assert!(parse_polynomial("1+x3+y", 3, 3).is_err());
+        assert_eq!(format_polynomial(&[(0, 0), (2, 1), (1, 0)]), "1+x2y+x");
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        let registry = CodeRegistry::builtin();
+        assert_eq!(registry.get("surface_d3").unwrap().key(), "surface:d=3");
+        assert_eq!(registry.get("repetition_3").unwrap().info().n_qubits, 3);
+        assert!(matches!(
+            registry.get("toric"),
+            Err(CodeError::UnknownCode(_))
+        ));
+        assert!(matches!(
+            registry.get("surface:d=5,p=2"),
+            Err(CodeError::InvalidParameter { .. })
+        ));
+        assert!(matches!(
+            registry.get("surface:d=x"),
+            Err(CodeError::InvalidParameter { .. })
+        ));
+        assert!(matches!(
+            registry.get("surface"),
+            Err(CodeError::InvalidParameter { .. })
+        ));
+    }
+
+    #[test]
+    fn test_surface_family() {
+        let registry = CodeRegistry::builtin();
+        let surface = registry.get("surface:d=5").unwrap();
+        let code = surface.stabilizer_code().unwrap();
+        assert_eq!(code.n_qubits, 25);
+        assert_eq!(code.n_logical(), 1);
+        assert_eq!(surface.layout().unwrap().n_qubits(), 25);
+
+        // The analytic distance agrees with the exhaustive search
+        let report = distance::compute_distance(&code);
+        assert!(report.exact);
+        assert_eq!(report.distance, 5);
+    }
+
+    #[test]
+    fn test_info_distances_match_search() {
+        let registry = CodeRegistry::builtin();
+        let keys = registry
+            .available()
+            .into_iter()
+            .chain(["repetition:n=5".to_string(), "surface:d=4".to_string()]);
+        for key in keys {
+            let entry = registry.get(&key).unwrap();
+            let info = entry.info();
+            if info.n_qubits > 25 {
+                continue;
+            }
+            let report = distance::compute_distance(&entry.stabilizer_code().unwrap());
+            assert!(report.exact, "{}", key);
+            assert_eq!(info.distance, report.distance, "{}", key);
+            assert_eq!(info.x_distance, report.x_distance, "{}", key);
+            assert_eq!(info.z_distance, report.z_distance, "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_bivariate_bicycle_family() {
+        let registry = CodeRegistry::builtin();
+        let small = registry.get("bb:l=6,m=6").unwrap();
+        let code = small.stabilizer_code().unwrap();
+        assert_eq!((code.n_qubits, code.n_logical()), (72, 12));
+        assert_eq!(small.key(), "bb:l=6,m=6");
+
+        let gross = registry.get("bb:l=12,m=6").unwrap();
+        let info = gross.info();
+        assert_eq!(
+            (info.n_qubits, info.n_logical, info.distance),
+            (144, 12, 12)
+        );
+        assert_eq!(info.name, "Bivariate Bicycle Code [[144,12,12]]");
+
+        let custom = registry.get("bb:l=3,m=3,a=x+y,b=1+x").unwrap();
+        assert_eq!(custom.key(), "bb:l=3,m=3,a=y+x,b=1+x");
+        assert!(custom.stabilizer_code().is_ok());
+        assert!(custom.info().distance_exact);
+        assert!(gross.info().distance_exact);
+
+        // A distance from the randomized search is reported as a bound
+        let mut bound =
+            BivariateBicycle::from_params(&CodeParams::parse("bb:l=6,m=6").unwrap()).unwrap();
+        bound.distance_exact = false;
+        let info = bound.info();
+        assert!(!info.distance_exact);
+        assert_eq!(info.name, "Bivariate Bicycle Code [[72,12,≤6]]");
+
+        // No known code on a 3x3 torus, and the default a = x3+y+y2 would
+        // reduce to 1+y+y2 there
+        assert!(matches!(
+            registry.get("bb:l=3,m=3"),
+            Err(CodeError::InvalidParameter { .. })
+        ));
+        assert!(registry.get("bb:l=3,m=3,a=x3+x6,b=1+x").is_err());
+    }
+
+    #[test]
+    fn test_layouts() {
+        let registry = CodeRegistry::builtin();
+        for key in registry.available() {
+            let entry = registry.get(&key).unwrap();
+            let layout = entry.layout().unwrap();
+            let generators = entry.generators().unwrap();
+            assert_eq!(layout.n_qubits(), entry.info().n_qubits, "{}", key);
+            assert_eq!(layout.ancilla_qubits.len(), generators.len(), "{}", key);
+        }
+
+        // Rotated surface code: 4 bulk squares and 4 boundary triangles
+        let surface = registry.get("surface_d3").unwrap().layout().unwrap();
+        assert_eq!(surface.plaquettes.len(), 8);
+        let triangles = surface
+            .plaquettes
+            .iter()
+            .filter(|p| p.vertices.len() == 3)
+            .count();
+        assert_eq!(triangles, 4);
+        assert_eq!(surface.ancilla_qubits[0], Point::new(0.5, -0.5));
+
+        // Non-local checks have no plaquettes
+        let bb = registry.get("bb:l=6,m=6").unwrap().layout().unwrap();
+        assert!(bb.plaquettes.is_empty());
+    }
+}
@@ -0,0 +1,584 @@
+#![allow(dead_code)]
+// Automated search for small codes
+//
+// Candidates are drawn from parametrized families, checked against the
+// device connectivity, and kept if no other candidate beats them on every
+// one of n (fewer is better), k and d (more is better).
+use crate::code_io::CodeDescription;
+use crate::codes::{
+    bivariate_bicycle_generators, css_operator, CodeInfo, Monomial, StabilizerCode,
+};
+use crate::distance::{self, DistanceReport};
+use crate::pauli::{Pauli, PauliString};
+use crate::symplectic;
+use rand::rngs::SmallRng;
+use rand::seq::{index, SliceRandom};
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Family of codes to draw candidates from
+/// Each family is sampled at every qubit count within the budget that it
+/// can realize.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "family", rename_all = "snake_case")]
+pub enum SearchSpace {
+    /// CSS codes with random X checks of weight `row_weight` and Z checks
+    /// drawn from a sparse basis of the X checks' dual
+    RandomCss { row_weight: usize },
+    /// Cyclic codes generated by the n shifts of one random Pauli string of
+    /// weight `seed_weight` that commutes with all of them
+    Cyclic { seed_weight: usize },
+    /// Bivariate bicycle codes on every l x m torus with n = 2lm, with
+    /// `terms` random monomials in each of A and B
+    BivariateBicycle { terms: usize },
+}
+
+/// Device coupling graph that every check must fit
+/// A check can be measured if its qubits exist on the device and form a
+/// connected subgraph, so it can be gathered by nearest-neighbor gates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Connectivity {
+    pub n_qubits: usize,
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Connectivity {
+    /// Nearest-neighbor grid with qubit `row * cols + col`
+    pub fn grid(rows: usize, cols: usize) -> Self {
+        let mut edges = Vec::new();
+        for r in 0..rows {
+            for c in 0..cols {
+                let q = r * cols + c;
+                if c + 1 < cols {
+                    edges.push((q, q + 1));
+                }
+                if r + 1 < rows {
+                    edges.push((q, q + cols));
+                }
+            }
+        }
+        Connectivity {
+            n_qubits: rows * cols,
+            edges,
+        }
+    }
+
+    /// Qubits on a ring, each coupled to its two neighbors
+    pub fn ring(n_qubits: usize) -> Self {
+        Connectivity {
+            n_qubits,
+            edges: (0..n_qubits).map(|q| (q, (q + 1) % n_qubits)).collect(),
+        }
+    }
+
+    /// Whether the support of `check` is a connected set of device qubits
+    /// when code qubit q sits on device qubit q
+    pub fn supports(&self, check: &PauliString) -> bool {
+        let support: Vec<usize> = (0..check.n_qubits())
+            .filter(|&q| check.paulis[q] != Pauli::I)
+            .collect();
+        support.iter().all(|&q| q < self.n_qubits) && self.connected(&support)
+    }
+
+    /// Device qubit of every code qubit such that each check is a connected
+    /// set of device qubits, or None if no placement was found
+    /// The identity placement is tried first, then a backtracking search
+    /// over injective placements that gives up after `PLACEMENT_BUDGET`
+    /// assignments.
+    pub fn place(&self, checks: &[PauliString]) -> Option<Vec<usize>> {
+        let n = checks.first().map_or(0, PauliString::n_qubits);
+        if n > self.n_qubits {
+            return None;
+        }
+        if checks.iter().all(|c| self.supports(c)) {
+            return Some((0..n).collect());
+        }
+        // Each check is tested once its last qubit has been placed
+        let supports: Vec<Vec<usize>> = checks
+            .iter()
+            .map(|c| (0..n).filter(|&q| c.paulis[q] != Pauli::I).collect())
+            .collect();
+        let mut completed = vec![Vec::new(); n];
+        for (i, support) in supports.iter().enumerate() {
+            if let Some(&last) = support.last() {
+                completed[last].push(i);
+            }
+        }
+        let mut placement = Vec::with_capacity(n);
+        let mut used = vec![false; self.n_qubits];
+        let mut budget = PLACEMENT_BUDGET;
+        self.extend_placement(
+            &supports,
+            &completed,
+            &mut placement,
+            &mut used,
+            &mut budget,
+        )
+        .then_some(placement)
+    }
+
+    fn extend_placement(
+        &self,
+        supports: &[Vec<usize>],
+        completed: &[Vec<usize>],
+        placement: &mut Vec<usize>,
+        used: &mut [bool],
+        budget: &mut usize,
+    ) -> bool {
+        let q = placement.len();
+        if q == completed.len() {
+            return true;
+        }
+        for v in 0..self.n_qubits {
+            if used[v] {
+                continue;
+            }
+            if *budget == 0 {
+                return false;
+            }
+            *budget -= 1;
+            placement.push(v);
+            used[v] = true;
+            let fits = completed[q].iter().all(|&i| {
+                let image: Vec<usize> = supports[i].iter().map(|&p| placement[p]).collect();
+                self.connected(&image)
+            });
+            if fits && self.extend_placement(supports, completed, placement, used, budget) {
+                return true;
+            }
+            placement.pop();
+            used[v] = false;
+        }
+        false
+    }
+
+    /// Whether `qubits` form a connected subgraph of the device
+    fn connected(&self, qubits: &[usize]) -> bool {
+        let Some(&start) = qubits.first() else {
+            return true;
+        };
+        let mut reached = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(q) = queue.pop_front() {
+            for &(a, b) in &self.edges {
+                let next = if a == q {
+                    b
+                } else if b == q {
+                    a
+                } else {
+                    continue;
+                };
+                if qubits.contains(&next) && !reached.contains(&next) {
+                    reached.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        reached.len() == qubits.len()
+    }
+}
+
+/// Assignments tried by `Connectivity::place` before a code is rejected
+const PLACEMENT_BUDGET: usize = 100_000;
+
+/// Search budget and constraints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchConfig {
+    pub spaces: Vec<SearchSpace>,
+    pub min_qubits: usize,
+    pub max_qubits: usize,
+    /// Candidates drawn per family and qubit count
+    pub samples: usize,
+    pub seed: u64,
+    #[serde(default)]
+    pub max_check_weight: Option<usize>,
+    /// Device the checks must fit under some placement of the code qubits,
+    /// found by `Connectivity::place`; codes whose placement search runs out
+    /// of budget are rejected even if a placement exists
+    #[serde(default)]
+    pub connectivity: Option<Connectivity>,
+}
+
+/// Code found by the search
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// Family and parameters the code was drawn with
+    pub origin: String,
+    pub code: StabilizerCode,
+    pub distance: DistanceReport,
+    /// Device qubit of every code qubit, when the search had a connectivity
+    pub placement: Option<Vec<usize>>,
+}
+
+impl Candidate {
+    pub fn n(&self) -> usize {
+        self.code.n_qubits
+    }
+
+    pub fn k(&self) -> usize {
+        self.code.logical_x.len()
+    }
+
+    pub fn d(&self) -> usize {
+        self.distance.distance
+    }
+
+    /// Whether this candidate is at least as good as `other` in n, k and d
+    /// and strictly better in one of them
+    pub fn dominates(&self, other: &Candidate) -> bool {
+        let (a, b) = (self.parameters(), other.parameters());
+        a.0 <= b.0 && a.1 >= b.1 && a.2 >= b.2 && a != b
+    }
+
+    /// Whether this candidate dominates `other` on distances that can be
+    /// trusted: an upper bound never beats an exact distance
+    fn outranks(&self, other: &Candidate) -> bool {
+        self.dominates(other) && (self.distance.exact || !other.distance.exact)
+    }
+
+    /// (n, k, d)
+    pub fn parameters(&self) -> (usize, usize, usize) {
+        (self.n(), self.k(), self.d())
+    }
+
+    /// Serializable description, loadable with `LoadedCode`
+    pub fn description(&self, name: &str) -> CodeDescription {
+        let info = CodeInfo {
+            name: name.to_string(),
+            description: match &self.placement {
+                Some(placement) => format!("{}, on device qubits {:?}", self.origin, placement),
+                None => self.origin.clone(),
+            },
+            n_qubits: self.n(),
+            n_logical: self.k(),
+            distance: self.d(),
+            x_distance: self.distance.x_distance,
+            z_distance: self.distance.z_distance,
+            distance_exact: self.distance.exact,
+            logical_gates: None,
+        };
+        CodeDescription::new(info, &self.code)
+    }
+}
+
+/// Candidates no other candidate outranks, sorted by (n, k, d)
+/// Of several candidates with the same parameters only the first is kept,
+/// unless a later one has an exact distance where the first had a bound.
+/// A candidate whose distance is only an upper bound neither evicts nor
+/// blocks one with an exact distance, so the two can both be on the front.
+#[derive(Debug, Clone, Default)]
+pub struct ParetoFront {
+    pub candidates: Vec<Candidate>,
+}
+
+impl ParetoFront {
+    /// Insert a candidate, returning whether it joined the front
+    pub fn insert(&mut self, candidate: Candidate) -> bool {
+        let parameters = candidate.parameters();
+        if let Some(i) = self
+            .candidates
+            .iter()
+            .position(|c| c.parameters() == parameters)
+        {
+            if candidate.distance.exact && !self.candidates[i].distance.exact {
+                self.candidates[i] = candidate;
+                return true;
+            }
+            return false;
+        }
+        if self.candidates.iter().any(|c| c.outranks(&candidate)) {
+            return false;
+        }
+        self.candidates.retain(|c| !candidate.outranks(c));
+        let at = self
+            .candidates
+            .partition_point(|c| c.parameters() < parameters);
+        self.candidates.insert(at, candidate);
+        true
+    }
+
+    /// (n, k, d) of every code on the front
+    pub fn parameters(&self) -> Vec<(usize, usize, usize)> {
+        self.candidates.iter().map(Candidate::parameters).collect()
+    }
+}
+
+/// Explore every family of the config and return the Pareto front
+pub fn search_codes(config: &SearchConfig) -> ParetoFront {
+    let mut rng = SmallRng::seed_from_u64(config.seed);
+    let mut front = ParetoFront::default();
+    for space in &config.spaces {
+        for n in config.min_qubits.max(2)..=config.max_qubits {
+            for _ in 0..config.samples {
+                let Some((origin, generators)) = sample(space, n, &mut rng) else {
+                    break;
+                };
+                if let Some(candidate) = evaluate(config, origin, n, generators) {
+                    front.insert(candidate);
+                }
+            }
+        }
+    }
+    front
+}
+
+/// Draw stabilizer generators on n qubits from a family, or None if the
+/// family has no member of that size
+fn sample<R: Rng>(
+    space: &SearchSpace,
+    n: usize,
+    rng: &mut R,
+) -> Option<(String, Vec<PauliString>)> {
+    match *space {
+        SearchSpace::RandomCss { row_weight } => {
+            if row_weight < 2 || row_weight > n {
+                return None;
+            }
+            let x_checks = rng.gen_range(1..n / 2 + 1);
+            let hx: Vec<Vec<bool>> = (0..x_checks)
+                .map(|_| {
+                    let mut row = vec![false; n];
+                    for q in index::sample(rng, n, row_weight) {
+                        row[q] = true;
+                    }
+                    row
+                })
+                .collect();
+            let hz = sparse_dual(&hx, n, rng);
+            let z_checks = rng.gen_range(1..=hz.len().max(1)).min(hz.len());
+            let support = |row: &Vec<bool>| -> Vec<usize> { (0..n).filter(|&q| row[q]).collect() };
+            let generators = hx
+                .iter()
+                .map(|row| css_operator(n, &support(row), Pauli::X))
+                .chain(
+                    hz.choose_multiple(rng, z_checks)
+                        .map(|row| css_operator(n, &support(row), Pauli::Z)),
+                )
+                .collect();
+            Some((
+                format!("random CSS, X check weight {}", row_weight),
+                generators,
+            ))
+        }
+        SearchSpace::Cyclic { seed_weight } => {
+            if seed_weight == 0 || seed_weight > n {
+                return None;
+            }
+            let mut seed = PauliString::identity(n);
+            for q in index::sample(rng, n, seed_weight) {
+                seed.paulis[q] = *[Pauli::X, Pauli::Y, Pauli::Z].choose(rng).unwrap();
+            }
+            let shifts: Vec<PauliString> = (0..n)
+                .map(|s| {
+                    let mut op = PauliString::identity(n);
+                    for q in 0..n {
+                        op.paulis[(q + s) % n] = seed.paulis[q];
+                    }
+                    op
+                })
+                .collect();
+            let origin = format!("cyclic, seed {}", seed);
+            if shifts.iter().all(|s| s.commutes_with(&seed)) {
+                Some((origin, symplectic::independent_subset(&shifts)))
+            } else {
+                // Rejected draws still count towards the sample budget
+                Some((origin, Vec::new()))
+            }
+        }
+        SearchSpace::BivariateBicycle { terms } => {
+            let tori: Vec<(usize, usize)> = (2..=n / 4)
+                .filter(|l| (n / 2).is_multiple_of(*l) && n.is_multiple_of(2))
+                .map(|l| (l, n / 2 / l))
+                .filter(|&(_, m)| m >= 2)
+                .collect();
+            let &(l, m) = tori.choose(rng)?;
+            if terms == 0 || terms > l * m {
+                return None;
+            }
+            let mut polynomial = || -> Vec<Monomial> {
+                index::sample(rng, l * m, terms)
+                    .into_iter()
+                    .map(|i| (i / m, i % m))
+                    .collect()
+            };
+            let (a, b) = (polynomial(), polynomial());
+            let origin = format!("bivariate bicycle, l={}, m={}, A={:?}, B={:?}", l, m, a, b);
+            Some((origin, bivariate_bicycle_generators(l, m, &a, &b)))
+        }
+    }
+}
+
+/// Basis of the dual of `rows`, reduced with the columns in a random order
+/// so that its vectors are sparse and vary between draws
+fn sparse_dual<R: Rng>(rows: &[Vec<bool>], n: usize, rng: &mut R) -> Vec<Vec<bool>> {
+    let mut order: Vec<usize> = (0..n).collect();
+    order.shuffle(rng);
+    let dual = symplectic::null_space(rows, n);
+    let permuted: Vec<Vec<bool>> = dual
+        .iter()
+        .map(|v| order.iter().map(|&q| v[q]).collect())
+        .collect();
+    symplectic::reduced_basis(&permuted)
+        .into_iter()
+        .map(|v| {
+            let mut row = vec![false; n];
+            for (i, &q) in order.iter().enumerate() {
+                row[q] = v[i];
+            }
+            row
+        })
+        .collect()
+}
+
+/// Check constraints, then compute k and the distance of a draw
+/// Draws encoding no logical qubit are dropped.
+fn evaluate(
+    config: &SearchConfig,
+    origin: String,
+    n: usize,
+    generators: Vec<PauliString>,
+) -> Option<Candidate> {
+    let generators = symplectic::independent_subset(&generators);
+    if generators.is_empty() || generators.len() >= n {
+        return None;
+    }
+    let too_heavy = |g: &PauliString| config.max_check_weight.is_some_and(|w| g.weight() > w);
+    if generators.iter().any(too_heavy) {
+        return None;
+    }
+    let placement = match &config.connectivity {
+        Some(connectivity) => Some(connectivity.place(&generators)?),
+        None => None,
+    };
+    let (logical_x, logical_z) = symplectic::logical_basis(&generators, n);
+    let mut code = StabilizerCode::with_distance(n, generators, logical_x, logical_z, 0).ok()?;
+    let distance = distance::compute_distance(&code);
+    code.distance = distance.distance;
+    Some(Candidate {
+        origin,
+        code,
+        distance,
+        placement,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::get_stabilizer_code;
+
+    fn candidate(name: &str) -> Candidate {
+        let code = get_stabilizer_code(name).unwrap();
+        Candidate {
+            origin: name.to_string(),
+            distance: code.distance_report(),
+            code,
+            placement: None,
+        }
+    }
+
+    #[test]
+    fn test_pareto_front() {
+        let mut front = ParetoFront::default();
+        assert!(front.insert(candidate("steane")));
+        assert!(front.insert(candidate("five_qubit")));
+        // [[7,1,3]] is dominated by [[5,1,3]]
+        assert_eq!(front.parameters(), vec![(5, 1, 3)]);
+        assert!(!front.insert(candidate("surface_d3")));
+        assert!(!front.insert(candidate("five_qubit")));
+        assert!(front.insert(candidate("repetition_3")));
+        assert_eq!(front.parameters(), vec![(3, 1, 1), (5, 1, 3)]);
+    }
+
+    #[test]
+    fn test_cyclic_search_finds_five_qubit_code() {
+        let config = SearchConfig {
+            spaces: vec![SearchSpace::Cyclic { seed_weight: 4 }],
+            min_qubits: 4,
+            max_qubits: 5,
+            samples: 400,
+            seed: 1,
+            max_check_weight: None,
+            connectivity: Some(Connectivity::ring(5)),
+        };
+        let front = search_codes(&config);
+        assert!(front.parameters().contains(&(5, 1, 3)));
+        for candidate in &front.candidates {
+            assert!(candidate.code.generators.iter().all(|g| g.weight() == 4));
+            assert!(candidate.distance.exact);
+        }
+    }
+
+    #[test]
+    fn test_random_css_and_bicycle_search() {
+        let config = SearchConfig {
+            spaces: vec![
+                SearchSpace::RandomCss { row_weight: 4 },
+                SearchSpace::BivariateBicycle { terms: 2 },
+            ],
+            min_qubits: 6,
+            max_qubits: 12,
+            samples: 20,
+            seed: 7,
+            max_check_weight: Some(6),
+            connectivity: None,
+        };
+        let front = search_codes(&config);
+        assert!(!front.candidates.is_empty());
+        for (i, a) in front.candidates.iter().enumerate() {
+            assert!(a.k() >= 1);
+            assert!(symplectic::is_css(&a.code.generators));
+            assert!(a.code.generators.iter().all(|g| g.weight() <= 6));
+            assert!(front
+                .candidates
+                .iter()
+                .skip(i + 1)
+                .all(|b| !b.dominates(a) && !a.dominates(b)));
+        }
+        let description = front.candidates[0].description("found");
+        assert_eq!(description.info.n_qubits, front.candidates[0].n());
+        assert_eq!(
+            description.generators.len(),
+            front.candidates[0].code.generators.len()
+        );
+    }
+
+    #[test]
+    fn test_connectivity() {
+        let grid = Connectivity::grid(2, 3);
+        let op = |s: &str| PauliString::from_str(s).unwrap();
+        assert!(grid.supports(&op("ZZIIZI")));
+        assert!(!grid.supports(&op("ZIZIII")));
+        assert!(!grid.supports(&op("ZZIIIIZ")));
+        assert!(Connectivity::ring(4).supports(&op("XIIX")));
+
+        // ZIZ needs code qubit 2 between 0 and 1 on a line
+        let line = Connectivity::grid(1, 3);
+        let checks = vec![op("ZIZ"), op("IZZ")];
+        assert!(!checks.iter().all(|c| line.supports(c)));
+        assert_eq!(line.place(&checks), Some(vec![0, 2, 1]));
+        // A triangle of weight-2 checks does not fit on a line
+        let triangle = vec![op("ZZI"), op("IZZ"), op("ZIZ")];
+        assert_eq!(line.place(&triangle), None);
+        assert!(Connectivity::ring(3).place(&triangle).is_some());
+        assert_eq!(line.place(&[op("ZZZZ")]), None);
+    }
+
+    #[test]
+    fn test_upper_bounds_do_not_evict_exact_distances() {
+        let mut bound = candidate("five_qubit");
+        bound.distance.distance = 4;
+        bound.distance.exact = false;
+        let mut front = ParetoFront::default();
+        assert!(front.insert(candidate("five_qubit")));
+        assert!(front.insert(bound.clone()));
+        assert_eq!(front.parameters(), vec![(5, 1, 3), (5, 1, 4)]);
+        assert!(!front.insert(candidate("steane")));
+
+        let mut front = ParetoFront::default();
+        assert!(front.insert(bound));
+        assert!(front.insert(candidate("five_qubit")));
+        assert_eq!(front.parameters(), vec![(5, 1, 3), (5, 1, 4)]);
+    }
+}
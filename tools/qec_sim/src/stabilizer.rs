@@ -1,39 +1,52 @@
 #![allow(dead_code)]
-use crate::pauli::{Pauli, PauliString, Phase};
+use crate::pauli::{Dimension, Pauli, PauliString, Phase, Qubit};
+use crate::symplectic;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 /// Stabilizer state representation using symplectic (binary) tableau
 /// This is based on the Gottesman-Knill theorem and Aaronson-Gottesman algorithm
+/// Generic over the local dimension: qubits keep the binary tableau below,
+/// while qudits store exponents modulo d (see `qudit`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StabilizerState {
+#[serde(bound(
+    serialize = "D::Digit: Serialize",
+    deserialize = "D: Default, D::Digit: Deserialize<'de>"
+))]
+pub struct StabilizerState<D: Dimension = Qubit> {
     /// Number of qubits
     n_qubits: usize,
 
+    /// Local dimension, implied by the type for qubits
+    #[serde(skip)]
+    dim: D,
+
     /// Binary tableau: (n x 2n) matrix
     /// First n columns are X part, next n columns are Z part
     /// Rows 0..n are stabilizer generators
     /// We store as a flat vector for efficiency
-    tableau: Vec<bool>,
+    tableau: Vec<D::Digit>,
 
     /// Phase vector (r in the tableau)
-    /// false = +1, true = -1
-    phases: Vec<bool>,
+    /// false = +1, true = -1; for qudits the exponent of ζ = e^{iπ/d}
+    phases: Vec<D::Digit>,
 }
 
-impl StabilizerState {
-    /// Create a new stabilizer state initialized to |0...0⟩
-    pub fn new(n_qubits: usize) -> Self {
-        let mut tableau = vec![false; n_qubits * 2 * n_qubits];
-        let phases = vec![false; n_qubits];
+impl<D: Dimension> StabilizerState<D> {
+    /// Create the state |0...0⟩ on sites of dimension `dim`, stabilized by
+    /// Z_i for each site i
+    pub fn with_dim(dim: D, n_qubits: usize) -> Self {
+        let mut tableau = vec![D::Digit::default(); n_qubits * 2 * n_qubits];
+        let phases = vec![D::Digit::default(); n_qubits];
 
-        // Initialize to |0...0⟩ state with stabilizers Z_i for each qubit i
         for i in 0..n_qubits {
             // Set Z part for qubit i in row i
-            tableau[i * 2 * n_qubits + n_qubits + i] = true;
+            tableau[i * 2 * n_qubits + n_qubits + i] = D::Digit::from(true);
         }
 
         StabilizerState {
             n_qubits,
+            dim,
             tableau,
             phases,
         }
@@ -44,53 +57,62 @@ impl StabilizerState {
         self.n_qubits
     }
 
-    /// Get X bit at position (row, col)
-    fn get_x(&self, row: usize, col: usize) -> bool {
+    /// Local dimension
+    pub fn dim(&self) -> D {
+        self.dim
+    }
+
+    /// Get X entry at position (row, col)
+    pub(crate) fn get_x(&self, row: usize, col: usize) -> D::Digit {
         self.tableau[row * 2 * self.n_qubits + col]
     }
 
-    /// Set X bit at position (row, col)
-    fn set_x(&mut self, row: usize, col: usize, val: bool) {
+    /// Set X entry at position (row, col)
+    pub(crate) fn set_x(&mut self, row: usize, col: usize, val: D::Digit) {
         self.tableau[row * 2 * self.n_qubits + col] = val;
     }
 
-    /// Get Z bit at position (row, col)
-    fn get_z(&self, row: usize, col: usize) -> bool {
+    /// Get Z entry at position (row, col)
+    pub(crate) fn get_z(&self, row: usize, col: usize) -> D::Digit {
         self.tableau[row * 2 * self.n_qubits + self.n_qubits + col]
     }
 
-    /// Set Z bit at position (row, col)
-    fn set_z(&mut self, row: usize, col: usize, val: bool) {
+    /// Set Z entry at position (row, col)
+    pub(crate) fn set_z(&mut self, row: usize, col: usize, val: D::Digit) {
         self.tableau[row * 2 * self.n_qubits + self.n_qubits + col] = val;
     }
 
     /// Get phase for row
-    fn get_phase(&self, row: usize) -> bool {
+    pub(crate) fn get_phase(&self, row: usize) -> D::Digit {
         self.phases[row]
     }
 
     /// Set phase for row
-    fn set_phase(&mut self, row: usize, val: bool) {
+    pub(crate) fn set_phase(&mut self, row: usize, val: D::Digit) {
         self.phases[row] = val;
     }
+}
+
+impl StabilizerState {
+    /// Create a new stabilizer state initialized to |0...0⟩
+    pub fn new(n_qubits: usize) -> Self {
+        StabilizerState::with_dim(Qubit, n_qubits)
+    }
 
-    /// Row addition (XOR) in the tableau
+    /// Row addition in the tableau: row `dest` becomes the product of rows
+    /// `src` and `dest` (Aaronson-Gottesman rowsum)
     fn row_add(&mut self, dest: usize, src: usize) {
-        // Update phase using the g function
-        let mut phase_update = 0;
+        // Exponent of i in the product, including both signs
+        let mut exponent = 2 * self.get_phase(dest) as i32 + 2 * self.get_phase(src) as i32;
         for q in 0..self.n_qubits {
-            let x1 = self.get_x(dest, q);
-            let z1 = self.get_z(dest, q);
-            let x2 = self.get_x(src, q);
-            let z2 = self.get_z(src, q);
-
-            phase_update += Self::g_function(x1, z1, x2, z2);
-        }
-
-        // Update phase
-        if phase_update % 4 == 2 {
-            self.set_phase(dest, !self.get_phase(dest));
+            exponent += Self::g_function(
+                self.get_x(src, q),
+                self.get_z(src, q),
+                self.get_x(dest, q),
+                self.get_z(dest, q),
+            );
         }
+        self.set_phase(dest, exponent.rem_euclid(4) == 2);
 
         // XOR the rows
         for col in 0..2 * self.n_qubits {
@@ -101,42 +123,14 @@ impl StabilizerState {
     }
 
     /// g function for phase calculation (from Aaronson-Gottesman)
+    /// Exponent of i picked up when multiplying Pauli (x1, z1) by (x2, z2)
     fn g_function(x1: bool, z1: bool, x2: bool, z2: bool) -> i32 {
-        match (x1, z1, x2, z2) {
-            (false, false, _, _) => 0,
-            (true, true, _, _) => {
-                if z2 {
-                    if x2 {
-                        1
-                    } else {
-                        -1
-                    }
-                } else {
-                    0
-                }
-            }
-            (true, false, _, _) => {
-                if z2 {
-                    if x2 {
-                        -1
-                    } else {
-                        1
-                    }
-                } else {
-                    0
-                }
-            }
-            (false, true, _, _) => {
-                if x2 {
-                    if z2 {
-                        1
-                    } else {
-                        -1
-                    }
-                } else {
-                    0
-                }
-            }
+        let (x2, z2) = (x2 as i32, z2 as i32);
+        match (x1, z1) {
+            (false, false) => 0,
+            (true, true) => z2 - x2,
+            (true, false) => z2 * (2 * x2 - 1),
+            (false, true) => x2 * (1 - 2 * z2),
         }
     }
 
@@ -187,13 +181,92 @@ impl StabilizerState {
             self.set_x(row, target, xt ^ xc);
             self.set_z(row, control, zc ^ zt);
 
-            // Update phase
-            if xc && zt && (xt != zc) {
+            // Update phase: r ^= xc zt (xt ⊕ zc ⊕ 1)
+            if xc && zt && (xt == zc) {
+                self.set_phase(row, !self.get_phase(row));
+            }
+        }
+    }
+
+    /// Apply S† gate to qubit: X -> -Y, Y -> X, Z -> Z
+    pub fn apply_sdg(&mut self, qubit: usize) {
+        for row in 0..self.n_qubits {
+            let x = self.get_x(row, qubit);
+            let z = self.get_z(row, qubit);
+            if x {
+                // X -> -Y flips the sign, Y -> X does not
+                if !z {
+                    self.set_phase(row, !self.get_phase(row));
+                }
+                self.set_z(row, qubit, !z);
+            }
+        }
+    }
+
+    /// Apply Pauli X to qubit: flips the sign of rows with Z or Y there
+    pub fn apply_x(&mut self, qubit: usize) {
+        for row in 0..self.n_qubits {
+            if self.get_z(row, qubit) {
+                self.set_phase(row, !self.get_phase(row));
+            }
+        }
+    }
+
+    /// Apply Pauli Y to qubit: flips the sign of rows with X or Z there
+    pub fn apply_y(&mut self, qubit: usize) {
+        for row in 0..self.n_qubits {
+            if self.get_x(row, qubit) != self.get_z(row, qubit) {
+                self.set_phase(row, !self.get_phase(row));
+            }
+        }
+    }
+
+    /// Apply Pauli Z to qubit: flips the sign of rows with X or Y there
+    pub fn apply_z(&mut self, qubit: usize) {
+        for row in 0..self.n_qubits {
+            if self.get_x(row, qubit) {
+                self.set_phase(row, !self.get_phase(row));
+            }
+        }
+    }
+
+    /// Apply an arbitrary Pauli string: flips the sign of every row whose
+    /// symplectic inner product with `op` is 1 (its phase is global and dropped)
+    pub fn apply_pauli(&mut self, op: &PauliString) {
+        assert_eq!(op.n_qubits(), self.n_qubits);
+
+        let bits: Vec<(bool, bool)> = op
+            .paulis
+            .iter()
+            .map(|p| match p {
+                Pauli::I => (false, false),
+                Pauli::X => (true, false),
+                Pauli::Y => (true, true),
+                Pauli::Z => (false, true),
+            })
+            .collect();
+        for row in 0..self.n_qubits {
+            let anticommutes = bits.iter().enumerate().fold(false, |acc, (q, &(x, z))| {
+                acc ^ (self.get_x(row, q) & z) ^ (self.get_z(row, q) & x)
+            });
+            if anticommutes {
                 self.set_phase(row, !self.get_phase(row));
             }
         }
     }
 
+    /// Apply SWAP gate
+    pub fn apply_swap(&mut self, qubit1: usize, qubit2: usize) {
+        for row in 0..self.n_qubits {
+            let (x1, z1) = (self.get_x(row, qubit1), self.get_z(row, qubit1));
+            let (x2, z2) = (self.get_x(row, qubit2), self.get_z(row, qubit2));
+            self.set_x(row, qubit1, x2);
+            self.set_z(row, qubit1, z2);
+            self.set_x(row, qubit2, x1);
+            self.set_z(row, qubit2, z1);
+        }
+    }
+
     /// Apply CZ (controlled-Z) gate
     pub fn apply_cz(&mut self, qubit1: usize, qubit2: usize) {
         for row in 0..self.n_qubits {
@@ -236,7 +309,11 @@ impl StabilizerState {
             Phase::Plus
         };
 
-        PauliString { phase, paulis }
+        PauliString {
+            dim: Qubit,
+            phase,
+            paulis,
+        }
     }
 
     /// Set stabilizer generator from PauliString
@@ -262,6 +339,48 @@ impl StabilizerState {
         (0..self.n_qubits).map(|i| self.get_stabilizer(i)).collect()
     }
 
+    /// Outcome of measuring a Pauli operator if the state determines it
+    /// Returns Some(false) for +1, Some(true) for -1 and None if the outcome
+    /// would be random
+    pub fn peek_pauli(&self, op: &PauliString) -> Option<bool> {
+        let rows = self.get_all_stabilizers();
+        let combo = symplectic::decompose(&rows, op)?;
+
+        let mut product = PauliString::identity(self.n_qubits);
+        for i in combo {
+            product = product.multiply(&rows[i]);
+        }
+        Some(product.phase != op.phase)
+    }
+
+    /// Measure a Hermitian Pauli operator, collapsing the state
+    /// Returns false for outcome +1 and true for outcome -1
+    pub fn measure_pauli<R: Rng>(&mut self, op: &PauliString, rng: &mut R) -> bool {
+        assert_eq!(op.n_qubits(), self.n_qubits);
+
+        let anticommuting: Vec<usize> = (0..self.n_qubits)
+            .filter(|&i| !self.get_stabilizer(i).commutes_with(op))
+            .collect();
+
+        match anticommuting.split_first() {
+            None => self
+                .peek_pauli(op)
+                .expect("operator commuting with a full stabilizer group is in the group"),
+            Some((&pivot, rest)) => {
+                for &row in rest {
+                    self.row_add(row, pivot);
+                }
+                let outcome: bool = rng.gen();
+                let mut new_row = op.clone();
+                if outcome {
+                    new_row.phase = new_row.phase.negate();
+                }
+                self.set_stabilizer(pivot, &new_row);
+                outcome
+            }
+        }
+    }
+
     /// Create stabilizer state from a set of stabilizer generators
     pub fn from_stabilizers(n_qubits: usize, stabilizers: &[PauliString]) -> Self {
         assert_eq!(stabilizers.len(), n_qubits);
@@ -317,4 +436,55 @@ mod tests {
         assert_eq!(stabs[0].to_string(), "XX");
         assert_eq!(stabs[1].to_string(), "ZZ");
     }
+
+    #[test]
+    fn test_cnot_phase() {
+        // CNOT maps Y⊗Y to -X⊗Z
+        let mut state = StabilizerState::from_stabilizers(
+            2,
+            &[
+                PauliString::from_str("YY").unwrap(),
+                PauliString::from_str("ZZ").unwrap(),
+            ],
+        );
+        state.apply_cnot(0, 1);
+        assert_eq!(state.get_stabilizer(0).to_string(), "-XZ");
+        assert_eq!(state.get_stabilizer(1).to_string(), "IZ");
+    }
+
+    #[test]
+    fn test_measure_pauli() {
+        use rand::rngs::SmallRng;
+        use rand::SeedableRng;
+
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut state = StabilizerState::new(2);
+        state.apply_h(0);
+        state.apply_cnot(0, 1);
+
+        // Bell state: XX = ZZ = +1, YY = -1
+        assert_eq!(
+            state.peek_pauli(&PauliString::from_str("XX").unwrap()),
+            Some(false)
+        );
+        assert_eq!(
+            state.peek_pauli(&PauliString::from_str("YY").unwrap()),
+            Some(true)
+        );
+        assert_eq!(
+            state.peek_pauli(&PauliString::from_str("ZI").unwrap()),
+            None
+        );
+
+        // Measuring Y on one half fixes the other half to the opposite value
+        let outcome = state.measure_pauli(&PauliString::from_str("YI").unwrap(), &mut rng);
+        assert_eq!(
+            state.peek_pauli(&PauliString::from_str("IY").unwrap()),
+            Some(!outcome)
+        );
+        assert_eq!(
+            state.measure_pauli(&PauliString::from_str("YI").unwrap(), &mut rng),
+            outcome
+        );
+    }
 }
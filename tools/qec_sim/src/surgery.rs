@@ -0,0 +1,971 @@
+// Lattice surgery on rotated surface code patches
+//
+// All patches live on one grid of data qubits simulated as a single
+// stabilizer state. Patches use the colouring of `rectangular_surface_plaquettes`
+// in grid coordinates, so they must sit on sites with row + col even; X̄ is
+// X on the left column and Z̄ is Z on the top row of a patch.
+//
+// Every measurement records outcomes the way hardware would: checks keep the
+// sign they were measured with (a Pauli frame) and each patch carries signed
+// representatives of its logical operators. A measurement that anticommutes
+// with a representative replaces it by an equivalent one that commutes,
+// multiplying in a current stabilizer, so logical values survive deformation
+// and can be read back from measurement results alone.
+use crate::codes::{css_operator, rectangular_surface_plaquettes};
+use crate::pauli::{Pauli, PauliString, Phase};
+use crate::stabilizer::StabilizerState;
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Rectangle of data qubits on the lattice grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub row: usize,
+    pub col: usize,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Region {
+    pub fn new(row: usize, col: usize, rows: usize, cols: usize) -> Self {
+        Region {
+            row,
+            col,
+            rows,
+            cols,
+        }
+    }
+
+    /// Distance-d patch with its top-left qubit at (row, col)
+    pub fn square(row: usize, col: usize, d: usize) -> Self {
+        Region::new(row, col, d, d)
+    }
+
+    fn contains(&self, r: usize, c: usize) -> bool {
+        (self.row..self.row + self.rows).contains(&r)
+            && (self.col..self.col + self.cols).contains(&c)
+    }
+
+    fn contains_region(&self, other: &Region) -> bool {
+        other.row >= self.row
+            && other.col >= self.col
+            && other.row + other.rows <= self.row + self.rows
+            && other.col + other.cols <= self.col + self.cols
+    }
+
+    fn overlaps(&self, other: &Region) -> bool {
+        self.row < other.row + other.rows
+            && other.row < self.row + self.rows
+            && self.col < other.col + other.cols
+            && other.col < self.col + self.cols
+    }
+
+    /// Smallest region containing both
+    fn union(&self, other: &Region) -> Region {
+        let row = self.row.min(other.row);
+        let col = self.col.min(other.col);
+        Region::new(
+            row,
+            col,
+            (self.row + self.rows).max(other.row + other.rows) - row,
+            (self.col + self.cols).max(other.col + other.cols) - col,
+        )
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.row..self.row + self.rows)
+            .flat_map(move |r| (self.col..self.col + self.cols).map(move |c| (r, c)))
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} at ({}, {})",
+            self.rows, self.cols, self.row, self.col
+        )
+    }
+}
+
+/// Error raised by a patch operation that the geometry does not allow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SurgeryError {
+    /// A patch index that does not exist
+    UnknownPatch(usize),
+    /// A region that leaves the grid or is thinner than two qubits
+    InvalidRegion(Region),
+    /// A region whose top-left qubit breaks the checkerboard colouring
+    OddOrigin(Region),
+    /// A region that covers qubits of another patch
+    Overlap { region: Region, patch: String },
+    /// Patches or regions that cannot be joined by the requested operation
+    Misaligned { reason: String },
+    /// An operation on a patch that is currently merged
+    Merged(String),
+    /// An operator that should have a definite value but is random, which
+    /// means the tracked stabilizers no longer describe the state
+    Indeterminate(String),
+}
+
+impl fmt::Display for SurgeryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SurgeryError::UnknownPatch(index) => write!(f, "Unknown patch {}", index),
+            SurgeryError::InvalidRegion(region) => {
+                write!(f, "Region {} does not fit on the grid", region)
+            }
+            SurgeryError::OddOrigin(region) => {
+                write!(
+                    f,
+                    "Region {} must start on a site with row + col even",
+                    region
+                )
+            }
+            SurgeryError::Overlap { region, patch } => {
+                write!(f, "Region {} overlaps patch {}", region, patch)
+            }
+            SurgeryError::Misaligned { reason } => write!(f, "{}", reason),
+            SurgeryError::Merged(name) => write!(f, "Patch {} is merged", name),
+            SurgeryError::Indeterminate(operator) => {
+                write!(f, "{} has no definite value", operator)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SurgeryError {}
+
+/// Surface code patch holding one logical qubit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Patch {
+    pub name: String,
+    pub region: Region,
+    /// Signed representative of X̄
+    pub logical_x: PauliString,
+    /// Signed representative of Z̄
+    pub logical_z: PauliString,
+}
+
+/// Stabilizer group of the lattice at one step of an operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub label: String,
+    /// Signed generators, with the signs they were measured with
+    pub stabilizers: Vec<PauliString>,
+}
+
+/// Record of a code deformation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deformation {
+    pub stages: Vec<Stage>,
+    /// Logical measurement outcome (true for -1), if the operation measured one
+    pub outcome: Option<bool>,
+}
+
+/// Outcomes of a logical CNOT by lattice surgery
+#[derive(Debug, Clone, PartialEq)]
+pub struct CnotRecord {
+    /// Z̄_control Z̄_ancilla merge and split
+    pub zz: Deformation,
+    /// X̄_ancilla X̄_target merge and split
+    pub xx: Deformation,
+    /// Final Z̄ measurement of the ancilla
+    pub ancilla_z: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Merge {
+    a: usize,
+    b: usize,
+    /// Seam qubits between the two patches
+    seam: Region,
+    /// Basis the seam is prepared and measured in
+    basis: Pauli,
+}
+
+/// Grid of data qubits carrying surface code patches
+pub struct Lattice {
+    pub rows: usize,
+    pub cols: usize,
+    pub patches: Vec<Patch>,
+    merges: Vec<Merge>,
+    state: StabilizerState,
+    rng: SmallRng,
+}
+
+impl Lattice {
+    /// Grid with every qubit in |0⟩ and seeded measurement randomness
+    pub fn new(rows: usize, cols: usize, seed: u64) -> Self {
+        Lattice {
+            rows,
+            cols,
+            patches: Vec::new(),
+            merges: Vec::new(),
+            state: StabilizerState::new(rows * cols),
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    fn n_qubits(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    fn qubit(&self, r: usize, c: usize) -> usize {
+        r * self.cols + c
+    }
+
+    /// Stabilizer generators of a patch occupying `region`
+    pub fn region_generators(&self, region: &Region) -> Vec<PauliString> {
+        rectangular_surface_plaquettes(region.rows, region.cols)
+            .iter()
+            .map(|p| {
+                let support: Vec<usize> = p
+                    .support
+                    .iter()
+                    .map(|&q| {
+                        self.qubit(region.row + q / region.cols, region.col + q % region.cols)
+                    })
+                    .collect();
+                css_operator(self.n_qubits(), &support, p.pauli)
+            })
+            .collect()
+    }
+
+    /// X̄ on the left column and Z̄ on the top row of `region`
+    fn canonical_logicals(&self, region: &Region) -> (PauliString, PauliString) {
+        let left: Vec<usize> = (0..region.rows)
+            .map(|r| self.qubit(region.row + r, region.col))
+            .collect();
+        let top: Vec<usize> = (0..region.cols)
+            .map(|c| self.qubit(region.row, region.col + c))
+            .collect();
+        (
+            css_operator(self.n_qubits(), &left, Pauli::X),
+            css_operator(self.n_qubits(), &top, Pauli::Z),
+        )
+    }
+
+    /// Regions currently encoded: unmerged patches and merged pairs
+    fn code_regions(&self) -> Vec<Region> {
+        let merged = |p: usize| self.merges.iter().any(|m| m.a == p || m.b == p);
+        let mut regions: Vec<Region> = (0..self.patches.len())
+            .filter(|&p| !merged(p))
+            .map(|p| self.patches[p].region)
+            .collect();
+        regions.extend(
+            self.merges
+                .iter()
+                .map(|m| self.patches[m.a].region.union(&self.patches[m.b].region)),
+        );
+        regions
+    }
+
+    /// Signed stabilizer generators of every encoded region
+    pub fn stabilizers(&self) -> Result<Vec<PauliString>, SurgeryError> {
+        self.code_regions()
+            .iter()
+            .flat_map(|region| self.region_generators(region))
+            .map(|g| self.signed(g))
+            .collect()
+    }
+
+    /// `op` with the sign it was last measured with
+    fn signed(&self, mut op: PauliString) -> Result<PauliString, SurgeryError> {
+        let outcome = self
+            .state
+            .peek_pauli(&op)
+            .ok_or_else(|| SurgeryError::Indeterminate(format!("Check {}", op)))?;
+        if outcome {
+            op.phase = op.phase.negate();
+        }
+        Ok(op)
+    }
+
+    fn snapshot(&self, label: &str, extra: &[PauliString]) -> Result<Stage, SurgeryError> {
+        let mut stabilizers = self.stabilizers()?;
+        for op in extra {
+            stabilizers.push(self.signed(op.clone())?);
+        }
+        Ok(Stage {
+            label: label.to_string(),
+            stabilizers,
+        })
+    }
+
+    /// Measure a Hermitian Pauli operator, keeping the logical
+    /// representatives of all patches valid
+    pub fn measure(&mut self, op: &PauliString) -> bool {
+        let stabilizers = self.state.get_all_stabilizers();
+        if stabilizers.iter().all(|s| s.commutes_with(op)) {
+            // Deterministic outcome: the state does not change
+            return self.state.measure_pauli(op, &mut self.rng);
+        }
+        for patch in &mut self.patches {
+            for rep in [&mut patch.logical_x, &mut patch.logical_z] {
+                if rep.commutes_with(op) {
+                    continue;
+                }
+                // A stabilizer that anticommutes with op but not with rep
+                let kind = |s: &PauliString| (!s.commutes_with(op), !s.commutes_with(rep));
+                let fix = match stabilizers.iter().find(|s| kind(s) == (true, false)) {
+                    Some(s) => s.clone(),
+                    None => {
+                        let both = stabilizers.iter().find(|s| kind(s) == (true, true));
+                        let rep_only = stabilizers.iter().find(|s| kind(s) == (false, true));
+                        match (both, rep_only) {
+                            (Some(a), Some(b)) => a.multiply(b),
+                            // Every stabilizer that anticommutes with rep also
+                            // anticommutes with op, so i op rep is a stabilizer
+                            // and op measures rep's logical up to sign, as for
+                            // Ȳ on a patch in |0̄⟩. No representative survives;
+                            // `canonicalize` starts a fresh one.
+                            _ => continue,
+                        }
+                    }
+                };
+                *rep = rep.multiply(&fix);
+            }
+        }
+        self.state.measure_pauli(op, &mut self.rng)
+    }
+
+    /// Put qubit (r, c) into the +1 eigenstate of `basis` (Z or X)
+    fn reset(&mut self, r: usize, c: usize, basis: Pauli) {
+        let q = self.qubit(r, c);
+        let op = PauliString::single(self.n_qubits(), q, basis);
+        if self.measure(&op) {
+            let flip = PauliString::single(
+                self.n_qubits(),
+                q,
+                if basis == Pauli::Z {
+                    Pauli::X
+                } else {
+                    Pauli::Z
+                },
+            );
+            self.apply_pauli(&flip);
+            // The flip is not a logical operation: representatives that see
+            // it change sign so that their values stay put
+            for patch in &mut self.patches {
+                for rep in [&mut patch.logical_x, &mut patch.logical_z] {
+                    if !rep.commutes_with(&flip) {
+                        rep.phase = rep.phase.negate();
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_pauli(&mut self, op: &PauliString) {
+        for (q, p) in op.paulis.iter().enumerate() {
+            match p {
+                Pauli::I => {}
+                Pauli::X => self.state.apply_x(q),
+                Pauli::Y => self.state.apply_y(q),
+                Pauli::Z => self.state.apply_z(q),
+            }
+        }
+    }
+
+    /// Replace a patch's representatives by the canonical ones with the signs
+    /// that make them equivalent on the current state
+    /// A representative whose logical was scrambled by a logical measurement
+    /// has no equivalent; its canonical operator starts a fresh frame with
+    /// sign +, which only relabels the post-measurement state by a Pauli
+    /// that leaves it unchanged.
+    fn canonicalize(&mut self, p: usize) {
+        let (x, z) = self.canonical_logicals(&self.patches[p].region);
+        let patch = &self.patches[p];
+        let sign = |rep: &PauliString, canonical: &PauliString| {
+            self.state.peek_pauli(&rep.multiply(canonical)) == Some(true)
+        };
+        let (flip_x, flip_z) = (sign(&patch.logical_x, &x), sign(&patch.logical_z, &z));
+        let patch = &mut self.patches[p];
+        patch.logical_x = x;
+        patch.logical_z = z;
+        if flip_x {
+            patch.logical_x.phase = Phase::Minus;
+        }
+        if flip_z {
+            patch.logical_z.phase = Phase::Minus;
+        }
+    }
+
+    fn patch(&self, p: usize) -> Result<&Patch, SurgeryError> {
+        self.patches.get(p).ok_or(SurgeryError::UnknownPatch(p))
+    }
+
+    fn check_unmerged(&self, p: usize) -> Result<(), SurgeryError> {
+        let name = &self.patch(p)?.name;
+        if self.merges.iter().any(|m| m.a == p || m.b == p) {
+            return Err(SurgeryError::Merged(name.clone()));
+        }
+        Ok(())
+    }
+
+    /// Check that `region` fits, is properly coloured and only overlaps the
+    /// patches in `allowed`
+    fn check_region(&self, region: &Region, allowed: &[usize]) -> Result<(), SurgeryError> {
+        if region.rows < 2
+            || region.cols < 2
+            || region.row + region.rows > self.rows
+            || region.col + region.cols > self.cols
+        {
+            return Err(SurgeryError::InvalidRegion(*region));
+        }
+        if (region.row + region.col) % 2 == 1 {
+            return Err(SurgeryError::OddOrigin(*region));
+        }
+        for (p, patch) in self.patches.iter().enumerate() {
+            if !allowed.contains(&p) && patch.region.overlaps(region) {
+                return Err(SurgeryError::Overlap {
+                    region: *region,
+                    patch: patch.name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a patch prepared in |0̄⟩ (`basis` Z) or |+̄⟩ (`basis` X)
+    pub fn add_patch(
+        &mut self,
+        name: &str,
+        region: Region,
+        basis: Pauli,
+    ) -> Result<usize, SurgeryError> {
+        self.check_region(&region, &[])?;
+        let (logical_x, logical_z) = self.canonical_logicals(&region);
+        self.patches.push(Patch {
+            name: name.to_string(),
+            region,
+            logical_x,
+            logical_z,
+        });
+        let p = self.patches.len() - 1;
+        self.prepare(p, basis)?;
+        Ok(p)
+    }
+
+    /// Reinitialize a patch in |0̄⟩ (`basis` Z) or |+̄⟩ (`basis` X)
+    /// Every data qubit is reset in `basis` and the checks measured once.
+    pub fn prepare(&mut self, p: usize, basis: Pauli) -> Result<(), SurgeryError> {
+        self.check_unmerged(p)?;
+        let region = self.patches[p].region;
+        for (r, c) in region.cells() {
+            self.reset(r, c, basis);
+        }
+        for g in self.region_generators(&region) {
+            self.measure(&g);
+        }
+        let (x, z) = self.canonical_logicals(&region);
+        self.patches[p].logical_x = x;
+        self.patches[p].logical_z = z;
+        Ok(())
+    }
+
+    /// Signed representative of a logical Pauli of a patch
+    pub fn logical_operator(&self, p: usize, pauli: Pauli) -> Result<PauliString, SurgeryError> {
+        let patch = self.patch(p)?;
+        Ok(match pauli {
+            Pauli::I => PauliString::identity(self.n_qubits()),
+            Pauli::X => patch.logical_x.clone(),
+            Pauli::Z => patch.logical_z.clone(),
+            Pauli::Y => {
+                // Ȳ = i X̄ Z̄
+                let mut y = patch.logical_x.multiply(&patch.logical_z);
+                y.phase = y.phase.multiply(Phase::PlusI);
+                y
+            }
+        })
+    }
+
+    /// Value of a product of logical Paulis, e.g. [(0, Z), (1, Z)], if the
+    /// state determines it (true for -1)
+    pub fn peek_logical(&self, paulis: &[(usize, Pauli)]) -> Result<Option<bool>, SurgeryError> {
+        let mut product = PauliString::identity(self.n_qubits());
+        for &(p, pauli) in paulis {
+            product = product.multiply(&self.logical_operator(p, pauli)?);
+        }
+        Ok(self.state.peek_pauli(&product))
+    }
+
+    /// Measure a logical Pauli of a patch (true for -1)
+    pub fn measure_logical(&mut self, p: usize, pauli: Pauli) -> Result<bool, SurgeryError> {
+        self.check_unmerged(p)?;
+        let op = self.logical_operator(p, pauli)?;
+        let outcome = self.measure(&op);
+        self.canonicalize(p);
+        Ok(outcome)
+    }
+
+    /// Apply a logical Pauli to a patch
+    pub fn apply_logical(&mut self, p: usize, pauli: Pauli) -> Result<(), SurgeryError> {
+        let op = self.logical_operator(p, pauli)?;
+        self.apply_pauli(&op);
+        Ok(())
+    }
+
+    /// Seam between two patches: side by side patches are joined through a
+    /// column prepared in |0⟩, which measures X̄X̄; stacked patches through a
+    /// row prepared in |+⟩, which measures Z̄Z̄
+    fn seam(&self, a: usize, b: usize) -> Result<Merge, SurgeryError> {
+        let (ra, rb) = (self.patch(a)?.region, self.patch(b)?.region);
+        let (first, second) = if (ra.row, ra.col) <= (rb.row, rb.col) {
+            (ra, rb)
+        } else {
+            (rb, ra)
+        };
+        if first.row == second.row
+            && first.rows == second.rows
+            && second.col == first.col + first.cols + 1
+        {
+            Ok(Merge {
+                a,
+                b,
+                seam: Region::new(first.row, first.col + first.cols, first.rows, 1),
+                basis: Pauli::Z,
+            })
+        } else if first.col == second.col
+            && first.cols == second.cols
+            && second.row == first.row + first.rows + 1
+        {
+            Ok(Merge {
+                a,
+                b,
+                seam: Region::new(first.row + first.rows, first.col, 1, first.cols),
+                basis: Pauli::X,
+            })
+        } else {
+            Err(SurgeryError::Misaligned {
+                reason: format!(
+                    "Patches {} and {} must be side by side or stacked with one row or column between them",
+                    self.patches[a].name, self.patches[b].name
+                ),
+            })
+        }
+    }
+
+    /// Logical Pauli whose product `measure_joint` measures for two patches:
+    /// X for side by side patches, Z for stacked ones
+    pub fn joint_basis(&self, a: usize, b: usize) -> Result<Pauli, SurgeryError> {
+        Ok(match self.seam(a, b)?.basis {
+            Pauli::Z => Pauli::X,
+            _ => Pauli::Z,
+        })
+    }
+
+    /// Merge two patches into one, measuring the product of their logicals
+    /// parallel to the shared boundary
+    pub fn merge(&mut self, a: usize, b: usize) -> Result<Deformation, SurgeryError> {
+        self.check_unmerged(a)?;
+        self.check_unmerged(b)?;
+        let merge = self.seam(a, b)?;
+        let merged = self.patches[a].region.union(&self.patches[b].region);
+        self.check_region(&merged, &[a, b])?;
+
+        let seam_ops: Vec<PauliString> = merge
+            .seam
+            .cells()
+            .map(|(r, c)| PauliString::single(self.n_qubits(), self.qubit(r, c), merge.basis))
+            .collect();
+        for (r, c) in merge.seam.cells() {
+            self.reset(r, c, merge.basis);
+        }
+        let initialized = self.snapshot("seam initialized", &seam_ops)?;
+
+        for g in self.region_generators(&merged) {
+            self.measure(&g);
+        }
+        self.merges.push(merge);
+        let logical = self.joint_basis(a, b)?;
+        // The merged code stabilizes the joint logical
+        let outcome = self
+            .peek_logical(&[(a, logical), (b, logical)])?
+            .ok_or_else(|| {
+                SurgeryError::Indeterminate(format!(
+                    "Joint logical of {} and {}",
+                    self.patches[a].name, self.patches[b].name
+                ))
+            })?;
+        Ok(Deformation {
+            stages: vec![initialized, self.snapshot("merged", &[])?],
+            outcome: Some(outcome),
+        })
+    }
+
+    /// Split a merged pair by measuring out the seam and remeasuring the
+    /// checks of both patches
+    pub fn split(&mut self, a: usize, b: usize) -> Result<Deformation, SurgeryError> {
+        let index = self
+            .merges
+            .iter()
+            .position(|m| (m.a, m.b) == (a, b) || (m.a, m.b) == (b, a))
+            .ok_or_else(|| SurgeryError::Misaligned {
+                reason: format!("Patches {} and {} are not merged", a, b),
+            })?;
+        let merge = self.merges.remove(index);
+        for (r, c) in merge.seam.cells() {
+            let op = PauliString::single(self.n_qubits(), self.qubit(r, c), merge.basis);
+            self.measure(&op);
+        }
+        for p in [a, b] {
+            for g in self.region_generators(&self.patches[p].region) {
+                self.measure(&g);
+            }
+        }
+
+        // The merge scrambled the logicals conjugate to the measured product
+        // one by one, but not their product, which fixes the relative frame
+        let conjugate = merge.basis;
+        let tracked = self
+            .logical_operator(a, conjugate)?
+            .multiply(&self.logical_operator(b, conjugate)?);
+        self.canonicalize(a);
+        self.canonicalize(b);
+        let canonical = self
+            .logical_operator(a, conjugate)?
+            .multiply(&self.logical_operator(b, conjugate)?);
+        // The conjugate product commutes with the joint measurement
+        let flip = self
+            .state
+            .peek_pauli(&tracked.multiply(&canonical))
+            .ok_or_else(|| {
+                SurgeryError::Indeterminate(format!(
+                    "Conjugate product of {} and {}",
+                    self.patches[a].name, self.patches[b].name
+                ))
+            })?;
+        if flip {
+            let patch = &mut self.patches[b];
+            let rep = match conjugate {
+                Pauli::X => &mut patch.logical_x,
+                _ => &mut patch.logical_z,
+            };
+            rep.phase = rep.phase.negate();
+        }
+        Ok(Deformation {
+            stages: vec![self.snapshot("split", &[])?],
+            outcome: None,
+        })
+    }
+
+    /// Lattice surgery measurement of X̄_a X̄_b (side by side) or Z̄_a Z̄_b
+    /// (stacked): merge followed by split
+    pub fn measure_joint(&mut self, a: usize, b: usize) -> Result<Deformation, SurgeryError> {
+        let mut deformation = self.merge(a, b)?;
+        deformation.stages.extend(self.split(a, b)?.stages);
+        Ok(deformation)
+    }
+
+    /// Grow a patch to `region`, which must contain it and differ in one
+    /// dimension only
+    /// New columns start in |0⟩ so Z̄ stretches across them; new rows start
+    /// in |+⟩ so X̄ does.
+    pub fn extend(&mut self, p: usize, region: Region) -> Result<Deformation, SurgeryError> {
+        self.check_unmerged(p)?;
+        let old = self.patches[p].region;
+        let basis = deformation_basis(&old, &region, region.contains_region(&old))?;
+        self.check_region(&region, &[p])?;
+
+        let added: Vec<(usize, usize)> = region
+            .cells()
+            .filter(|&(r, c)| !old.contains(r, c))
+            .collect();
+        let added_ops: Vec<PauliString> = added
+            .iter()
+            .map(|&(r, c)| PauliString::single(self.n_qubits(), self.qubit(r, c), basis))
+            .collect();
+        for &(r, c) in &added {
+            self.reset(r, c, basis);
+        }
+        let initialized = self.snapshot("qubits initialized", &added_ops)?;
+
+        self.patches[p].region = region;
+        for g in self.region_generators(&region) {
+            self.measure(&g);
+        }
+        self.canonicalize(p);
+        Ok(Deformation {
+            stages: vec![initialized, self.snapshot("extended", &[])?],
+            outcome: None,
+        })
+    }
+
+    /// Shrink a patch to `region`, measuring out the qubits it drops
+    /// Dropped columns are measured in Z and dropped rows in X.
+    pub fn shrink(&mut self, p: usize, region: Region) -> Result<Deformation, SurgeryError> {
+        self.check_unmerged(p)?;
+        let old = self.patches[p].region;
+        let basis = deformation_basis(&region, &old, old.contains_region(&region))?;
+        self.check_region(&region, &[p])?;
+
+        let removed: Vec<(usize, usize)> = old
+            .cells()
+            .filter(|&(r, c)| !region.contains(r, c))
+            .collect();
+        let removed_ops: Vec<PauliString> = removed
+            .iter()
+            .map(|&(r, c)| PauliString::single(self.n_qubits(), self.qubit(r, c), basis))
+            .collect();
+        for op in &removed_ops {
+            self.measure(op);
+        }
+        self.patches[p].region = region;
+        for g in self.region_generators(&region) {
+            self.measure(&g);
+        }
+        self.canonicalize(p);
+        Ok(Deformation {
+            stages: vec![self.snapshot("shrunk", &removed_ops)?],
+            outcome: None,
+        })
+    }
+
+    /// Move a patch along a row or column by extending it over the target
+    /// region and shrinking it onto it
+    pub fn move_patch(&mut self, p: usize, region: Region) -> Result<Deformation, SurgeryError> {
+        let old = self.patch(p)?.region;
+        if (region.rows, region.cols) != (old.rows, old.cols) {
+            return Err(SurgeryError::Misaligned {
+                reason: format!(
+                    "Cannot move patch {} to {} of a different size",
+                    old, region
+                ),
+            });
+        }
+        let mut deformation = self.extend(p, old.union(&region))?;
+        deformation.stages.extend(self.shrink(p, region)?.stages);
+        Ok(deformation)
+    }
+
+    /// Logical CNOT by lattice surgery through an ancilla patch stacked with
+    /// the control and side by side with the target
+    /// Measures Z̄_c Z̄_a and X̄_a X̄_t with the ancilla in |+̄⟩, then Z̄_a, and
+    /// applies the Pauli byproducts X̄_t^(m_zz + m_a) and Z̄_c^(m_xx).
+    pub fn cnot(
+        &mut self,
+        control: usize,
+        ancilla: usize,
+        target: usize,
+    ) -> Result<CnotRecord, SurgeryError> {
+        if self.joint_basis(control, ancilla)? != Pauli::Z
+            || self.joint_basis(ancilla, target)? != Pauli::X
+        {
+            return Err(SurgeryError::Misaligned {
+                reason: "The ancilla must be stacked with the control and beside the target"
+                    .to_string(),
+            });
+        }
+        self.prepare(ancilla, Pauli::X)?;
+        let zz = self.measure_joint(control, ancilla)?;
+        let xx = self.measure_joint(ancilla, target)?;
+        let ancilla_z = self.measure_logical(ancilla, Pauli::Z)?;
+        if zz.outcome != Some(ancilla_z) {
+            self.apply_logical(target, Pauli::X)?;
+        }
+        if xx.outcome == Some(true) {
+            self.apply_logical(control, Pauli::Z)?;
+        }
+        Ok(CnotRecord { zz, xx, ancilla_z })
+    }
+}
+
+/// Basis of the qubits by which `large` exceeds `small`: Z for extra
+/// columns, X for extra rows
+fn deformation_basis(small: &Region, large: &Region, nested: bool) -> Result<Pauli, SurgeryError> {
+    let misaligned = || SurgeryError::Misaligned {
+        reason: format!(
+            "{} and {} must differ along one row or column direction",
+            small, large
+        ),
+    };
+    if !nested || small == large {
+        return Err(misaligned());
+    }
+    if small.rows == large.rows {
+        Ok(Pauli::Z)
+    } else if small.cols == large.cols {
+        Ok(Pauli::X)
+    } else {
+        Err(misaligned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_measures_joint_logical() {
+        for seed in 0..4 {
+            // Side by side patches in |+̄⟩ have X̄X̄ = +1
+            let mut lattice = Lattice::new(3, 7, seed);
+            let a = lattice
+                .add_patch("a", Region::square(0, 0, 3), Pauli::X)
+                .unwrap();
+            let b = lattice
+                .add_patch("b", Region::square(0, 4, 3), Pauli::X)
+                .unwrap();
+            let merged = lattice.merge(a, b).unwrap();
+            assert_eq!(merged.outcome, Some(false));
+            // Seam of three |0⟩ qubits, then one 3x7 patch with 20 checks
+            assert_eq!(merged.stages[0].stabilizers.len(), 8 + 8 + 3);
+            assert_eq!(merged.stages[1].stabilizers.len(), 20);
+            lattice.split(a, b).unwrap();
+            assert_eq!(lattice.stabilizers().unwrap().len(), 16);
+            assert_eq!(lattice.peek_logical(&[(a, Pauli::X)]).unwrap(), Some(false));
+            assert_eq!(lattice.peek_logical(&[(b, Pauli::X)]).unwrap(), Some(false));
+
+            // Stacked patches in |0̄⟩ and |1̄⟩ have Z̄Z̄ = -1, which survives the split
+            let mut lattice = Lattice::new(7, 3, seed);
+            let a = lattice
+                .add_patch("a", Region::square(0, 0, 3), Pauli::Z)
+                .unwrap();
+            let b = lattice
+                .add_patch("b", Region::square(4, 0, 3), Pauli::Z)
+                .unwrap();
+            lattice.apply_logical(b, Pauli::X).unwrap();
+            assert_eq!(lattice.joint_basis(a, b).unwrap(), Pauli::Z);
+            let joint = lattice.measure_joint(b, a).unwrap();
+            assert_eq!(joint.outcome, Some(true));
+            assert_eq!(lattice.peek_logical(&[(a, Pauli::Z)]).unwrap(), Some(false));
+            assert_eq!(lattice.peek_logical(&[(b, Pauli::Z)]).unwrap(), Some(true));
+        }
+    }
+
+    #[test]
+    fn test_move_preserves_logical_state() {
+        for seed in 0..4 {
+            for (basis, flip) in [(Pauli::Z, Pauli::X), (Pauli::X, Pauli::Z)] {
+                let mut lattice = Lattice::new(7, 7, seed);
+                let p = lattice
+                    .add_patch("q", Region::square(0, 0, 3), basis)
+                    .unwrap();
+                lattice.apply_logical(p, flip).unwrap();
+                let moved = lattice.move_patch(p, Region::square(0, 4, 3)).unwrap();
+                assert_eq!(moved.stages.len(), 3);
+                assert_eq!(moved.stages[1].stabilizers.len(), 20);
+                lattice.move_patch(p, Region::square(4, 4, 3)).unwrap();
+                assert_eq!(lattice.patches[p].region, Region::square(4, 4, 3));
+                assert_eq!(lattice.peek_logical(&[(p, basis)]).unwrap(), Some(true));
+                // Every signed check is a stabilizer of the state
+                for s in lattice.stabilizers().unwrap() {
+                    assert_eq!(lattice.state.peek_pauli(&s), Some(false));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lattice_surgery_cnot() {
+        // target | ancilla
+        //        | control
+        let setup = |seed: u64, control_basis: Pauli, flip: bool| {
+            let mut lattice = Lattice::new(7, 7, seed);
+            let target = lattice
+                .add_patch("target", Region::square(0, 0, 3), Pauli::Z)
+                .unwrap();
+            let ancilla = lattice
+                .add_patch("ancilla", Region::square(0, 4, 3), Pauli::X)
+                .unwrap();
+            let control = lattice
+                .add_patch("control", Region::square(4, 4, 3), control_basis)
+                .unwrap();
+            if flip {
+                lattice.apply_logical(control, Pauli::X).unwrap();
+            }
+            lattice.cnot(control, ancilla, target).unwrap();
+            (lattice, control, target)
+        };
+        for seed in 0..6 {
+            // |1⟩|0⟩ -> |1⟩|1⟩
+            let (lattice, control, target) = setup(seed, Pauli::Z, true);
+            assert_eq!(
+                lattice.peek_logical(&[(control, Pauli::Z)]).unwrap(),
+                Some(true)
+            );
+            assert_eq!(
+                lattice.peek_logical(&[(target, Pauli::Z)]).unwrap(),
+                Some(true)
+            );
+
+            // |+⟩|0⟩ -> Bell pair
+            let (lattice, control, target) = setup(seed, Pauli::X, false);
+            let zz = [(control, Pauli::Z), (target, Pauli::Z)];
+            let xx = [(control, Pauli::X), (target, Pauli::X)];
+            assert_eq!(lattice.peek_logical(&zz).unwrap(), Some(false));
+            assert_eq!(lattice.peek_logical(&xx).unwrap(), Some(false));
+            assert_eq!(lattice.peek_logical(&[(control, Pauli::Z)]).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_measure_stabilized_logical() {
+        for basis in [Pauli::Z, Pauli::X] {
+            let mut lattice = Lattice::new(3, 3, 0);
+            let a = lattice
+                .add_patch("a", Region::square(0, 0, 3), basis)
+                .unwrap();
+            let before = lattice.stabilizers().unwrap();
+            assert!(!lattice.measure_logical(a, basis).unwrap());
+            assert_eq!(lattice.peek_logical(&[(a, basis)]).unwrap(), Some(false));
+            assert_eq!(lattice.stabilizers().unwrap(), before);
+        }
+    }
+
+    #[test]
+    fn test_measure_logical_y() {
+        for seed in 0..6 {
+            for basis in [Pauli::Z, Pauli::X] {
+                // Ȳ anticommutes with the stabilized logical: a random outcome
+                // that is then repeated
+                let mut lattice = Lattice::new(3, 3, seed);
+                let a = lattice
+                    .add_patch("a", Region::square(0, 0, 3), basis)
+                    .unwrap();
+                let y = lattice.measure_logical(a, Pauli::Y).unwrap();
+                assert_eq!(lattice.peek_logical(&[(a, Pauli::Y)]).unwrap(), Some(y));
+                assert_eq!(lattice.peek_logical(&[(a, basis)]).unwrap(), None);
+                assert_eq!(lattice.measure_logical(a, Pauli::Y).unwrap(), y);
+                for s in lattice.stabilizers().unwrap() {
+                    assert_eq!(lattice.state.peek_pauli(&s), Some(false));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_geometry() {
+        let mut lattice = Lattice::new(7, 7, 0);
+        let a = lattice
+            .add_patch("a", Region::square(0, 0, 3), Pauli::Z)
+            .unwrap();
+        assert_eq!(
+            lattice.add_patch("b", Region::square(0, 1, 3), Pauli::Z),
+            Err(SurgeryError::OddOrigin(Region::square(0, 1, 3)))
+        );
+        assert!(matches!(
+            lattice.add_patch("b", Region::square(2, 2, 3), Pauli::Z),
+            Err(SurgeryError::Overlap { .. })
+        ));
+        assert_eq!(
+            lattice.add_patch("b", Region::square(6, 0, 3), Pauli::Z),
+            Err(SurgeryError::InvalidRegion(Region::square(6, 0, 3)))
+        );
+        let b = lattice
+            .add_patch("b", Region::square(4, 4, 3), Pauli::Z)
+            .unwrap();
+        assert!(matches!(
+            lattice.merge(a, b),
+            Err(SurgeryError::Misaligned { .. })
+        ));
+        assert!(matches!(
+            lattice.extend(a, Region::new(0, 0, 4, 4)),
+            Err(SurgeryError::Misaligned { .. })
+        ));
+    }
+}
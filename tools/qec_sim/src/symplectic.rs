@@ -0,0 +1,488 @@
+#![allow(dead_code)]
+use crate::pauli::{Pauli, PauliString};
+
+// Binary symplectic representation of Pauli strings and GF(2) linear algebra
+// A Pauli string on n qubits maps to a 2n bit vector (x_0..x_{n-1} | z_0..z_{n-1});
+// the phase is dropped
+
+/// Convert a Pauli string to its (x | z) bit vector
+pub fn to_bits(p: &PauliString) -> Vec<bool> {
+    let n = p.n_qubits();
+    let mut bits = vec![false; 2 * n];
+    for (q, pauli) in p.paulis.iter().enumerate() {
+        let (x, z) = match pauli {
+            Pauli::I => (false, false),
+            Pauli::X => (true, false),
+            Pauli::Y => (true, true),
+            Pauli::Z => (false, true),
+        };
+        bits[q] = x;
+        bits[n + q] = z;
+    }
+    bits
+}
+
+/// Convert an (x | z) bit vector back to a Pauli string with phase +1
+pub fn from_bits(bits: &[bool]) -> PauliString {
+    let n = bits.len() / 2;
+    let paulis = (0..n)
+        .map(|q| match (bits[q], bits[n + q]) {
+            (false, false) => Pauli::I,
+            (true, false) => Pauli::X,
+            (true, true) => Pauli::Y,
+            (false, true) => Pauli::Z,
+        })
+        .collect();
+    PauliString {
+        dim: crate::pauli::Qubit,
+        phase: crate::pauli::Phase::Plus,
+        paulis,
+    }
+}
+
+/// Row-reduced basis of the span of a set of bit vectors
+/// Each basis row remembers which input vectors it is the sum of
+struct Echelon {
+    rows: Vec<Vec<bool>>,
+    pivots: Vec<usize>,
+    combos: Vec<Vec<bool>>,
+}
+
+impl Echelon {
+    fn new(vectors: &[Vec<bool>]) -> Self {
+        let mut echelon = Echelon {
+            rows: Vec::new(),
+            pivots: Vec::new(),
+            combos: Vec::new(),
+        };
+        for (i, v) in vectors.iter().enumerate() {
+            let mut combo = vec![false; vectors.len()];
+            combo[i] = true;
+            echelon.insert(v.clone(), combo);
+        }
+        echelon
+    }
+
+    /// Reduce `v` against the basis, returning the remainder and the
+    /// combination of basis inputs that was added
+    fn reduce(&self, mut v: Vec<bool>, mut combo: Vec<bool>) -> (Vec<bool>, Vec<bool>) {
+        for ((row, &pivot), row_combo) in self.rows.iter().zip(&self.pivots).zip(&self.combos) {
+            if v[pivot] {
+                xor_into(&mut v, row);
+                xor_into(&mut combo, row_combo);
+            }
+        }
+        (v, combo)
+    }
+
+    fn insert(&mut self, v: Vec<bool>, combo: Vec<bool>) -> bool {
+        let (v, combo) = self.reduce(v, combo);
+        match v.iter().position(|&b| b) {
+            Some(pivot) => {
+                // Keep the basis fully reduced on pivot columns
+                for (row, row_combo) in self.rows.iter_mut().zip(self.combos.iter_mut()) {
+                    if row[pivot] {
+                        xor_into(row, &v);
+                        xor_into(row_combo, &combo);
+                    }
+                }
+                self.rows.push(v);
+                self.pivots.push(pivot);
+                self.combos.push(combo);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// In-place XOR of two bit vectors of equal length
+pub fn xor_into(dest: &mut [bool], src: &[bool]) {
+    for (d, s) in dest.iter_mut().zip(src) {
+        *d ^= *s;
+    }
+}
+
+/// Symplectic inner product of two (x | z) bit vectors: `true` if the
+/// corresponding Pauli strings anticommute
+pub fn symplectic_product(a: &[bool], b: &[bool]) -> bool {
+    let n = a.len() / 2;
+    (0..n).fold(false, |acc, q| acc ^ (a[q] & b[n + q]) ^ (a[n + q] & b[q]))
+}
+
+/// GF(2) rank of a set of Pauli strings
+pub fn rank(ops: &[PauliString]) -> usize {
+    let vectors: Vec<Vec<bool>> = ops.iter().map(to_bits).collect();
+    Echelon::new(&vectors).rows.len()
+}
+
+/// Check whether `target` is a product of `ops` up to phase
+pub fn is_in_span(ops: &[PauliString], target: &PauliString) -> bool {
+    decompose(ops, target).is_some()
+}
+
+/// Indices of `ops` whose product equals `target` up to phase
+pub fn decompose(ops: &[PauliString], target: &PauliString) -> Option<Vec<usize>> {
+    let vectors: Vec<Vec<bool>> = ops.iter().map(to_bits).collect();
+    let echelon = Echelon::new(&vectors);
+    let (rest, combo) = echelon.reduce(to_bits(target), vec![false; ops.len()]);
+    if rest.iter().any(|&b| b) {
+        return None;
+    }
+    Some((0..ops.len()).filter(|&i| combo[i]).collect())
+}
+
+/// Index of the first operator that is a product of the ones before it
+pub fn first_dependent(ops: &[PauliString]) -> Option<usize> {
+    let mut echelon = Echelon::new(&[]);
+    ops.iter()
+        .position(|op| !echelon.insert(to_bits(op), Vec::new()))
+}
+
+/// Indices of a maximal independent subset of `ops`, keeping the first occurrence
+pub fn independent_indices(ops: &[PauliString]) -> Vec<usize> {
+    let mut echelon = Echelon::new(&[]);
+    (0..ops.len())
+        .filter(|&i| echelon.insert(to_bits(&ops[i]), Vec::new()))
+        .collect()
+}
+
+/// Maximal independent subset of `ops`, keeping the first occurrence
+pub fn independent_subset(ops: &[PauliString]) -> Vec<PauliString> {
+    independent_indices(ops)
+        .into_iter()
+        .map(|i| ops[i].clone())
+        .collect()
+}
+
+/// Fully reduced row echelon basis of the span of a set of bit vectors
+/// Pivots are chosen in column order.
+pub fn reduced_basis(vectors: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let mut echelon = Echelon::new(&[]);
+    for v in vectors {
+        echelon.insert(v.clone(), Vec::new());
+    }
+    echelon.rows
+}
+
+/// Basis of the null space of a binary matrix given by its rows
+pub fn null_space(rows: &[Vec<bool>], n_cols: usize) -> Vec<Vec<bool>> {
+    let mut echelon = Echelon::new(&[]);
+    for row in rows {
+        echelon.insert(row.clone(), Vec::new());
+    }
+
+    let free: Vec<usize> = (0..n_cols)
+        .filter(|c| !echelon.pivots.contains(c))
+        .collect();
+    free.iter()
+        .map(|&f| {
+            let mut v = vec![false; n_cols];
+            v[f] = true;
+            // Rows are fully reduced, so each pivot variable depends only on
+            // free variables
+            for (row, &pivot) in echelon.rows.iter().zip(&echelon.pivots) {
+                v[pivot] = row[f];
+            }
+            v
+        })
+        .collect()
+}
+
+/// Solve `rows · v = rhs` over GF(2) with the ordinary dot product
+/// Returns one solution (free variables set to zero) or None if inconsistent.
+pub fn solve(rows: &[Vec<bool>], rhs: &[bool]) -> Option<Vec<bool>> {
+    let width = rows.first().map_or(0, Vec::len);
+    let mut echelon = Echelon::new(&[]);
+    for (row, &b) in rows.iter().zip(rhs) {
+        let mut augmented = row.clone();
+        augmented.push(b);
+        echelon.insert(augmented, Vec::new());
+    }
+    let mut solution = vec![false; width];
+    for (row, &pivot) in echelon.rows.iter().zip(&echelon.pivots) {
+        if pivot == width {
+            return None;
+        }
+        solution[pivot] = row[width];
+    }
+    Some(solution)
+}
+
+/// Destabilizers of independent commuting `stabilizers` that also commute
+/// with the `logicals`
+/// Destabilizer i anticommutes with stabilizer i only and all destabilizers
+/// commute with each other, completing a symplectic basis.
+pub fn destabilizers(stabilizers: &[PauliString], logicals: &[PauliString]) -> Vec<PauliString> {
+    let Some(n) = stabilizers.first().map(|s| s.n_qubits()) else {
+        return Vec::new();
+    };
+    // Swapping the x and z halves turns the symplectic product into a dot product
+    let swapped = |op: &PauliString| {
+        let bits = to_bits(op);
+        let mut out = bits[n..].to_vec();
+        out.extend_from_slice(&bits[..n]);
+        out
+    };
+    let rows: Vec<Vec<bool>> = stabilizers.iter().chain(logicals).map(swapped).collect();
+
+    let mut result: Vec<Vec<bool>> = (0..stabilizers.len())
+        .map(|i| {
+            let rhs: Vec<bool> = (0..rows.len()).map(|j| j == i).collect();
+            solve(&rows, &rhs).expect("independent operators always have a destabilizer")
+        })
+        .collect();
+
+    // Multiplying destabilizer j by stabilizer i toggles only its product with destabilizer i
+    let stabilizer_bits: Vec<Vec<bool>> = stabilizers.iter().map(to_bits).collect();
+    for j in 0..result.len() {
+        for i in 0..j {
+            if symplectic_product(&result[i], &result[j]) {
+                let (head, tail) = result.split_at_mut(j);
+                xor_into(&mut tail[0], &stabilizer_bits[i]);
+                debug_assert!(!symplectic_product(&head[i], &tail[0]));
+            }
+        }
+    }
+    result.iter().map(|bits| from_bits(bits)).collect()
+}
+
+/// Check whether every operator is X-type or Z-type (CSS form)
+pub fn is_css(ops: &[PauliString]) -> bool {
+    ops.iter().all(|op| {
+        let has_x = op.paulis.iter().any(|&p| p == Pauli::X || p == Pauli::Y);
+        let has_z = op.paulis.iter().any(|&p| p == Pauli::Z || p == Pauli::Y);
+        !(has_x && has_z)
+    })
+}
+
+/// Basis of all Pauli strings on `n_qubits` qubits commuting with every operator in `ops`
+/// For CSS inputs the basis consists of pure X-type and pure Z-type strings.
+pub fn centralizer(ops: &[PauliString], n_qubits: usize) -> Vec<PauliString> {
+    let n = n_qubits;
+    if is_css(ops) {
+        // X-type strings commute with the Z checks, Z-type strings with the X checks
+        let support = |pauli: Pauli| -> Vec<Vec<bool>> {
+            ops.iter()
+                .filter(|op| op.paulis.contains(&pauli))
+                .map(|op| op.paulis.iter().map(|&p| p == pauli).collect())
+                .collect()
+        };
+        let x_part = null_space(&support(Pauli::Z), n).into_iter().map(|x| {
+            let mut bits = x;
+            bits.resize(2 * n, false);
+            from_bits(&bits)
+        });
+        let z_part = null_space(&support(Pauli::X), n).into_iter().map(|z| {
+            let mut bits = vec![false; n];
+            bits.extend(z);
+            from_bits(&bits)
+        });
+        return x_part.chain(z_part).collect();
+    }
+
+    // v commutes with g iff (g_z | g_x) . v = 0
+    let rows: Vec<Vec<bool>> = ops
+        .iter()
+        .map(|op| {
+            let bits = to_bits(op);
+            let mut swapped = bits[n..].to_vec();
+            swapped.extend_from_slice(&bits[..n]);
+            swapped
+        })
+        .collect();
+    null_space(&rows, 2 * n)
+        .iter()
+        .map(|v| from_bits(v))
+        .collect()
+}
+
+/// Symplectic basis of logical operators for the code stabilized by `stabilizers`
+/// Returns (logical X, logical Z) with X_i Z_j anticommuting iff i == j and
+/// every other pair commuting. CSS codes get X-type logical X and Z-type logical Z.
+pub fn logical_basis(
+    stabilizers: &[PauliString],
+    n_qubits: usize,
+) -> (Vec<PauliString>, Vec<PauliString>) {
+    let (logical_x, logical_z) =
+        symplectic_basis_modulo(&centralizer(stabilizers, n_qubits), stabilizers);
+    (
+        logical_x
+            .into_iter()
+            .map(|op| reduce_weight(op, stabilizers))
+            .collect(),
+        logical_z
+            .into_iter()
+            .map(|op| reduce_weight(op, stabilizers))
+            .collect(),
+    )
+}
+
+/// Symplectic pairs spanning `ops` modulo the commuting `stabilizers`
+/// With the centralizer of a gauge group as `ops` this gives the bare
+/// logical operators of a subsystem code, and with the gauge generators
+/// themselves its gauge qubits. Pairs come out as (first, partner); for CSS
+/// inputs listing X-type strings first, the firsts are X type.
+pub fn symplectic_basis_modulo(
+    ops: &[PauliString],
+    stabilizers: &[PauliString],
+) -> (Vec<PauliString>, Vec<PauliString>) {
+    // Operators that are independent of the stabilizer group
+    let mut echelon = Echelon::new(&[]);
+    for s in stabilizers {
+        echelon.insert(to_bits(s), Vec::new());
+    }
+    let mut candidates: Vec<Vec<bool>> = ops
+        .iter()
+        .map(to_bits)
+        .filter(|v| echelon.insert(v.clone(), Vec::new()))
+        .collect();
+
+    // Symplectic Gram-Schmidt
+    let mut firsts = Vec::new();
+    let mut partners = Vec::new();
+    while !candidates.is_empty() {
+        let a = candidates.remove(0);
+        let Some(partner) = candidates.iter().position(|c| symplectic_product(&a, c)) else {
+            // Only possible if the stabilizers do not commute with `ops`
+            continue;
+        };
+        let b = candidates.remove(partner);
+        for c in candidates.iter_mut() {
+            if symplectic_product(c, &b) {
+                xor_into(c, &a);
+            }
+            if symplectic_product(c, &a) {
+                xor_into(c, &b);
+            }
+        }
+        firsts.push(from_bits(&a));
+        partners.push(from_bits(&b));
+    }
+
+    (firsts, partners)
+}
+
+/// Greedily multiply by stabilizers while that lowers the weight
+pub fn reduce_weight(mut op: PauliString, stabilizers: &[PauliString]) -> PauliString {
+    loop {
+        let improved = stabilizers
+            .iter()
+            .map(|s| from_bits(&xor(&to_bits(&op), &to_bits(s))))
+            .find(|candidate| candidate.weight() < op.weight());
+        match improved {
+            Some(candidate) => op = candidate,
+            None => return op,
+        }
+    }
+}
+
+fn xor(a: &[bool], b: &[bool]) -> Vec<bool> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_roundtrip() {
+        let p = PauliString::from_str("IXYZ").unwrap();
+        let bits = to_bits(&p);
+        assert_eq!(
+            bits,
+            vec![false, true, true, false, false, false, true, true]
+        );
+        assert_eq!(from_bits(&bits), p);
+    }
+
+    #[test]
+    fn test_rank_and_decompose() {
+        let ops = vec![
+            PauliString::from_str("XXI").unwrap(),
+            PauliString::from_str("IXX").unwrap(),
+            PauliString::from_str("XIX").unwrap(),
+        ];
+        assert_eq!(rank(&ops), 2);
+        assert_eq!(independent_subset(&ops).len(), 2);
+
+        let target = PauliString::from_str("XIX").unwrap();
+        let combo = decompose(&ops[..2], &target).unwrap();
+        assert_eq!(combo, vec![0, 1]);
+        assert!(!is_in_span(&ops, &PauliString::from_str("ZII").unwrap()));
+    }
+
+    #[test]
+    fn test_symplectic_product() {
+        let x = to_bits(&PauliString::from_str("XI").unwrap());
+        let z = to_bits(&PauliString::from_str("ZI").unwrap());
+        let zz = to_bits(&PauliString::from_str("ZZ").unwrap());
+        let xx = to_bits(&PauliString::from_str("XX").unwrap());
+        assert!(symplectic_product(&x, &z));
+        assert!(!symplectic_product(&xx, &zz));
+    }
+
+    #[test]
+    fn test_logical_basis() {
+        let stabs = vec![
+            PauliString::from_str("XZZXI").unwrap(),
+            PauliString::from_str("IXZZX").unwrap(),
+            PauliString::from_str("XIXZZ").unwrap(),
+            PauliString::from_str("ZXIXZ").unwrap(),
+        ];
+        let (lx, lz) = logical_basis(&stabs, 5);
+        assert_eq!(lx.len(), 1);
+        assert_eq!(lz.len(), 1);
+        for s in &stabs {
+            assert!(s.commutes_with(&lx[0]));
+            assert!(s.commutes_with(&lz[0]));
+        }
+        assert!(!lx[0].commutes_with(&lz[0]));
+        assert!(!is_in_span(&stabs, &lx[0]));
+    }
+
+    #[test]
+    fn test_css_logical_basis_is_typed() {
+        let stabs = vec![
+            PauliString::from_str("ZZIIII").unwrap(),
+            PauliString::from_str("IZZIII").unwrap(),
+            PauliString::from_str("IIIZZI").unwrap(),
+            PauliString::from_str("IIIIZZ").unwrap(),
+        ];
+        let (lx, lz) = logical_basis(&stabs, 6);
+        assert_eq!(lx.len(), 2);
+        for i in 0..2 {
+            assert!(lx[i].paulis.iter().all(|&p| p == Pauli::I || p == Pauli::X));
+            assert!(lz[i].paulis.iter().all(|&p| p == Pauli::I || p == Pauli::Z));
+            assert_eq!(lz[i].weight(), 1);
+            for j in 0..2 {
+                assert_eq!(lx[i].commutes_with(&lz[j]), i != j);
+                assert!(lx[i].commutes_with(&lx[j]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_destabilizers() {
+        let stabs: Vec<PauliString> = ["XZZXI", "IXZZX", "XIXZZ", "ZXIXZ"]
+            .iter()
+            .map(|s| PauliString::from_str(s).unwrap())
+            .collect();
+        let logicals: Vec<PauliString> = ["XXXXX", "ZZZZZ"]
+            .iter()
+            .map(|s| PauliString::from_str(s).unwrap())
+            .collect();
+        let destabs = destabilizers(&stabs, &logicals);
+        for (i, d) in destabs.iter().enumerate() {
+            for (j, s) in stabs.iter().enumerate() {
+                assert_eq!(d.commutes_with(s), i != j);
+            }
+            assert!(logicals.iter().all(|l| l.commutes_with(d)));
+            assert!(destabs.iter().all(|other| other.commutes_with(d)));
+        }
+        assert_eq!(
+            solve(&[vec![true, true], vec![true, true]], &[true, false]),
+            None
+        );
+    }
+}
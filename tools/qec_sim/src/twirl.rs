@@ -0,0 +1,447 @@
+// Pauli twirling of general quantum channels
+//
+// Twirling a channel with Kraus operators K_i over the n-qubit Pauli group
+// leaves the Pauli channel whose probabilities are the diagonal of the
+// process (chi) matrix:
+//   p(P) = sum_i |Tr(P K_i)|^2 / 4^n
+// which is what the stabilizer simulator can sample.
+use crate::error::{NoiseError, PauliChannel, TwoQubitChannel};
+use crate::pauli::Pauli;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+
+    fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+/// Entry (row, col) of a single-qubit Pauli matrix
+fn pauli_entry(pauli: Pauli, row: usize, col: usize) -> Complex {
+    match (pauli, row, col) {
+        (Pauli::I, r, c) if r == c => Complex::new(1.0, 0.0),
+        (Pauli::X, r, c) if r != c => Complex::new(1.0, 0.0),
+        (Pauli::Y, 0, 1) => Complex::new(0.0, -1.0),
+        (Pauli::Y, 1, 0) => Complex::new(0.0, 1.0),
+        (Pauli::Z, 0, 0) => Complex::new(1.0, 0.0),
+        (Pauli::Z, 1, 1) => Complex::new(-1.0, 0.0),
+        _ => Complex::ZERO,
+    }
+}
+
+/// Channel on `n_qubits` qubits given by Kraus operators
+/// Each operator is a 2^n x 2^n matrix of [re, im] entries in row-major
+/// order, with qubit 0 as the most significant bit of the basis index.
+/// Channels are validated on construction, including deserialization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "KrausOperators")]
+pub struct KrausChannel {
+    n_qubits: usize,
+    operators: Vec<Vec<Vec<[f64; 2]>>>,
+}
+
+/// Unchecked fields of a deserialized `KrausChannel`
+#[derive(Deserialize)]
+struct KrausOperators {
+    n_qubits: usize,
+    operators: Vec<Vec<Vec<[f64; 2]>>>,
+}
+
+impl TryFrom<KrausOperators> for KrausChannel {
+    type Error = NoiseError;
+
+    fn try_from(raw: KrausOperators) -> Result<Self, NoiseError> {
+        KrausChannel::new(raw.n_qubits, raw.operators)
+    }
+}
+
+impl KrausChannel {
+    /// Check shapes and trace preservation: sum_i K_i† K_i = I
+    pub fn new(n_qubits: usize, operators: Vec<Vec<Vec<[f64; 2]>>>) -> Result<Self, NoiseError> {
+        let channel = KrausChannel {
+            n_qubits,
+            operators,
+        };
+        channel.validate()?;
+        Ok(channel)
+    }
+
+    pub fn n_qubits(&self) -> usize {
+        self.n_qubits
+    }
+
+    fn dim(&self) -> usize {
+        1 << self.n_qubits
+    }
+
+    fn entry(&self, op: usize, row: usize, col: usize) -> Complex {
+        let [re, im] = self.operators[op][row][col];
+        Complex::new(re, im)
+    }
+
+    fn validate(&self) -> Result<(), NoiseError> {
+        if self.n_qubits > MAX_QUBITS {
+            return Err(NoiseError::invalid(
+                "kraus",
+                "n_qubits",
+                format!("at most {} qubits", MAX_QUBITS),
+            ));
+        }
+        let dim = self.dim();
+        let square = self
+            .operators
+            .iter()
+            .all(|op| op.len() == dim && op.iter().all(|row| row.len() == dim));
+        if self.operators.is_empty() || !square {
+            return Err(NoiseError::invalid(
+                "kraus",
+                "operators",
+                "operators must be 2^n x 2^n matrices",
+            ));
+        }
+        for row in 0..dim {
+            for col in 0..dim {
+                let mut sum = Complex::ZERO;
+                for op in 0..self.operators.len() {
+                    for k in 0..dim {
+                        sum = sum + self.entry(op, k, row).conj() * self.entry(op, k, col);
+                    }
+                }
+                let expected = if row == col { 1.0 } else { 0.0 };
+                if (sum.re - expected).abs() > 1e-9 || sum.im.abs() > 1e-9 {
+                    return Err(NoiseError::invalid(
+                        "kraus",
+                        "operators",
+                        "channel is not trace preserving",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply `self` first and then `other`: Kraus operators L_j K_i
+    pub fn then(&self, other: &KrausChannel) -> Result<KrausChannel, NoiseError> {
+        if other.n_qubits != self.n_qubits {
+            return Err(NoiseError::QubitCountMismatch {
+                expected: self.n_qubits,
+                found: other.n_qubits,
+            });
+        }
+        let dim = self.dim();
+        let mut operators = Vec::new();
+        for j in 0..other.operators.len() {
+            for i in 0..self.operators.len() {
+                let product = (0..dim)
+                    .map(|r| {
+                        (0..dim)
+                            .map(|c| {
+                                let sum = (0..dim).fold(Complex::ZERO, |acc, k| {
+                                    acc + other.entry(j, r, k) * self.entry(i, k, c)
+                                });
+                                [sum.re, sum.im]
+                            })
+                            .collect()
+                    })
+                    .collect();
+                operators.push(product);
+            }
+        }
+        Ok(KrausChannel {
+            n_qubits: self.n_qubits,
+            operators,
+        })
+    }
+
+    /// Probability of each n-qubit Pauli after twirling, indexed in base 4
+    /// with qubit 0 most significant and digits I, X, Y, Z
+    pub fn pauli_probabilities(&self) -> Vec<f64> {
+        let dim = self.dim();
+        let n_paulis = dim * dim;
+        (0..n_paulis)
+            .map(|index| {
+                let paulis: Vec<Pauli> = (0..self.n_qubits)
+                    .map(|q| Pauli::ALL[(index >> (2 * (self.n_qubits - 1 - q))) & 3])
+                    .collect();
+                // Entry (row, col) of the tensor product
+                let entry = |row: usize, col: usize| {
+                    paulis
+                        .iter()
+                        .enumerate()
+                        .fold(Complex::new(1.0, 0.0), |acc, (q, &p)| {
+                            let shift = self.n_qubits - 1 - q;
+                            acc * pauli_entry(p, (row >> shift) & 1, (col >> shift) & 1)
+                        })
+                };
+                (0..self.operators.len())
+                    .map(|op| {
+                        let mut trace = Complex::ZERO;
+                        for r in 0..dim {
+                            for c in 0..dim {
+                                trace = trace + entry(r, c) * self.entry(op, c, r);
+                            }
+                        }
+                        trace.norm_sqr()
+                    })
+                    .sum::<f64>()
+                    / n_paulis as f64
+            })
+            .collect()
+    }
+
+    /// Twirled single-qubit channel
+    pub fn pauli_channel(&self) -> Result<PauliChannel, NoiseError> {
+        if self.n_qubits != 1 {
+            return Err(NoiseError::invalid(
+                "kraus",
+                "n_qubits",
+                "expected a single-qubit channel",
+            ));
+        }
+        let p = self.pauli_probabilities();
+        Ok(PauliChannel::Pauli {
+            px: p[1],
+            py: p[2],
+            pz: p[3],
+        })
+    }
+
+    /// Twirled two-qubit channel as a probability table
+    pub fn two_qubit_channel(&self) -> Result<TwoQubitChannel, NoiseError> {
+        if self.n_qubits != 2 {
+            return Err(NoiseError::invalid(
+                "kraus",
+                "n_qubits",
+                "expected a two-qubit channel",
+            ));
+        }
+        let probabilities = self
+            .pauli_probabilities()
+            .into_iter()
+            .enumerate()
+            .skip(1)
+            .filter(|&(_, p)| p > 1e-15)
+            .map(|(i, p)| {
+                let label = format!(
+                    "{}{}",
+                    Pauli::ALL[i >> 2].to_char(),
+                    Pauli::ALL[i & 3].to_char()
+                );
+                (label, p)
+            })
+            .collect();
+        Ok(TwoQubitChannel::Table { probabilities })
+    }
+}
+
+/// Largest channel accepted, whose twirl sums over 4^n Paulis
+const MAX_QUBITS: usize = 4;
+
+fn real(values: [[f64; 2]; 2]) -> Vec<Vec<[f64; 2]>> {
+    values
+        .iter()
+        .map(|row| row.iter().map(|&v| [v, 0.0]).collect())
+        .collect()
+}
+
+fn check_probability(model: &str, parameter: &str, value: f64) -> Result<(), NoiseError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(NoiseError::invalid(model, parameter, "must lie in [0, 1]"))
+    }
+}
+
+/// Amplitude damping |1⟩ -> |0⟩ with probability `gamma`
+pub fn amplitude_damping(gamma: f64) -> Result<KrausChannel, NoiseError> {
+    check_probability("amplitude_damping", "gamma", gamma)?;
+    Ok(KrausChannel {
+        n_qubits: 1,
+        operators: vec![
+            real([[1.0, 0.0], [0.0, (1.0 - gamma).sqrt()]]),
+            real([[0.0, gamma.sqrt()], [0.0, 0.0]]),
+        ],
+    })
+}
+
+/// Phase damping: coherences shrink by sqrt(1 - lambda)
+pub fn phase_damping(lambda: f64) -> Result<KrausChannel, NoiseError> {
+    check_probability("phase_damping", "lambda", lambda)?;
+    Ok(KrausChannel {
+        n_qubits: 1,
+        operators: vec![
+            real([[1.0, 0.0], [0.0, (1.0 - lambda).sqrt()]]),
+            real([[0.0, 0.0], [0.0, lambda.sqrt()]]),
+        ],
+    })
+}
+
+/// Relaxation and dephasing over `duration_ns` for T1 and T2 in microseconds:
+/// amplitude damping with gamma = 1 - e^(-t/T1) followed by the pure
+/// dephasing that brings coherences down to e^(-t/T2)
+pub fn thermal_relaxation(
+    duration_ns: f64,
+    t1_us: f64,
+    t2_us: f64,
+) -> Result<KrausChannel, NoiseError> {
+    if !(t1_us > 0.0 && t2_us > 0.0 && t2_us <= 2.0 * t1_us && duration_ns >= 0.0) {
+        return Err(NoiseError::invalid(
+            "thermal_relaxation",
+            "t2_us",
+            "need positive times with T2 <= 2 T1",
+        ));
+    }
+    let t = duration_ns / 1000.0;
+    let gamma = 1.0 - (-t / t1_us).exp();
+    // Pure dephasing rate 1/T_phi = 1/T2 - 1/(2 T1)
+    let lambda = 1.0 - (-2.0 * t * (1.0 / t2_us - 0.5 / t1_us)).exp();
+    amplitude_damping(gamma)?.then(&phase_damping(lambda)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn test_amplitude_and_phase_damping() {
+        let gamma = 0.3;
+        let p = amplitude_damping(gamma).unwrap().pauli_probabilities();
+        assert!(close(p[1], gamma / 4.0) && close(p[2], gamma / 4.0));
+        assert!(close(p[3], 0.5 - gamma / 4.0 - (1.0 - gamma).sqrt() / 2.0));
+        assert!(close(p.iter().sum(), 1.0));
+
+        let lambda = 0.2;
+        let channel = phase_damping(lambda).unwrap().pauli_channel().unwrap();
+        let [px, py, pz] = channel.probabilities();
+        assert!(close(px, 0.0) && close(py, 0.0));
+        assert!(close(pz, (1.0 - (1.0 - lambda).sqrt()) / 2.0));
+
+        assert!(amplitude_damping(1.2).is_err());
+        assert!(phase_damping(-0.1).is_err());
+        assert!(phase_damping(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_thermal_relaxation_matches_t1_t2_formula() {
+        let (t, t1, t2) = (500.0, 40.0, 30.0);
+        let [px, py, pz] = thermal_relaxation(t, t1, t2)
+            .unwrap()
+            .pauli_channel()
+            .unwrap()
+            .probabilities();
+        let relax = 1.0 - (-0.5f64 / t1).exp();
+        let dephase = 1.0 - (-0.5f64 / t2).exp();
+        assert!(close(px, relax / 4.0) && close(py, relax / 4.0));
+        assert!(close(pz, dephase / 2.0 - relax / 4.0));
+        assert!(thermal_relaxation(t, 10.0, 30.0).is_err());
+    }
+
+    #[test]
+    fn test_general_kraus_channels() {
+        // A coherent X rotation by theta twirls to X flips with sin^2(theta/2)
+        let theta: f64 = 0.4;
+        let (c, s) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+        let rotation = KrausChannel::new(
+            1,
+            vec![vec![vec![[c, 0.0], [0.0, -s]], vec![[0.0, -s], [c, 0.0]]]],
+        )
+        .unwrap();
+        let [px, _, _] = rotation.pauli_channel().unwrap().probabilities();
+        assert!(close(px, s * s));
+
+        // Missing Kraus weight is rejected
+        let lossy = KrausChannel::new(1, vec![real([[0.5, 0.0], [0.0, 0.5]])]);
+        assert!(lossy.is_err());
+        assert!(KrausChannel::new(70, vec![]).is_err());
+
+        // Channels on different qubit counts do not compose
+        let two = KrausChannel::new(2, vec![real4_identity()]).unwrap();
+        assert_eq!(
+            rotation.then(&two),
+            Err(NoiseError::QubitCountMismatch {
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+
+    fn real4_identity() -> Vec<Vec<[f64; 2]>> {
+        (0..4)
+            .map(|r| {
+                (0..4)
+                    .map(|c| [if r == c { 1.0 } else { 0.0 }, 0.0])
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_deserialization_validates() {
+        let channel = amplitude_damping(0.3).unwrap();
+        let json = serde_json::to_string(&channel).unwrap();
+        let parsed: KrausChannel = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, channel);
+
+        let lossy = r#"{"n_qubits": 1, "operators": [[[[0.5, 0], [0, 0]], [[0, 0], [0.5, 0]]]]}"#;
+        assert!(serde_json::from_str::<KrausChannel>(lossy).is_err());
+        let misshapen = r#"{"n_qubits": 2, "operators": [[[[1, 0], [0, 0]], [[0, 0], [1, 0]]]]}"#;
+        assert!(serde_json::from_str::<KrausChannel>(misshapen).is_err());
+    }
+
+    #[test]
+    fn test_two_qubit_twirl() {
+        // exp(-i theta ZZ / 2) is diagonal with phases e^(∓i theta/2)
+        let theta: f64 = 0.3;
+        let phase = |sign: f64| [(theta / 2.0).cos(), -sign * (theta / 2.0).sin()];
+        let mut op = vec![vec![[0.0, 0.0]; 4]; 4];
+        for (i, row) in op.iter_mut().enumerate() {
+            let parity = ((i >> 1) ^ i) & 1;
+            row[i] = phase(if parity == 0 { 1.0 } else { -1.0 });
+        }
+        let crosstalk = KrausChannel::new(2, vec![op]).unwrap();
+        let TwoQubitChannel::Table { probabilities } = crosstalk.two_qubit_channel().unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(probabilities.len(), 1);
+        assert!(close(probabilities["ZZ"], (theta / 2.0).sin().powi(2)));
+        assert!(crosstalk.pauli_channel().is_err());
+    }
+}
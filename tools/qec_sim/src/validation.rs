@@ -0,0 +1,480 @@
+#![allow(dead_code)]
+use crate::codes::{CodeInfo, StabilizerCode};
+use crate::distance::{self, DistanceCheck};
+use crate::pauli::{Pauli, PauliString, Phase};
+use crate::symplectic;
+use serde::Serialize;
+use std::fmt;
+
+/// Reasons a code description can be rejected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum CodeError {
+    /// A name that no constructor knows about
+    UnknownCode(String),
+    /// A Pauli string that could not be parsed
+    InvalidPauliString(String),
+    /// An operator acting on the wrong number of qubits
+    QubitCountMismatch {
+        operator: String,
+        expected: usize,
+        found: usize,
+    },
+    /// A generator or logical operator with phase ±i
+    NonHermitian { operator: String },
+    /// Two stabilizer generators that anticommute
+    NonCommutingStabilizers { first: usize, second: usize },
+    /// A generator that is a product of earlier generators
+    DependentGenerator { index: usize },
+    /// Number of logical X/Z operators does not match k = n - rank(S)
+    LogicalCountMismatch {
+        n_logical: usize,
+        logical_x: usize,
+        logical_z: usize,
+    },
+    /// A logical operator that anticommutes with a stabilizer
+    LogicalNotInNormalizer { logical: String, stabilizer: usize },
+    /// Logical operators that do not form a symplectic basis
+    LogicalPairing { first: String, second: String },
+    /// A parameter that differs from the one claimed in `CodeInfo`
+    InfoMismatch {
+        field: String,
+        claimed: usize,
+        computed: usize,
+    },
+    /// A code family parameter that is missing, unknown or out of range
+    InvalidParameter {
+        code: String,
+        parameter: String,
+        reason: String,
+    },
+    /// A code file that could not be read or parsed
+    InvalidFile { format: String, reason: String },
+    /// Outer code qubits cannot be split into blocks of the inner code
+    IncompatibleConcatenation {
+        outer_qubits: usize,
+        inner_logical: usize,
+    },
+}
+
+impl CodeError {
+    /// `InvalidParameter` error for `parameter` of `code`
+    pub fn invalid_parameter(
+        code: impl Into<String>,
+        parameter: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        CodeError::InvalidParameter {
+            code: code.into(),
+            parameter: parameter.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeError::UnknownCode(name) => write!(f, "Unknown code: {}", name),
+            CodeError::InvalidPauliString(s) => write!(f, "Invalid Pauli string: {}", s),
+            CodeError::QubitCountMismatch {
+                operator,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} acts on {} qubits, expected {}",
+                operator, found, expected
+            ),
+            CodeError::NonHermitian { operator } => {
+                write!(f, "{} has an imaginary phase", operator)
+            }
+            CodeError::NonCommutingStabilizers { first, second } => {
+                write!(f, "Stabilizers {} and {} do not commute", first, second)
+            }
+            CodeError::DependentGenerator { index } => write!(
+                f,
+                "Stabilizer {} is a product of the preceding generators",
+                index
+            ),
+            CodeError::LogicalCountMismatch {
+                n_logical,
+                logical_x,
+                logical_z,
+            } => write!(
+                f,
+                "Code encodes {} logical qubits but has {} logical X and {} logical Z operators",
+                n_logical, logical_x, logical_z
+            ),
+            CodeError::LogicalNotInNormalizer {
+                logical,
+                stabilizer,
+            } => write!(
+                f,
+                "Logical operator {} anticommutes with stabilizer {}",
+                logical, stabilizer
+            ),
+            CodeError::LogicalPairing { first, second } => write!(
+                f,
+                "Logical operators {} and {} have the wrong commutation relation",
+                first, second
+            ),
+            CodeError::InfoMismatch {
+                field,
+                claimed,
+                computed,
+            } => write!(
+                f,
+                "Claimed {} = {} but computed {}",
+                field, claimed, computed
+            ),
+            CodeError::InvalidParameter {
+                code,
+                parameter,
+                reason,
+            } => write!(
+                f,
+                "Invalid parameter {} for {}: {}",
+                parameter, code, reason
+            ),
+            CodeError::InvalidFile { format, reason } => {
+                write!(f, "Invalid {} file: {}", format, reason)
+            }
+            CodeError::IncompatibleConcatenation {
+                outer_qubits,
+                inner_logical,
+            } => write!(
+                f,
+                "Cannot split {} outer qubits into inner blocks of {} logical qubits",
+                outer_qubits, inner_logical
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+/// Check the algebraic structure of a code
+/// - every operator acts on n qubits and has a real phase
+/// - stabilizer generators commute and are independent
+/// - there are k = n - rank(S) logical X and Z operators
+/// - logical operators commute with every stabilizer
+/// - X̄_i and Z̄_j anticommute iff i == j, all other pairs commute
+pub fn validate_code(code: &StabilizerCode) -> Result<(), CodeError> {
+    let n = code.n_qubits;
+    validate_stabilizers(n, &code.generators)?;
+    let labeled = code
+        .logical_x
+        .iter()
+        .enumerate()
+        .map(|(i, l)| (format!("X{}", i), l))
+        .chain(
+            code.logical_z
+                .iter()
+                .enumerate()
+                .map(|(i, l)| (format!("Z{}", i), l)),
+        );
+    for (label, op) in labeled {
+        check_operator(label, op, n)?;
+    }
+
+    let k = code.n_logical();
+    if code.logical_x.len() != k || code.logical_z.len() != k {
+        return Err(CodeError::LogicalCountMismatch {
+            n_logical: k,
+            logical_x: code.logical_x.len(),
+            logical_z: code.logical_z.len(),
+        });
+    }
+
+    let logicals: Vec<(String, &PauliString)> = code
+        .logical_x
+        .iter()
+        .enumerate()
+        .map(|(i, l)| (format!("X{}", i), l))
+        .chain(
+            code.logical_z
+                .iter()
+                .enumerate()
+                .map(|(i, l)| (format!("Z{}", i), l)),
+        )
+        .collect();
+
+    for (label, logical) in &logicals {
+        if let Some(s) = code
+            .generators
+            .iter()
+            .position(|g| !g.commutes_with(logical))
+        {
+            return Err(CodeError::LogicalNotInNormalizer {
+                logical: label.clone(),
+                stabilizer: s,
+            });
+        }
+    }
+
+    // X_i is entry i, Z_i is entry k + i; only X_i / Z_i pairs anticommute
+    for (a, (label_a, op_a)) in logicals.iter().enumerate() {
+        for (b, (label_b, op_b)) in logicals.iter().enumerate().skip(a + 1) {
+            let should_anticommute = b == a + k;
+            if op_a.commutes_with(op_b) == should_anticommute {
+                return Err(CodeError::LogicalPairing {
+                    first: label_a.clone(),
+                    second: label_b.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a list of stabilizer generators on `n_qubits` qubits: each has a
+/// real phase, and they commute and are independent
+pub fn validate_stabilizers(n_qubits: usize, generators: &[PauliString]) -> Result<(), CodeError> {
+    for (i, g) in generators.iter().enumerate() {
+        check_operator(format!("S{}", i), g, n_qubits)?;
+    }
+    for (i, a) in generators.iter().enumerate() {
+        for (j, b) in generators.iter().enumerate().skip(i + 1) {
+            if !a.commutes_with(b) {
+                return Err(CodeError::NonCommutingStabilizers {
+                    first: i,
+                    second: j,
+                });
+            }
+        }
+    }
+    if let Some(index) = symplectic::first_dependent(generators) {
+        return Err(CodeError::DependentGenerator { index });
+    }
+    Ok(())
+}
+
+fn check_operator(label: String, op: &PauliString, n_qubits: usize) -> Result<(), CodeError> {
+    if op.n_qubits() != n_qubits {
+        return Err(CodeError::QubitCountMismatch {
+            operator: label,
+            expected: n_qubits,
+            found: op.n_qubits(),
+        });
+    }
+    if matches!(op.phase, Phase::PlusI | Phase::MinusI) {
+        return Err(CodeError::NonHermitian { operator: label });
+    }
+    Ok(())
+}
+
+/// Check that n, k and d claimed in `info` match the code
+/// Claimed X and Z distances must not exceed the weight of a logical
+/// representative of that type, d must be the smaller of the two, and no
+/// lighter logical operator may turn up in an exhaustive search where that
+/// search fits the budget.
+pub fn validate_info(code: &StabilizerCode, info: &CodeInfo) -> Result<(), CodeError> {
+    let mismatch = |field: &str, claimed, computed| CodeError::InfoMismatch {
+        field: field.to_string(),
+        claimed,
+        computed,
+    };
+    let checks = [
+        ("n_qubits", info.n_qubits, code.n_qubits),
+        ("n_logical", info.n_logical, code.n_logical()),
+        ("distance", info.distance, code.distance),
+    ];
+    for (field, claimed, computed) in checks {
+        if claimed != computed {
+            return Err(mismatch(field, claimed, computed));
+        }
+    }
+
+    if let (Some(x), Some(z)) = (info.x_distance, info.z_distance) {
+        if info.distance != x.min(z) {
+            return Err(mismatch("distance", info.distance, x.min(z)));
+        }
+    }
+    let typed = [
+        ("x_distance", info.x_distance, &code.logical_x, Pauli::X),
+        ("z_distance", info.z_distance, &code.logical_z, Pauli::Z),
+    ];
+    for (field, claimed, representatives, pauli) in typed {
+        let Some(claimed) = claimed else { continue };
+        let lightest = representatives
+            .iter()
+            .filter(|op| op.paulis.iter().all(|&p| p == Pauli::I || p == pauli))
+            .map(PauliString::weight)
+            .min();
+        let lighter =
+            lightest.filter(|&w| w < claimed).or_else(|| {
+                match distance::lighter_logical(code, &[pauli], claimed) {
+                    DistanceCheck::Lighter(w) => Some(w),
+                    DistanceCheck::Confirmed | DistanceCheck::Skipped => None,
+                }
+            });
+        if let Some(computed) = lighter {
+            return Err(mismatch(field, claimed, computed));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{available_codes, get_stabilizer_code, surface_code_d3};
+
+    fn paulis(strings: &[&str]) -> Vec<PauliString> {
+        strings
+            .iter()
+            .map(|s| PauliString::from_str(s).unwrap())
+            .collect()
+    }
+
+    fn unchecked(generators: &[&str], lx: &[&str], lz: &[&str]) -> StabilizerCode {
+        StabilizerCode {
+            n_qubits: generators.first().map_or(0, |g| g.len()),
+            generators: paulis(generators),
+            logical_x: paulis(lx),
+            logical_z: paulis(lz),
+            distance: 0,
+        }
+    }
+
+    #[test]
+    fn test_builtin_codes_are_valid() {
+        for name in available_codes() {
+            let code = get_stabilizer_code(&name).unwrap();
+            assert_eq!(validate_code(&code), Ok(()));
+        }
+        assert!(validate_code(&surface_code_d3().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_old_surface_code_generators() {
+        // The earlier surface_code_d3 list mixed up plaquette supports
+        let code = unchecked(
+            &[
+                "XXIIIIIII",
+                "IXIIXIIII",
+                "IIIXXXXII",
+                "IIIIIIXIX",
+                "ZIIZIIIII",
+                "IZIZIZIII",
+                "IIZIIIZIZ",
+                "IIIIIIZZI",
+            ],
+            &["XIIXIIXII"],
+            &["ZZZIIIIII"],
+        );
+        assert_eq!(
+            validate_code(&code),
+            Err(CodeError::NonCommutingStabilizers {
+                first: 0,
+                second: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_logicals_and_dependencies() {
+        let dependent = unchecked(&["ZZI", "IZZ", "ZIZ"], &[], &[]);
+        assert_eq!(
+            validate_code(&dependent),
+            Err(CodeError::DependentGenerator { index: 2 })
+        );
+
+        let wrong_logical = unchecked(&["ZZI", "IZZ"], &["XII"], &["ZZZ"]);
+        assert_eq!(
+            validate_code(&wrong_logical),
+            Err(CodeError::LogicalNotInNormalizer {
+                logical: "X0".to_string(),
+                stabilizer: 0
+            })
+        );
+
+        let commuting_pair = unchecked(&["ZZI", "IZZ"], &["XXX"], &["III"]);
+        assert!(matches!(
+            validate_code(&commuting_pair),
+            Err(CodeError::LogicalPairing { .. })
+        ));
+
+        let missing = unchecked(&["ZZI", "IZZ"], &[], &[]);
+        assert!(matches!(
+            validate_code(&missing),
+            Err(CodeError::LogicalCountMismatch { n_logical: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_info_mismatch() {
+        let code = get_stabilizer_code("repetition_3").unwrap();
+        let info = CodeInfo {
+            name: String::new(),
+            description: String::new(),
+            n_qubits: 3,
+            n_logical: 1,
+            distance: 3,
+            x_distance: None,
+            z_distance: None,
+            distance_exact: true,
+            logical_gates: None,
+        };
+        assert_eq!(
+            validate_info(&code, &info),
+            Err(CodeError::InfoMismatch {
+                field: "distance".to_string(),
+                claimed: 3,
+                computed: 1
+            })
+        );
+
+        let correct = CodeInfo {
+            distance: 1,
+            x_distance: Some(3),
+            z_distance: Some(1),
+            ..info.clone()
+        };
+        assert_eq!(validate_info(&code, &correct), Ok(()));
+
+        // The Z̄ = ZZZ representative caps nothing, but a single Z is logical
+        let swapped = CodeInfo {
+            x_distance: Some(1),
+            z_distance: Some(3),
+            ..correct.clone()
+        };
+        assert_eq!(
+            validate_info(&code, &swapped),
+            Err(CodeError::InfoMismatch {
+                field: "z_distance".to_string(),
+                claimed: 3,
+                computed: 1
+            })
+        );
+
+        // X̄ = XXX caps the X distance at 3
+        let too_large = CodeInfo {
+            x_distance: Some(4),
+            ..correct.clone()
+        };
+        assert_eq!(
+            validate_info(&code, &too_large),
+            Err(CodeError::InfoMismatch {
+                field: "x_distance".to_string(),
+                claimed: 4,
+                computed: 3
+            })
+        );
+
+        let inconsistent = CodeInfo {
+            x_distance: Some(2),
+            z_distance: Some(2),
+            ..correct
+        };
+        assert_eq!(
+            validate_info(&code, &inconsistent),
+            Err(CodeError::InfoMismatch {
+                field: "distance".to_string(),
+                claimed: 1,
+                computed: 2
+            })
+        );
+    }
+}